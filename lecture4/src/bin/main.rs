@@ -5,7 +5,9 @@ use lecture4_lib::paths;
 use lecture4_lib::modC::modC_helper;
 use lecture4_lib::modC;
 
-use paths::shapes::new_rect;
+use paths::shapes::rectangles::Rect;
+use paths::shapes::solids::{Cuboid, Solid};
+use paths::shapes::{csv, new_circle, new_rect, total_area, Registry, Shape, ShapeCollection};
 
 use std::{collections::HashMap, io::BufRead};
 
@@ -21,6 +23,67 @@ fn main() {
     let rect1 = new_rect(1, 2);
     rect.get_area();
     rect1.get_area();
+
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(rect),
+        Box::new(rect1),
+        Box::new(new_circle(1.0).unwrap()),
+    ];
+    println!("Total area: {}", total_area(&shapes));
+
+    let collection: ShapeCollection = shapes.into_iter().collect();
+    println!(
+        "{} shapes, total area {}, total perimeter {}",
+        collection.len(),
+        collection.total_area(),
+        collection.total_perimeter()
+    );
+    if let Some(largest) = collection.largest() {
+        println!("Largest is a {} with area {}", largest.name(), largest.area());
+    }
+    for (name, count) in collection.count_by_name() {
+        println!("  {}: {}", name, count);
+    }
+
+    let cuboid = Cuboid::from((new_rect(5, 6), 7));
+    println!(
+        "Extruding 5x6 by 7 gives a {} with volume {} and surface area {}",
+        cuboid.name(),
+        cuboid.get_volume(),
+        cuboid.get_surface_area()
+    );
+
+    if let Some(spec) = std::env::args().nth(1) {
+        match spec.parse::<Rect>() {
+            Ok(r) => println!("{} has area {}", r, r.get_area()),
+            Err(e) => println!("Cannot parse {:?}: {}", spec, e),
+        }
+    }
+
+    // Each line of the shapes file is a spec such as `rect 5 6` or `square 3`.
+    if let Some(shapes_path) = std::env::args().nth(2) {
+        let registry = Registry::new();
+        let f = std::fs::File::open(&shapes_path).unwrap();
+        for line in std::io::BufReader::new(f).lines() {
+            let line = line.unwrap();
+            if line.trim().is_empty() {
+                continue;
+            }
+            match registry.create_from_str(&line) {
+                Ok(shape) => println!("{:?} has area {}", shape, shape.area()),
+                Err(e) => println!("Cannot create {:?}: {}", line, e),
+            }
+        }
+    }
+
+    if let Some(rects_path) = std::env::args().nth(3) {
+        let f = std::fs::File::open(&rects_path).unwrap();
+        match csv::load_rects(std::io::BufReader::new(f)) {
+            Ok(rects) => println!("Loaded {} rectangles from {}", rects.len(), rects_path),
+            Err(e) => println!("Cannot load {}: {}", rects_path, e),
+        }
+    }
+
     let mut map = HashMap::new();
     map.insert(1, 1);
 
@@ -1,24 +1,4 @@
-pub mod shapes {
-    pub fn new_rect(width: u32, height: u32) -> rectangles::Rect {
-            rectangles::Rect {
-                    width,
-                    height
-            }
-    }
-
-    pub mod rectangles {
-            pub struct Rect {
-                    pub width: u32,
-                    pub height: u32
-            }
-
-            impl Rect {
-                    pub fn get_area(&self) -> u32 {self.width * self.height }
-                    pub fn get_perimeter(&self) -> u32 {self.width * 2 + self.height * 2 }
-            }
-
-    }
-}
+pub mod shapes;
 
 pub fn create_rectangle_v1() {
         shapes::rectangles::Rect {
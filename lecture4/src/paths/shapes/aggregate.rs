@@ -0,0 +1,175 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+use super::rectangles::Rect;
+
+/// The summed area of a collection of rects, e.g.
+/// `rects.iter().collect::<TotalArea>()`. Each area is widened to `u64`
+/// before adding, and the total saturates at `u64::MAX` rather than
+/// wrapping, which takes at least two near-maximal rects to reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct TotalArea(pub u64);
+
+impl TotalArea {
+    fn add(&mut self, rect: &Rect) {
+        self.0 = self.0.saturating_add(rect.get_area_u64());
+    }
+}
+
+impl<'a> Extend<&'a Rect> for TotalArea {
+    fn extend<I: IntoIterator<Item = &'a Rect>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|rect| self.add(rect));
+    }
+}
+
+impl Extend<Rect> for TotalArea {
+    fn extend<I: IntoIterator<Item = Rect>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|rect| self.add(&rect));
+    }
+}
+
+impl<'a> FromIterator<&'a Rect> for TotalArea {
+    fn from_iter<I: IntoIterator<Item = &'a Rect>>(iter: I) -> TotalArea {
+        let mut total = TotalArea::default();
+        total.extend(iter);
+        total
+    }
+}
+
+impl FromIterator<Rect> for TotalArea {
+    fn from_iter<I: IntoIterator<Item = Rect>>(iter: I) -> TotalArea {
+        let mut total = TotalArea::default();
+        total.extend(iter);
+        total
+    }
+}
+
+impl fmt::Display for TotalArea {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Running mean of the widths and heights of a collection of rects. An
+/// empty collection has no mean, so `width` and `height` return `None`
+/// rather than NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AverageDimensions {
+    count: u64,
+    sum_width: u128,
+    sum_height: u128,
+}
+
+impl AverageDimensions {
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn width(&self) -> Option<f64> {
+        self.mean(self.sum_width)
+    }
+
+    pub fn height(&self) -> Option<f64> {
+        self.mean(self.sum_height)
+    }
+
+    fn mean(&self, sum: u128) -> Option<f64> {
+        if self.count == 0 {
+            None
+        } else {
+            Some(sum as f64 / self.count as f64)
+        }
+    }
+
+    fn add(&mut self, rect: &Rect) {
+        self.count += 1;
+        self.sum_width += rect.width as u128;
+        self.sum_height += rect.height as u128;
+    }
+}
+
+impl<'a> Extend<&'a Rect> for AverageDimensions {
+    fn extend<I: IntoIterator<Item = &'a Rect>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|rect| self.add(rect));
+    }
+}
+
+impl Extend<Rect> for AverageDimensions {
+    fn extend<I: IntoIterator<Item = Rect>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|rect| self.add(&rect));
+    }
+}
+
+impl<'a> FromIterator<&'a Rect> for AverageDimensions {
+    fn from_iter<I: IntoIterator<Item = &'a Rect>>(iter: I) -> AverageDimensions {
+        let mut average = AverageDimensions::default();
+        average.extend(iter);
+        average
+    }
+}
+
+impl FromIterator<Rect> for AverageDimensions {
+    fn from_iter<I: IntoIterator<Item = Rect>>(iter: I) -> AverageDimensions {
+        let mut average = AverageDimensions::default();
+        average.extend(iter);
+        average
+    }
+}
+
+impl fmt::Display for AverageDimensions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.width(), self.height()) {
+            (Some(w), Some(h)) => write!(f, "{}x{}", w, h),
+            _ => write!(f, "no rects"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::testing::RectGen;
+    use super::*;
+
+    #[test]
+    fn empty_iterators() {
+        let none: Vec<Rect> = Vec::new();
+        assert_eq!(none.iter().collect::<TotalArea>(), TotalArea(0));
+        let average: AverageDimensions = none.into_iter().collect();
+        assert_eq!(average.count(), 0);
+        assert_eq!(average.width(), None);
+        assert_eq!(average.height(), None);
+        assert_eq!(average.to_string(), "no rects");
+    }
+
+    #[test]
+    fn totals_and_averages() {
+        let rects = vec![Rect::from((2, 3)), Rect::from((4, 5)), Rect::from((6, 1))];
+        let total: TotalArea = rects.iter().collect();
+        assert_eq!(total, TotalArea(32));
+        assert_eq!(total.to_string(), "32");
+        assert_eq!(rects.clone().into_iter().collect::<TotalArea>(), total);
+
+        let average: AverageDimensions = rects.iter().collect();
+        assert_eq!(average.width(), Some(4.0));
+        assert_eq!(average.height(), Some(3.0));
+        assert_eq!(average.to_string(), "4x3");
+
+        let mut more = total;
+        more.extend(vec![Rect::UNIT]);
+        more.extend(&[Rect::square(2)]);
+        assert_eq!(more, TotalArea(37));
+    }
+
+    #[test]
+    fn large_collections() {
+        let rects = RectGen::new(5).rects(100_000, 60_000, 60_000);
+        let expected: u64 = rects.iter().map(|r| r.get_area_u64()).sum();
+        assert_eq!(rects.iter().collect::<TotalArea>(), TotalArea(expected));
+
+        let max = Rect::from((u32::MAX, u32::MAX));
+        let huge: TotalArea = vec![max; 1000].into_iter().collect();
+        assert_eq!(huge, TotalArea(u64::MAX));
+        let average: AverageDimensions = vec![max; 1000].into_iter().collect();
+        assert_eq!(average.width(), Some(u32::MAX as f64));
+    }
+}
@@ -0,0 +1,148 @@
+use super::rectangles::Rect;
+
+fn lerp_dim(from: u32, to: u32, t: f64) -> u32 {
+    let value = from as f64 + (to as f64 - from as f64) * t;
+    value.round() as u32
+}
+
+/// Interpolates width and height from `from` (at `t = 0`) to `to` (at
+/// `t = 1`), rounding to the nearest unit. `t` is clamped to `[0, 1]` and a
+/// NaN `t` counts as 0. The end points are returned exactly.
+pub fn lerp(from: &Rect, to: &Rect, t: f64) -> Rect {
+    if t.is_nan() || t <= 0.0 {
+        return *from;
+    }
+    if t >= 1.0 {
+        return *to;
+    }
+    Rect {
+        width: lerp_dim(from.width, to.width, t),
+        height: lerp_dim(from.height, to.height, t),
+    }
+}
+
+/// Iterator returned by `Rect::animate_to`.
+#[derive(Debug, Clone)]
+pub struct AnimationIter {
+    from: Rect,
+    to: Rect,
+    steps: usize,
+    next: usize,
+}
+
+impl Iterator for AnimationIter {
+    type Item = Rect;
+
+    fn next(&mut self) -> Option<Rect> {
+        if self.next > self.steps {
+            return None;
+        }
+        let frame = if self.next == self.steps {
+            self.to
+        } else {
+            lerp(&self.from, &self.to, self.next as f64 / self.steps as f64)
+        };
+        self.next += 1;
+        Some(frame)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.next <= self.steps {
+            self.steps - self.next + 1
+        } else {
+            0
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for AnimationIter {}
+
+impl Rect {
+    /// `steps + 1` frames from `self` to `target`, both included. With zero
+    /// steps the only frame is `self`.
+    ///
+    /// # Panics
+    ///
+    /// If `steps` is `usize::MAX`, since the number of frames wouldn't fit
+    /// in a `usize`.
+    pub fn animate_to(&self, target: &Rect, steps: usize) -> AnimationIter {
+        assert!(steps < usize::MAX, "too many animation steps");
+        AnimationIter {
+            from: *self,
+            to: if steps == 0 { *self } else { *target },
+            steps,
+            next: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn lerp_clamps_t() {
+        let (a, b) = (rect(10, 20), rect(20, 0));
+        assert_eq!(lerp(&a, &b, 0.5), rect(15, 10));
+        assert_eq!(lerp(&a, &b, -1.0), a);
+        assert_eq!(lerp(&a, &b, 2.0), b);
+        assert_eq!(lerp(&a, &b, f64::NAN), a);
+        assert_eq!(lerp(&a, &b, 0.25), rect(13, 15));
+    }
+
+    #[test]
+    fn odd_step_counts() {
+        let frames: Vec<_> = rect(0, 0).animate_to(&rect(10, 5), 3).collect();
+        assert_eq!(
+            frames,
+            vec![rect(0, 0), rect(3, 2), rect(7, 3), rect(10, 5)]
+        );
+    }
+
+    #[test]
+    fn shrinking_targets() {
+        let frames: Vec<_> = rect(100, 7).animate_to(&rect(1, 2), 7).collect();
+        assert_eq!(frames.len(), 8);
+        assert_eq!(frames[0], rect(100, 7));
+        assert_eq!(frames[7], rect(1, 2));
+        assert!(frames.windows(2).all(|w| w[1].width <= w[0].width));
+    }
+
+    #[test]
+    fn final_frame_is_exact() {
+        let from = rect(u32::MAX, 1);
+        let to = rect(3, u32::MAX - 1);
+        for steps in [1, 2, 3, 9, 1000].iter() {
+            assert_eq!(from.animate_to(&to, *steps).last(), Some(to));
+        }
+    }
+
+    #[test]
+    fn zero_steps() {
+        let mut iter = rect(4, 4).animate_to(&rect(8, 8), 0);
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(rect(4, 4)));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn most_steps() {
+        let mut iter = rect(0, 0).animate_to(&rect(10, 10), usize::MAX - 1);
+        assert_eq!(iter.len(), usize::MAX);
+        assert_eq!(iter.next(), Some(rect(0, 0)));
+        assert_eq!(iter.next(), Some(rect(0, 0)));
+        assert_eq!(iter.len(), usize::MAX - 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "too many animation steps")]
+    fn max_steps_panics() {
+        rect(0, 0).animate_to(&rect(1, 1), usize::MAX);
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderError {
+    TooLarge { size: Rect, limit: Rect },
+    Fmt,
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenderError::TooLarge { size, limit } => {
+                write!(f, "{} rectangle exceeds the {} render limit", size, limit)
+            }
+            RenderError::Fmt => write!(f, "failed to write rendered rectangle"),
+        }
+    }
+}
+
+impl std::error::Error for RenderError {}
+
+impl From<fmt::Error> for RenderError {
+    fn from(_: fmt::Error) -> RenderError {
+        RenderError::Fmt
+    }
+}
+
+/// Draws rects as boxes of `+`, `-`, `|` and spaces, one character per unit
+/// and one line per row, each line ending in `\n`. A rect with a zero
+/// dimension renders as the empty string. Rects wider or taller
+/// than `limit` are refused rather than rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsciiRenderer {
+    pub limit: Rect,
+}
+
+impl Default for AsciiRenderer {
+    fn default() -> AsciiRenderer {
+        AsciiRenderer {
+            limit: Rect {
+                width: 200,
+                height: 200,
+            },
+        }
+    }
+}
+
+impl AsciiRenderer {
+    pub fn new(max_width: u32, max_height: u32) -> AsciiRenderer {
+        AsciiRenderer {
+            limit: Rect {
+                width: max_width,
+                height: max_height,
+            },
+        }
+    }
+
+    pub fn render(&self, rect: &Rect) -> Result<String, RenderError> {
+        let mut out = String::new();
+        self.render_into(rect, &mut out)?;
+        Ok(out)
+    }
+
+    pub fn render_into(&self, rect: &Rect, w: &mut impl fmt::Write) -> Result<(), RenderError> {
+        if rect.width > self.limit.width || rect.height > self.limit.height {
+            return Err(RenderError::TooLarge {
+                size: *rect,
+                limit: self.limit,
+            });
+        }
+        if rect.width == 0 {
+            return Ok(());
+        }
+        for y in 0..rect.height {
+            let edge_row = y == 0 || y == rect.height - 1;
+            for x in 0..rect.width {
+                let edge_col = x == 0 || x == rect.width - 1;
+                let c = match (edge_row, edge_col) {
+                    (true, true) => '+',
+                    (true, false) => '-',
+                    (false, true) => '|',
+                    (false, false) => ' ',
+                };
+                w.write_char(c)?;
+            }
+            w.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn golden_renders() {
+        assert_eq!(rect(1, 1).render_ascii().unwrap(), "+\n");
+        assert_eq!(rect(2, 2).render_ascii().unwrap(), "++\n++\n");
+        assert_eq!(rect(5, 3).render_ascii().unwrap(), "+---+\n|   |\n+---+\n");
+        assert_eq!(rect(4, 1).render_ascii().unwrap(), "+--+\n");
+        assert_eq!(rect(0, 3).render_ascii().unwrap(), "");
+    }
+
+    #[test]
+    fn render_into_streams_to_writer() {
+        let mut out = String::from("> ");
+        rect(3, 3).render_ascii_into(&mut out).unwrap();
+        assert_eq!(out, "> +-+\n| |\n+-+\n");
+    }
+
+    #[test]
+    fn oversized_rects_are_refused() {
+        assert_eq!(
+            rect(201, 1).render_ascii(),
+            Err(RenderError::TooLarge {
+                size: rect(201, 1),
+                limit: rect(200, 200)
+            })
+        );
+        assert!(rect(u32::MAX, u32::MAX).render_ascii().is_err());
+        assert!(AsciiRenderer::new(2, 2).render(&rect(3, 1)).is_err());
+        assert_eq!(
+            AsciiRenderer::new(2, 2).render(&rect(2, 1)).unwrap(),
+            "++\n"
+        );
+    }
+}
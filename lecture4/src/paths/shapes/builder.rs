@@ -0,0 +1,232 @@
+use std::fmt;
+
+use super::rectangles::Rect;
+
+/// One constraint a `RectBuilder` could not satisfy.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Violation {
+    MissingWidth,
+    MissingHeight,
+    AreaTooSmall { area: u64, min: u32 },
+    AspectRatioTooLarge { ratio: f64, max: f64 },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::MissingWidth => write!(f, "width is not set"),
+            Violation::MissingHeight => write!(f, "height is not set"),
+            Violation::AreaTooSmall { area, min } => {
+                write!(f, "area {} is below the minimum {}", area, min)
+            }
+            Violation::AspectRatioTooLarge { ratio, max } => {
+                write!(f, "aspect ratio {} exceeds the maximum {}", ratio, max)
+            }
+        }
+    }
+}
+
+/// Every violated constraint, in the order the builder checks them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuildError {
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cannot build rect: ")?;
+        for (i, v) in self.violations.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", v)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[derive(Debug, Clone, Default)]
+pub struct RectBuilder {
+    width: Option<u32>,
+    height: Option<u32>,
+    min_area: Option<u32>,
+    max_aspect_ratio: Option<f64>,
+}
+
+impl RectBuilder {
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn height(mut self, height: u32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn min_area(mut self, min_area: u32) -> Self {
+        self.min_area = Some(min_area);
+        self
+    }
+
+    /// Limits the longer side divided by the shorter one, so a 4x1 and a
+    /// 1x4 rect both have ratio 4. A zero side makes the ratio infinite.
+    pub fn max_aspect_ratio(mut self, max: f64) -> Self {
+        self.max_aspect_ratio = Some(max);
+        self
+    }
+
+    /// The constraints on area and aspect ratio are only checked once both
+    /// dimensions are set.
+    pub fn build(&self) -> Result<Rect, BuildError> {
+        let mut violations = Vec::new();
+        if self.width.is_none() {
+            violations.push(Violation::MissingWidth);
+        }
+        if self.height.is_none() {
+            violations.push(Violation::MissingHeight);
+        }
+        let (width, height) = match (self.width, self.height) {
+            (Some(w), Some(h)) => (w, h),
+            _ => return Err(BuildError { violations }),
+        };
+        let rect = Rect { width, height };
+
+        if let Some(min) = self.min_area {
+            let area = rect.get_area_u64();
+            if area < min as u64 {
+                violations.push(Violation::AreaTooSmall { area, min });
+            }
+        }
+        if let Some(max) = self.max_aspect_ratio {
+            let (long, short) = (width.max(height), width.min(height));
+            // 0 / 0 would be NaN, which compares false against `max`
+            let ratio = if short == 0 {
+                f64::INFINITY
+            } else {
+                long as f64 / short as f64
+            };
+            if ratio > max {
+                violations.push(Violation::AspectRatioTooLarge { ratio, max });
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(rect)
+        } else {
+            Err(BuildError { violations })
+        }
+    }
+}
+
+impl Rect {
+    pub fn builder() -> RectBuilder {
+        RectBuilder::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_when_unconstrained() {
+        let r = Rect::builder().width(3).height(4).build();
+        assert_eq!(r, Ok(Rect::from((3, 4))));
+    }
+
+    #[test]
+    fn builds_when_constraints_hold() {
+        let r = Rect::builder()
+            .width(4)
+            .height(2)
+            .min_area(8)
+            .max_aspect_ratio(2.0)
+            .build();
+        assert_eq!(r, Ok(Rect::from((4, 2))));
+    }
+
+    #[test]
+    fn missing_dimensions() {
+        let err = Rect::builder().min_area(1).build().unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![Violation::MissingWidth, Violation::MissingHeight]
+        );
+        let err = Rect::builder().height(1).build().unwrap_err();
+        assert_eq!(err.violations, vec![Violation::MissingWidth]);
+        let err = Rect::builder().width(1).build().unwrap_err();
+        assert_eq!(err.violations, vec![Violation::MissingHeight]);
+    }
+
+    #[test]
+    fn area_too_small() {
+        let err = Rect::builder()
+            .width(2)
+            .height(3)
+            .min_area(7)
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.violations,
+            vec![Violation::AreaTooSmall { area: 6, min: 7 }]
+        );
+    }
+
+    #[test]
+    fn aspect_ratio_is_orientation_independent() {
+        for (w, h) in [(9, 2), (2, 9)].iter() {
+            let err = Rect::builder()
+                .width(*w)
+                .height(*h)
+                .max_aspect_ratio(4.0)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                err.violations,
+                vec![Violation::AspectRatioTooLarge {
+                    ratio: 4.5,
+                    max: 4.0
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn zero_side_has_infinite_ratio() {
+        for (w, h) in [(0, 5), (5, 0), (0, 0)].iter() {
+            let err = Rect::builder()
+                .width(*w)
+                .height(*h)
+                .max_aspect_ratio(1e9)
+                .build()
+                .unwrap_err();
+            assert_eq!(
+                err.violations,
+                vec![Violation::AspectRatioTooLarge {
+                    ratio: f64::INFINITY,
+                    max: 1e9
+                }]
+            );
+        }
+    }
+
+    #[test]
+    fn reports_every_violation() {
+        let err = Rect::builder()
+            .width(1)
+            .height(3)
+            .min_area(4)
+            .max_aspect_ratio(2.0)
+            .build()
+            .unwrap_err();
+        assert_eq!(err.violations.len(), 2);
+        assert_eq!(
+            err.to_string(),
+            "cannot build rect: area 3 is below the minimum 4; \
+             aspect ratio 3 exceeds the maximum 2"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use std::any::Any;
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::{Point, Shape, ShapeError};
+
+/// A circle with a floating point radius.
+///
+/// Unlike `Rect`, which works in whole `u32` units, circle measurements are
+/// irrational for almost every radius, so both `get_area` and
+/// `get_perimeter` return `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl Circle {
+    /// Rejects negative and NaN radii. A radius of zero is allowed.
+    pub fn new(radius: f64) -> Result<Circle, ShapeError> {
+        if radius.is_nan() || radius < 0.0 {
+            return Err(ShapeError::InvalidRadius(radius));
+        }
+        Ok(Circle { radius })
+    }
+
+    pub fn get_area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    /// The circumference of the circle.
+    pub fn get_perimeter(&self) -> f64 {
+        2.0 * PI * self.radius
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        self.get_area()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.get_perimeter()
+    }
+
+    fn name(&self) -> &'static str {
+        "circle"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn fmt_shape(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+
+    /// `origin` is the centre of the circle.
+    fn to_svg_element(&self, origin: Point) -> String {
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+            origin.x, origin.y, self.radius
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_circle_has_area_pi() {
+        let c = Circle::new(1.0).unwrap();
+        assert!((c.get_area() - PI).abs() < 1e-12);
+        assert!((c.get_perimeter() - 2.0 * PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn zero_radius_is_allowed() {
+        let c = Circle::new(0.0).unwrap();
+        assert_eq!(c.get_area(), 0.0);
+        assert_eq!(c.get_perimeter(), 0.0);
+    }
+
+    #[test]
+    fn very_large_radius_stays_finite_until_overflow() {
+        let c = Circle::new(1e150).unwrap();
+        assert!(c.get_area().is_finite());
+        let huge = Circle::new(f64::MAX).unwrap();
+        assert!(huge.get_area().is_infinite());
+    }
+
+    #[test]
+    fn negative_and_nan_radii_are_rejected() {
+        assert_eq!(Circle::new(-1.0), Err(ShapeError::InvalidRadius(-1.0)));
+        assert!(matches!(
+            Circle::new(f64::NAN),
+            Err(ShapeError::InvalidRadius(r)) if r.is_nan()
+        ));
+    }
+}
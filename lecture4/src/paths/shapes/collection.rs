@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::iter::FromIterator;
+
+use super::Shape;
+
+#[derive(Debug, Default)]
+pub struct ShapeCollection {
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl ShapeCollection {
+    pub fn new() -> ShapeCollection {
+        ShapeCollection { shapes: Vec::new() }
+    }
+
+    pub fn push(&mut self, shape: Box<dyn Shape>) {
+        self.shapes.push(shape);
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Box<dyn Shape>> {
+        self.shapes.iter()
+    }
+
+    pub fn total_area(&self) -> f64 {
+        self.shapes.iter().map(|s| s.area()).sum()
+    }
+
+    pub fn total_perimeter(&self) -> f64 {
+        self.shapes.iter().map(|s| s.perimeter()).sum()
+    }
+
+    /// The shape with the largest area. Shapes whose area is NaN are
+    /// skipped, so this only returns `None` when no shape has a comparable
+    /// area.
+    pub fn largest(&self) -> Option<&dyn Shape> {
+        self.shapes
+            .iter()
+            .filter(|s| !s.area().is_nan())
+            .max_by(|a, b| a.area().total_cmp(&b.area()))
+            .map(|s| s.as_ref())
+    }
+
+    pub fn count_by_name(&self) -> HashMap<&'static str, usize> {
+        let mut counts = HashMap::new();
+        for shape in &self.shapes {
+            *counts.entry(shape.name()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl Extend<Box<dyn Shape>> for ShapeCollection {
+    fn extend<I: IntoIterator<Item = Box<dyn Shape>>>(&mut self, iter: I) {
+        self.shapes.extend(iter);
+    }
+}
+
+impl FromIterator<Box<dyn Shape>> for ShapeCollection {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Shape>>>(iter: I) -> ShapeCollection {
+        ShapeCollection {
+            shapes: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a ShapeCollection {
+    type Item = &'a Box<dyn Shape>;
+    type IntoIter = std::slice::Iter<'a, Box<dyn Shape>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for ShapeCollection {
+    type Item = Box<dyn Shape>;
+    type IntoIter = std::vec::IntoIter<Box<dyn Shape>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.shapes.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{new_circle, new_rect};
+    use super::*;
+
+    struct Broken;
+
+    impl Shape for Broken {
+        fn area(&self) -> f64 {
+            f64::NAN
+        }
+
+        fn perimeter(&self) -> f64 {
+            f64::NAN
+        }
+
+        fn name(&self) -> &'static str {
+            "broken"
+        }
+
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+    }
+
+    fn sample() -> ShapeCollection {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(new_rect(2, 3)),
+            Box::new(new_rect(4, 4)),
+            Box::new(new_circle(1.0).unwrap()),
+        ];
+        shapes.into_iter().collect()
+    }
+
+    #[test]
+    fn aggregates() {
+        let c = sample();
+        assert_eq!(c.len(), 3);
+        assert!((c.total_area() - (6.0 + 16.0 + std::f64::consts::PI)).abs() < 1e-12);
+        assert!((c.total_perimeter() - (10.0 + 16.0 + 2.0 * std::f64::consts::PI)).abs() < 1e-12);
+        assert_eq!(c.largest().unwrap().area(), 16.0);
+
+        let counts = c.count_by_name();
+        assert_eq!(counts["rect"], 2);
+        assert_eq!(counts["circle"], 1);
+        assert_eq!(c.iter().count(), 3);
+    }
+
+    #[test]
+    fn extend_and_push() {
+        let mut c = ShapeCollection::new();
+        assert!(c.is_empty());
+        assert!(c.largest().is_none());
+        c.push(Box::new(new_rect(1, 1)));
+        c.extend(sample());
+        assert_eq!(c.len(), 4);
+        assert_eq!(c.count_by_name()["rect"], 3);
+    }
+
+    #[test]
+    fn largest_skips_nan_areas() {
+        let mut c = ShapeCollection::new();
+        c.push(Box::new(Broken));
+        assert!(c.largest().is_none());
+        c.push(Box::new(new_rect(2, 2)));
+        c.push(Box::new(Broken));
+        assert_eq!(c.largest().unwrap().name(), "rect");
+        assert!(c.total_area().is_nan());
+    }
+
+    #[test]
+    fn collection_debug() {
+        let mut c = ShapeCollection::new();
+        c.push(Box::new(new_rect(1, 2)));
+        c.push(Box::new(Broken));
+        assert_eq!(
+            format!("{:?}", c),
+            "ShapeCollection { shapes: [Rect { width: 1, height: 2 }, broken { area: NaN }] }"
+        );
+    }
+}
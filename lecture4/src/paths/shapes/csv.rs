@@ -0,0 +1,115 @@
+//! Rectangles stored one per line as `width,height`. Blank lines and lines
+//! starting with `#` are ignored when loading.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::num::ParseIntError;
+
+use super::rectangles::Rect;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    /// The line is not two comma-separated fields.
+    Format {
+        line: usize,
+    },
+    Parse {
+        line: usize,
+        source: ParseIntError,
+    },
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "read failed: {}", e),
+            LoadError::Format { line } => {
+                write!(f, "line {}: expected `width,height`", line)
+            }
+            LoadError::Parse { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Format { .. } => None,
+            LoadError::Parse { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+/// Reads every rectangle from `reader`. Line numbers in errors start at 1.
+pub fn load_rects<R: BufRead>(reader: R) -> Result<Vec<Rect>, LoadError> {
+    let mut rects = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let mut fields = trimmed.split(',');
+        let (width, height) = match (fields.next(), fields.next(), fields.next()) {
+            (Some(w), Some(h), None) => (w.trim(), h.trim()),
+            _ => return Err(LoadError::Format { line: number }),
+        };
+        let parse = |s: &str| {
+            s.parse::<u32>().map_err(|source| LoadError::Parse {
+                line: number,
+                source,
+            })
+        };
+        rects.push(Rect {
+            width: parse(width)?,
+            height: parse(height)?,
+        });
+    }
+    Ok(rects)
+}
+
+/// Writes `rects` in the format read by `load_rects`.
+pub fn save_rects<W: Write>(mut writer: W, rects: &[Rect]) -> io::Result<()> {
+    for r in rects {
+        writeln!(writer, "{},{}", r.width, r.height)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_blanks_and_comments() {
+        let input = "# sizes\n3,4\n\n  # indented\n 5 , 6 \n";
+        let rects = load_rects(input.as_bytes()).unwrap();
+        assert_eq!(rects, vec![Rect::from((3, 4)), Rect::from((5, 6))]);
+    }
+
+    #[test]
+    fn reports_format_errors() {
+        for input in ["1,2\n3\n", "1,2\n3,4,5\n"].iter() {
+            match load_rects(input.as_bytes()) {
+                Err(LoadError::Format { line: 2 }) => {}
+                other => panic!("unexpected {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let err = load_rects("1,x\n".as_bytes()).unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid digit found in string");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
@@ -0,0 +1,169 @@
+use std::any::Any;
+use std::convert::TryFrom;
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::circles::Circle;
+use super::{Point, Shape, ShapeError};
+
+/// Relative difference between the axes below which an ellipse counts as
+/// a circle in `TryFrom<Ellipse> for Circle`.
+pub const CIRCLE_EPSILON: f64 = 1e-9;
+
+/// An ellipse with `semi_major >= semi_minor >= 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipse {
+    pub semi_major: f64,
+    pub semi_minor: f64,
+}
+
+impl Ellipse {
+    /// Takes the two semi-axes in either order and swaps them if needed.
+    /// Negative and NaN axes are rejected. A zero axis is allowed, just as
+    /// `Circle` allows a zero radius, so that every circle converts.
+    pub fn new(a: f64, b: f64) -> Result<Ellipse, ShapeError> {
+        for &axis in [a, b].iter() {
+            if axis.is_nan() || axis < 0.0 {
+                return Err(ShapeError::InvalidAxis(axis));
+            }
+        }
+        Ok(Ellipse {
+            semi_major: a.max(b),
+            semi_minor: a.min(b),
+        })
+    }
+
+    pub fn get_area(&self) -> f64 {
+        PI * self.semi_major * self.semi_minor
+    }
+
+    /// Ramanujan's second approximation, accurate to well within 0.1% for
+    /// every eccentricity.
+    pub fn get_perimeter(&self) -> f64 {
+        let (a, b) = (self.semi_major, self.semi_minor);
+        if a + b == 0.0 {
+            return 0.0;
+        }
+        let h = ((a - b) / (a + b)).powi(2);
+        PI * (a + b) * (1.0 + 3.0 * h / (10.0 + (4.0 - 3.0 * h).sqrt()))
+    }
+
+    /// 0 for a circle, approaching 1 as the ellipse flattens.
+    pub fn eccentricity(&self) -> f64 {
+        if self.semi_major == 0.0 {
+            return 0.0;
+        }
+        (1.0 - (self.semi_minor / self.semi_major).powi(2)).sqrt()
+    }
+}
+
+impl From<Circle> for Ellipse {
+    fn from(circle: Circle) -> Ellipse {
+        Ellipse {
+            semi_major: circle.radius,
+            semi_minor: circle.radius,
+        }
+    }
+}
+
+/// Succeeds when the axes agree to within `CIRCLE_EPSILON`, relative to the
+/// semi-major axis, using the mean of the two as the radius.
+impl TryFrom<Ellipse> for Circle {
+    type Error = ShapeError;
+
+    fn try_from(ellipse: Ellipse) -> Result<Circle, ShapeError> {
+        let (a, b) = (ellipse.semi_major, ellipse.semi_minor);
+        if a - b > CIRCLE_EPSILON * a {
+            return Err(ShapeError::NotACircle {
+                eccentricity: ellipse.eccentricity(),
+            });
+        }
+        Circle::new((a + b) / 2.0)
+    }
+}
+
+impl Shape for Ellipse {
+    fn area(&self) -> f64 {
+        self.get_area()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.get_perimeter()
+    }
+
+    fn name(&self) -> &'static str {
+        "ellipse"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn fmt_shape(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+
+    /// `origin` is the centre, with the major axis horizontal.
+    fn to_svg_element(&self, origin: Point) -> String {
+        format!(
+            r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"/>"#,
+            origin.x, origin.y, self.semi_major, self.semi_minor
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_within_0_1_percent(actual: f64, expected: f64) {
+        assert!(
+            ((actual - expected) / expected).abs() < 0.001,
+            "{} is not within 0.1% of {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn perimeter_matches_known_values() {
+        // Reference values from the exact elliptic integral.
+        assert_within_0_1_percent(Ellipse::new(5.0, 3.0).unwrap().perimeter(), 25.526_998_863);
+        assert_within_0_1_percent(Ellipse::new(10.0, 1.0).unwrap().perimeter(), 40.639_741_801);
+        assert_within_0_1_percent(Ellipse::new(1.0, 0.0).unwrap().perimeter(), 4.0);
+        assert_within_0_1_percent(Ellipse::new(2.0, 2.0).unwrap().perimeter(), 4.0 * PI);
+    }
+
+    #[test]
+    fn area_and_eccentricity() {
+        let e = Ellipse::new(3.0, 5.0).unwrap();
+        assert_eq!(e.semi_major, 5.0);
+        assert_eq!(e.semi_minor, 3.0);
+        assert_eq!(e.area(), 15.0 * PI);
+        assert_eq!(e.eccentricity(), 0.8);
+        assert_eq!(Ellipse::new(0.0, 0.0).unwrap().eccentricity(), 0.0);
+    }
+
+    #[test]
+    fn constructor_rejects_invalid_axes() {
+        assert_eq!(Ellipse::new(-1.0, 2.0), Err(ShapeError::InvalidAxis(-1.0)));
+        assert!(Ellipse::new(1.0, f64::NAN).is_err());
+    }
+
+    #[test]
+    fn circle_round_trip() {
+        for &radius in [0.0, 1.0, 2.5, 1e6].iter() {
+            let circle = Circle::new(radius).unwrap();
+            let ellipse = Ellipse::from(circle);
+            assert_eq!(ellipse.area(), circle.area());
+            assert!((ellipse.perimeter() - circle.perimeter()).abs() < 1e-9 * radius.max(1.0));
+            assert_eq!(Circle::try_from(ellipse), Ok(circle));
+        }
+        let almost = Ellipse::new(1.0, 1.0 - 1e-12).unwrap();
+        assert!(Circle::try_from(almost).is_ok());
+        assert_eq!(
+            Circle::try_from(Ellipse::new(5.0, 3.0).unwrap()),
+            Err(ShapeError::NotACircle { eccentricity: 0.8 })
+        );
+    }
+}
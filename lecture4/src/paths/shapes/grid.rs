@@ -0,0 +1,144 @@
+use std::ops::{Index, IndexMut};
+
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+/// A `width` x `height` grid of cells stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    size: Rect,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(rect: &Rect, fill: T) -> Grid<T> {
+        let len = (rect.width as usize)
+            .checked_mul(rect.height as usize)
+            .expect("grid is too large to allocate");
+        Grid {
+            size: *rect,
+            cells: vec![fill; len],
+        }
+    }
+
+    /// Sets every cell covered by `region` to `value`. Any part of the region
+    /// that falls outside the grid is ignored.
+    pub fn fill_region(&mut self, region: &PositionedRect, value: T) {
+        let bounds = PositionedRect::new(Point { x: 0, y: 0 }, self.size);
+        let clipped = match bounds.intersection(region) {
+            Some(r) => r,
+            None => return,
+        };
+        let (x0, y0) = (clipped.origin.x as u32, clipped.origin.y as u32);
+        for y in y0..y0 + clipped.size.height {
+            for x in x0..x0 + clipped.size.width {
+                self[(x, y)] = value.clone();
+            }
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn size(&self) -> Rect {
+        self.size
+    }
+
+    fn offset(&self, x: u32, y: u32) -> Option<usize> {
+        if x < self.size.width && y < self.size.height {
+            Some(y as usize * self.size.width as usize + x as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Option<&T> {
+        self.offset(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: u32, y: u32) -> Option<&mut T> {
+        self.offset(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// One slice per row, top to bottom. A zero-width grid still yields
+    /// `height` empty rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        let width = self.size.width as usize;
+        (0..self.size.height as usize).map(move |y| &self.cells[y * width..(y + 1) * width])
+    }
+
+    fn offset_or_panic(&self, x: u32, y: u32) -> usize {
+        match self.offset(x, y) {
+            Some(i) => i,
+            None => panic!(
+                "grid index ({}, {}) out of bounds for a {} grid",
+                x, y, self.size
+            ),
+        }
+    }
+}
+
+impl<T> Index<(u32, u32)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (u32, u32)) -> &T {
+        &self.cells[self.offset_or_panic(x, y)]
+    }
+}
+
+impl<T> IndexMut<(u32, u32)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (u32, u32)) -> &mut T {
+        let i = self.offset_or_panic(x, y);
+        &mut self.cells[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn index_and_get() {
+        let mut g = Grid::new(&rect(3, 2), 0);
+        g[(2, 1)] = 7;
+        *g.get_mut(0, 1).unwrap() = 4;
+        assert_eq!(g[(2, 1)], 7);
+        assert_eq!(g.get(0, 1), Some(&4));
+        assert_eq!(g.get(3, 0), None);
+        assert_eq!(g.get(0, 2), None);
+        let rows: Vec<&[i32]> = g.rows().collect();
+        assert_eq!(rows, vec![&[0, 0, 0][..], &[4, 0, 7][..]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid index (3, 0) out of bounds for a 3x2 grid")]
+    fn index_out_of_bounds_panics() {
+        let g = Grid::new(&rect(3, 2), 'a');
+        let _ = g[(3, 0)];
+    }
+
+    #[test]
+    fn zero_width_grid() {
+        let g = Grid::new(&rect(0, 3), 1u8);
+        assert_eq!(g.get(0, 0), None);
+        assert_eq!(g.rows().count(), 3);
+        assert!(g.rows().all(|r| r.is_empty()));
+    }
+
+    #[test]
+    fn fill_region_is_clipped_to_the_grid() {
+        let mut g = Grid::new(&rect(4, 3), '.');
+        g.fill_region(
+            &PositionedRect::new(Point { x: 2, y: -1 }, rect(10, 3)),
+            '#',
+        );
+        let rendered: Vec<String> = g.rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rendered, vec!["..##", "..##", "...."]);
+
+        g.fill_region(&PositionedRect::new(Point { x: 9, y: 9 }, rect(1, 1)), 'x');
+        assert!(g.rows().flatten().all(|&c| c != 'x'));
+    }
+}
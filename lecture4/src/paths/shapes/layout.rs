@@ -0,0 +1,184 @@
+use std::fmt;
+
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The gaps between cells leave no room for the cells themselves.
+    GapTooLarge,
+    /// Even without gaps there are more cells than units along an edge.
+    ContainerTooSmall,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::GapTooLarge => write!(f, "gap leaves no room for the cells"),
+            LayoutError::ContainerTooSmall => write!(f, "container is too small for the cells"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// The `(rows, columns)` arrangement closest to square that has room for
+/// `cells` cells, preferring an extra column over an extra row.
+pub fn rows_and_columns(cells: u32) -> (u32, u32) {
+    if cells == 0 {
+        return (0, 0);
+    }
+    let mut cols = (cells as f64).sqrt() as u32;
+    while (cols as u64) * (cols as u64) < cells as u64 {
+        cols += 1;
+    }
+    (cells.div_ceil(cols), cols)
+}
+
+/// Splits `length` into `parts` sizes separated by `gap`, handing the
+/// remainder out one unit at a time to the first parts.
+fn split_evenly(length: u32, parts: u32, gap: u32) -> Result<Vec<(u32, u32)>, LayoutError> {
+    let gaps = gap as u64 * (parts as u64 - 1);
+    if (length as u64) < parts as u64 {
+        return Err(LayoutError::ContainerTooSmall);
+    }
+    if (length as u64) < gaps + parts as u64 {
+        return Err(LayoutError::GapTooLarge);
+    }
+    let available = (length as u64 - gaps) as u32;
+    let (base, extra) = (available / parts, available % parts);
+    let mut offset = 0;
+    Ok((0..parts)
+        .map(|i| {
+            // only between parts: a gap after the last one could overflow
+            if i > 0 {
+                offset += gap;
+            }
+            let size = base + u32::from(i < extra);
+            let start = offset;
+            offset += size;
+            (start, size)
+        })
+        .collect())
+}
+
+/// Lays `cells` cells out row by row in the `rows_and_columns` grid,
+/// positioned relative to the container's top-left corner. The cells and
+/// gaps of each full row or column add up to exactly the container's
+/// width or height; a partial last row keeps the column widths.
+pub fn grid_layout(
+    container: &Rect,
+    cells: u32,
+    gap: u32,
+) -> Result<Vec<PositionedRect>, LayoutError> {
+    if cells == 0 {
+        return Ok(Vec::new());
+    }
+    let (rows, cols) = rows_and_columns(cells);
+    let columns = split_evenly(container.width, cols, gap)?;
+    let row_spans = split_evenly(container.height, rows, gap)?;
+    Ok((0..cells as usize)
+        .map(|i| {
+            let (x, width) = columns[i % cols as usize];
+            let (y, height) = row_spans[i / cols as usize];
+            PositionedRect::new(
+                Point {
+                    x: x as i64,
+                    y: y as i64,
+                },
+                Rect { width, height },
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrangement_closest_to_square() {
+        assert_eq!(rows_and_columns(0), (0, 0));
+        assert_eq!(rows_and_columns(1), (1, 1));
+        assert_eq!(rows_and_columns(2), (1, 2));
+        assert_eq!(rows_and_columns(5), (2, 3));
+        assert_eq!(rows_and_columns(9), (3, 3));
+        assert_eq!(rows_and_columns(10), (3, 4));
+        assert_eq!(rows_and_columns(u32::MAX), (65536, 65536));
+    }
+
+    #[test]
+    fn zero_cells() {
+        assert_eq!(grid_layout(&Rect::from((10, 10)), 0, 100), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn cells_and_gaps_fill_the_container_exactly() {
+        for &(w, h) in [(100, 100), (97, 89), (13, 7), (101, 53), (1000, 31)].iter() {
+            for &cells in [1, 2, 3, 6, 7, 9].iter() {
+                let gap = 1;
+                let container = Rect::from((w, h));
+                let layout = grid_layout(&container, cells, gap).unwrap();
+                assert_eq!(layout.len(), cells as usize);
+                let (rows, cols) = rows_and_columns(cells);
+
+                let first_row = &layout[..cols.min(cells) as usize];
+                let width: u32 = first_row.iter().map(|c| c.size.width).sum();
+                if cols as usize == first_row.len() {
+                    assert_eq!(width + gap * (cols - 1), w);
+                }
+                let first_col: Vec<_> = layout.iter().step_by(cols as usize).collect();
+                assert_eq!(first_col.len(), rows as usize);
+                let height: u32 = first_col.iter().map(|c| c.size.height).sum();
+                assert_eq!(height + gap * (rows - 1), h);
+
+                for (i, a) in layout.iter().enumerate() {
+                    for b in &layout[i + 1..] {
+                        assert!(!a.intersects(b));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn remainder_goes_to_the_first_columns() {
+        let layout = grid_layout(&Rect::from((11, 5)), 9, 0).unwrap();
+        let widths: Vec<_> = layout[..3].iter().map(|c| c.size.width).collect();
+        let xs: Vec<_> = layout[..3].iter().map(|c| c.origin.x).collect();
+        let heights: Vec<_> = layout.iter().step_by(3).map(|c| c.size.height).collect();
+        assert_eq!(widths, vec![4, 4, 3]);
+        assert_eq!(xs, vec![0, 4, 8]);
+        assert_eq!(heights, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            grid_layout(&Rect::from((10, 10)), 4, 9),
+            Err(LayoutError::GapTooLarge)
+        );
+        assert_eq!(
+            grid_layout(&Rect::from((10, 10)), 4, u32::MAX),
+            Err(LayoutError::GapTooLarge)
+        );
+        assert_eq!(
+            grid_layout(&Rect::from((1, 10)), 4, 0),
+            Err(LayoutError::ContainerTooSmall)
+        );
+        assert!(grid_layout(&Rect::from((10, 10)), 4, 8).is_ok());
+    }
+
+    #[test]
+    fn fills_the_widest_container() {
+        let container = Rect {
+            width: u32::MAX,
+            height: 1,
+        };
+        let layout = grid_layout(&container, 2, 1).unwrap();
+        assert_eq!(layout[0].origin.x, 0);
+        assert_eq!(layout[0].size.width, u32::MAX / 2);
+        assert_eq!(layout[1].origin.x, u32::MAX as i64 / 2 + 1);
+        assert_eq!(layout[1].size.width, u32::MAX / 2);
+    }
+}
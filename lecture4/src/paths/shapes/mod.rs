@@ -0,0 +1,262 @@
+use std::any::Any;
+use std::fmt;
+
+pub mod aggregate;
+pub mod animation;
+pub mod ascii;
+pub mod builder;
+pub mod circles;
+pub mod collection;
+pub mod csv;
+pub mod ellipses;
+pub mod grid;
+pub mod layout;
+pub mod numeric;
+pub mod packer;
+pub mod perimeter;
+pub mod polygons;
+pub mod positioned;
+pub mod quadtree;
+pub mod rectangles;
+pub mod rectf;
+pub mod registry;
+pub mod serialize;
+pub mod solids;
+pub mod squares;
+pub mod stats;
+pub mod svg;
+pub mod testing;
+pub mod tiles;
+pub mod transform;
+
+pub use aggregate::{AverageDimensions, TotalArea};
+pub use animation::lerp;
+pub use collection::ShapeCollection;
+pub use grid::Grid;
+pub use numeric::Numeric;
+pub use positioned::{bounding_box, ClampPolicy, Point, PositionedRect};
+pub use rectf::RectF;
+pub use registry::Registry;
+pub use squares::Square;
+pub use transform::Rounding;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    InvalidRadius(f64),
+    InvalidAxis(f64),
+    NotACircle { eccentricity: f64 },
+    InvalidDimension(f64),
+    InvalidArgument(String),
+    ArityMismatch { expected: usize, got: usize },
+    UnknownShape(String),
+    AlreadyRegistered(String),
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShapeError::InvalidRadius(r) => write!(f, "invalid radius: {}", r),
+            ShapeError::InvalidAxis(a) => write!(f, "invalid semi-axis: {}", a),
+            ShapeError::NotACircle { eccentricity } => {
+                write!(
+                    f,
+                    "ellipse with eccentricity {} is not a circle",
+                    eccentricity
+                )
+            }
+            ShapeError::InvalidDimension(d) => write!(f, "invalid dimension: {}", d),
+            ShapeError::InvalidArgument(a) => write!(f, "invalid argument: {:?}", a),
+            ShapeError::ArityMismatch { expected, got } => {
+                write!(f, "expected {} arguments, got {}", expected, got)
+            }
+            ShapeError::UnknownShape(name) => write!(f, "unknown shape: {:?}", name),
+            ShapeError::AlreadyRegistered(name) => {
+                write!(f, "shape {:?} is already registered", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+pub trait Shape {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn name(&self) -> &'static str;
+
+    /// Lets a `dyn Shape` be downcast back to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Used by the `Debug` impl for `dyn Shape`. Concrete shapes forward
+    /// to their own `Debug`; the default prints the name and area.
+    fn fmt_shape(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct(self.name())
+            .field("area", &self.area())
+            .finish()
+    }
+
+    /// An SVG element drawing this shape at `origin`. Shapes without a
+    /// natural SVG element are emitted as a comment.
+    fn to_svg_element(&self, origin: Point) -> String {
+        format!("<!-- {} at ({}, {}) -->", self.name(), origin.x, origin.y)
+    }
+}
+
+impl fmt::Debug for dyn Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_shape(f)
+    }
+}
+
+pub fn total_area(shapes: &[Box<dyn Shape>]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
+
+/// The `Rect`s (measured in `u32`) among `shapes`, skipping every other
+/// kind of shape.
+pub fn rects_only(shapes: &[Box<dyn Shape>]) -> impl Iterator<Item = &rectangles::Rect> {
+    shapes.iter().filter_map(|s| s.as_any().downcast_ref())
+}
+
+/// Panics on the same inputs that `try_new_rect` rejects.
+pub fn new_rect(width: u32, height: u32) -> rectangles::Rect {
+    match try_new_rect(width, height) {
+        Ok(rect) => rect,
+        Err(e) => panic!("cannot create {}x{} rectangle: {}", width, height, e),
+    }
+}
+
+pub fn try_new_rect(width: u32, height: u32) -> Result<rectangles::Rect, rectangles::RectError> {
+    if width == 0 {
+        return Err(rectangles::RectError::ZeroWidth);
+    }
+    if height == 0 {
+        return Err(rectangles::RectError::ZeroHeight);
+    }
+    if width.checked_mul(height).is_none() {
+        return Err(rectangles::RectError::AreaOverflow);
+    }
+    Ok(rectangles::Rect { width, height })
+}
+
+pub fn new_circle(radius: f64) -> Result<circles::Circle, ShapeError> {
+    circles::Circle::new(radius)
+}
+
+/// Sorts by area, smallest first, using `Rect`'s `Ord`, so rects with equal
+/// areas always end up in the same order.
+pub fn sort_by_area(rects: &mut [rectangles::Rect]) {
+    rects.sort();
+}
+
+/// The rect with the largest area, ties broken as in `Rect`'s `Ord`.
+pub fn largest(rects: &[rectangles::Rect]) -> Option<&rectangles::Rect> {
+    rects.iter().max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_area_sums_mixed_shapes() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(new_rect(2, 3)),
+            Box::new(new_rect(4, 5)),
+            Box::new(new_circle(1.0).unwrap()),
+        ];
+        let expected = 6.0 + 20.0 + std::f64::consts::PI;
+        assert!((total_area(&shapes) - expected).abs() < 1e-12);
+        assert_eq!(total_area(&[]), 0.0);
+    }
+
+    #[test]
+    fn try_new_rect_reports_each_error() {
+        use rectangles::RectError;
+
+        assert_eq!(try_new_rect(0, 5).err(), Some(RectError::ZeroWidth));
+        assert_eq!(try_new_rect(5, 0).err(), Some(RectError::ZeroHeight));
+        assert_eq!(try_new_rect(0, 0).err(), Some(RectError::ZeroWidth));
+        assert_eq!(
+            try_new_rect(65536, 65536).err(),
+            Some(RectError::AreaOverflow)
+        );
+    }
+
+    #[test]
+    fn new_rect_matches_try_new_rect() {
+        let r = new_rect(5, 6);
+        let t = try_new_rect(5, 6).unwrap();
+        assert_eq!((r.width, r.height), (t.width, t.height));
+        assert_eq!(r.get_area(), 30);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot create 0x5 rectangle")]
+    fn new_rect_panics_on_zero_width() {
+        new_rect(0, 5);
+    }
+
+    #[test]
+    fn sorting_equal_areas_is_deterministic() {
+        let mut a = vec![
+            new_rect(6, 1),
+            new_rect(2, 3),
+            new_rect(1, 6),
+            new_rect(3, 2),
+            new_rect(1, 1),
+        ];
+        let mut b = a.clone();
+        b.reverse();
+        sort_by_area(&mut a);
+        sort_by_area(&mut b);
+        assert_eq!(a, b);
+        assert_eq!(
+            a,
+            vec![
+                new_rect(1, 1),
+                new_rect(1, 6),
+                new_rect(2, 3),
+                new_rect(3, 2),
+                new_rect(6, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn largest_rect() {
+        assert_eq!(largest(&[]), None);
+        let rects = [new_rect(2, 3), new_rect(4, 1), new_rect(3, 2)];
+        assert_eq!(largest(&rects), Some(&new_rect(3, 2)));
+    }
+
+    #[test]
+    fn downcasting_mixed_shapes() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(new_rect(2, 3)),
+            Box::new(new_circle(1.0).unwrap()),
+            Box::new(new_rect(4, 5)),
+            Box::new(rectangles::Rect {
+                width: 1.5f64,
+                height: 2.0,
+            }),
+        ];
+        let rects: Vec<_> = rects_only(&shapes).collect();
+        assert_eq!(rects, vec![&new_rect(2, 3), &new_rect(4, 5)]);
+        let circles = shapes
+            .iter()
+            .filter(|s| s.as_any().is::<circles::Circle>())
+            .count();
+        assert_eq!(circles, 1);
+    }
+
+    #[test]
+    fn debug_for_dyn_shape() {
+        let shapes: Vec<Box<dyn Shape>> =
+            vec![Box::new(new_rect(2, 3)), Box::new(new_circle(1.0).unwrap())];
+        assert_eq!(
+            format!("{:?}", shapes),
+            "[Rect { width: 2, height: 3 }, Circle { radius: 1.0 }]"
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt::Debug;
+use std::ops::{Add, Mul};
+
+/// The numeric types a `Rect` can be measured in.
+///
+/// For integers `checked_add`/`checked_mul` return `None` on overflow. For
+/// floats they return `None` when the result is not finite, which covers
+/// both overflow to infinity and NaN inputs.
+pub trait Numeric:
+    'static + Copy + PartialOrd + Debug + Add<Output = Self> + Mul<Output = Self>
+{
+    fn zero() -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! impl_numeric_int {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_numeric_float {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                fn zero() -> Self {
+                    0.0
+                }
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other).filter(|v| v.is_finite())
+                }
+
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    Some(self * other).filter(|v| v.is_finite())
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+impl_numeric_float!(f32, f64);
@@ -0,0 +1,136 @@
+use std::fmt;
+
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackError {
+    /// The item is wider or taller than the container on its own.
+    TooLarge { index: usize },
+    /// The item ran out of room after the items before it were placed.
+    DoesNotFit { index: usize },
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::TooLarge { index } => {
+                write!(f, "item {} is larger than the container", index)
+            }
+            PackError::DoesNotFit { index } => write!(f, "no room left for item {}", index),
+        }
+    }
+}
+
+impl std::error::Error for PackError {}
+
+/// Shelf packing: items are placed left to right in the order given, and
+/// a new row starts below the tallest item of the current row whenever the
+/// next item would cross the container's right edge. Positions are
+/// relative to the container's top-left corner.
+pub fn pack(container: &Rect, items: &[Rect]) -> Result<Vec<PositionedRect>, PackError> {
+    if let Some(index) = items
+        .iter()
+        .position(|r| r.width > container.width || r.height > container.height)
+    {
+        return Err(PackError::TooLarge { index });
+    }
+
+    let (container_w, container_h) = (container.width as u64, container.height as u64);
+    let (mut x, mut y, mut row_height) = (0u64, 0u64, 0u64);
+    let mut placed = Vec::with_capacity(items.len());
+    for (index, item) in items.iter().enumerate() {
+        let (w, h) = (item.width as u64, item.height as u64);
+        if x + w > container_w {
+            x = 0;
+            y += row_height;
+            row_height = 0;
+        }
+        if y + h > container_h {
+            return Err(PackError::DoesNotFit { index });
+        }
+        placed.push(item.at(Point {
+            x: x as i64,
+            y: y as i64,
+        }));
+        x += w;
+        row_height = row_height.max(h);
+    }
+    Ok(placed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    fn assert_valid_packing(container: &Rect, placed: &[PositionedRect]) {
+        let bounds = container.at(Point { x: 0, y: 0 });
+        for (i, a) in placed.iter().enumerate() {
+            if a.size.get_area() > 0 {
+                assert_eq!(bounds.intersection(a), Some(*a), "item {} escapes", i);
+            }
+            for b in &placed[i + 1..] {
+                assert!(!a.intersects(b), "{:?} overlaps {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn packs_into_rows() {
+        let container = rect(10, 10);
+        let items = [rect(4, 3), rect(4, 2), rect(4, 4), rect(10, 1), rect(2, 2)];
+        let placed = pack(&container, &items).unwrap();
+        let origins: Vec<_> = placed.iter().map(|p| (p.origin.x, p.origin.y)).collect();
+        assert_eq!(origins, vec![(0, 0), (4, 0), (0, 3), (0, 7), (0, 8)]);
+        assert_valid_packing(&container, &placed);
+    }
+
+    #[test]
+    fn many_items_never_overlap() {
+        let container = rect(50, 1000);
+        let items: Vec<Rect> = (0..200)
+            .map(|i| rect(1 + i * 7 % 13, 1 + i * 5 % 11))
+            .collect();
+        let placed = pack(&container, &items).unwrap();
+        assert_eq!(placed.len(), items.len());
+        assert_valid_packing(&container, &placed);
+    }
+
+    #[test]
+    fn oversized_items_fail_immediately() {
+        let container = rect(10, 10);
+        let items = [rect(10, 10), rect(10, 10), rect(11, 1)];
+        assert_eq!(
+            pack(&container, &items),
+            Err(PackError::TooLarge { index: 2 })
+        );
+        assert_eq!(
+            pack(&container, &[rect(1, 1), rect(1, 11)]),
+            Err(PackError::TooLarge { index: 1 })
+        );
+    }
+
+    #[test]
+    fn running_out_of_room() {
+        let container = rect(10, 10);
+        let items = [rect(6, 6), rect(6, 5)];
+        assert_eq!(
+            pack(&container, &items),
+            Err(PackError::DoesNotFit { index: 1 })
+        );
+    }
+
+    #[test]
+    fn translate() {
+        let p = rect(2, 3).at(Point { x: 1, y: 1 });
+        assert_eq!(
+            p.translate(-4, 2),
+            Some(rect(2, 3).at(Point { x: -3, y: 3 }))
+        );
+        assert_eq!(p.translate(i64::MAX, 0), None);
+    }
+}
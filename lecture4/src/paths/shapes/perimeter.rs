@@ -0,0 +1,167 @@
+use super::positioned::Point;
+use super::rectangles::Rect;
+
+/// Iterator returned by `Rect::perimeter_cells`.
+#[derive(Debug, Clone)]
+pub struct PerimeterIter {
+    origin: Point,
+    width: u64,
+    height: u64,
+    next: u64,
+    len: u64,
+}
+
+impl PerimeterIter {
+    fn new(rect: &Rect, origin: Point) -> PerimeterIter {
+        let (width, height) = (rect.width as u64, rect.height as u64);
+        let len = if width == 0 || height == 0 {
+            0
+        } else if width == 1 || height == 1 {
+            width * height
+        } else {
+            2 * width + 2 * height - 4
+        };
+        PerimeterIter {
+            origin,
+            width,
+            height,
+            next: 0,
+            len,
+        }
+    }
+
+    fn cell(&self, dx: u64, dy: u64) -> Point {
+        Point {
+            x: self.origin.x + dx as i64,
+            y: self.origin.y + dy as i64,
+        }
+    }
+}
+
+impl Iterator for PerimeterIter {
+    type Item = Point;
+
+    fn next(&mut self) -> Option<Point> {
+        if self.next >= self.len {
+            return None;
+        }
+        let (w, h) = (self.width, self.height);
+        let mut i = self.next;
+        self.next += 1;
+
+        if i < w {
+            return Some(self.cell(i, 0));
+        }
+        i -= w;
+        if i < h - 1 {
+            return Some(self.cell(w - 1, 1 + i));
+        }
+        i -= h - 1;
+        if i < w - 1 {
+            return Some(self.cell(w - 2 - i, h - 1));
+        }
+        i -= w - 1;
+        Some(self.cell(0, h - 2 - i))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.len - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for PerimeterIter {}
+
+impl Rect {
+    /// Every whole cell on the rect's boundary exactly once, clockwise in
+    /// screen coordinates: along the top edge from `origin`, down the right
+    /// edge, back along the bottom and up the left. The count is
+    /// `2 * (width + height) - 4`, except that a 1xN or Nx1 rect has N cells
+    /// and a rect with a zero dimension has none.
+    ///
+    /// Panics if the far corner does not fit in an `i64`.
+    pub fn perimeter_cells(&self, origin: Point) -> PerimeterIter {
+        assert!(
+            origin.x.checked_add(self.width as i64).is_some()
+                && origin.y.checked_add(self.height as i64).is_some(),
+            "{} rect at ({}, {}) overflows i64 coordinates",
+            self,
+            origin.x,
+            origin.y
+        );
+        PerimeterIter::new(self, origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn cells(width: u32, height: u32) -> Vec<(i64, i64)> {
+        Rect { width, height }
+            .perimeter_cells(Point { x: 0, y: 0 })
+            .map(|p| (p.x, p.y))
+            .collect()
+    }
+
+    #[test]
+    fn clockwise_from_the_origin() {
+        assert_eq!(
+            cells(3, 3),
+            vec![
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 1)
+            ]
+        );
+        let offset: Vec<_> = Rect::square(2)
+            .perimeter_cells(Point { x: -5, y: 10 })
+            .collect();
+        assert_eq!(
+            offset,
+            vec![
+                Point { x: -5, y: 10 },
+                Point { x: -4, y: 10 },
+                Point { x: -4, y: 11 },
+                Point { x: -5, y: 11 }
+            ]
+        );
+    }
+
+    #[test]
+    fn degenerate_shapes() {
+        assert_eq!(cells(1, 1), vec![(0, 0)]);
+        assert_eq!(cells(4, 1), vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(cells(1, 3), vec![(0, 0), (0, 1), (0, 2)]);
+        assert!(cells(0, 5).is_empty());
+        assert!(cells(5, 0).is_empty());
+    }
+
+    #[test]
+    fn no_duplicates_and_exact_counts() {
+        for w in 0..8 {
+            for h in 0..8 {
+                let rect = Rect::from((w, h));
+                let iter = rect.perimeter_cells(Point { x: 0, y: 0 });
+                let expected = iter.len();
+                let all = cells(w, h);
+                let unique: HashSet<_> = all.iter().collect();
+                assert_eq!(all.len(), expected);
+                assert_eq!(unique.len(), expected);
+                assert!(all
+                    .iter()
+                    .all(|&(x, y)| x == 0 || y == 0 || x == w as i64 - 1 || y == h as i64 - 1));
+                if w >= 2 && h >= 2 {
+                    assert_eq!(expected as u32, rect.get_perimeter() - 4);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use std::any::Any;
+use std::f64::consts::PI;
+use std::fmt;
+
+use super::positioned::Point;
+use super::rectangles::Rect;
+use super::Shape;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewVertices(usize),
+    DuplicateVertex { index: usize },
+    CoordinateOverflow,
+    /// The exact shoelace sum doesn't fit in `i128`.
+    AreaOverflow,
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(n) => {
+                write!(f, "a polygon needs at least 3 vertices, got {}", n)
+            }
+            PolygonError::DuplicateVertex { index } => {
+                write!(f, "vertex {} repeats the previous vertex", index)
+            }
+            PolygonError::CoordinateOverflow => write!(f, "vertex coordinates overflow i64"),
+            PolygonError::AreaOverflow => write!(f, "area overflows i128"),
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// A closed polygon; the last vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    /// Rejects fewer than 3 vertices and any vertex equal to the one before
+    /// it, including the last vertex repeating the first.
+    pub fn new(vertices: Vec<Point>) -> Result<Polygon, PolygonError> {
+        let n = vertices.len();
+        if n < 3 {
+            return Err(PolygonError::TooFewVertices(n));
+        }
+        for i in 0..n {
+            if vertices[i] == vertices[(i + 1) % n] {
+                return Err(PolygonError::DuplicateVertex { index: (i + 1) % n });
+            }
+        }
+        Ok(Polygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let n = self.vertices.len();
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Shoelace formula, summed exactly by `checked_area` where possible.
+    /// Coordinates far enough from the origin can overflow even `i128`;
+    /// the sum is then done in `f64` instead, which is only approximate.
+    pub fn area(&self) -> f64 {
+        self.checked_area().unwrap_or_else(|_| {
+            let twice: f64 = self
+                .edges()
+                .map(|(a, b)| a.x as f64 * b.y as f64 - b.x as f64 * a.y as f64)
+                .sum();
+            twice.abs() / 2.0
+        })
+    }
+
+    /// Shoelace formula, summed exactly in `i128` before converting. Each
+    /// cross product fits, but with coordinates beyond about ±2^62 their
+    /// differences and sum may not, which is reported as `AreaOverflow`.
+    pub fn checked_area(&self) -> Result<f64, PolygonError> {
+        let mut twice: i128 = 0;
+        for (a, b) in self.edges() {
+            // each product is at most 2^126 in magnitude, so only the
+            // subtraction and the sum can overflow
+            let cross = (a.x as i128 * b.y as i128)
+                .checked_sub(b.x as i128 * a.y as i128)
+                .ok_or(PolygonError::AreaOverflow)?;
+            twice = twice.checked_add(cross).ok_or(PolygonError::AreaOverflow)?;
+        }
+        Ok(twice.unsigned_abs() as f64 / 2.0)
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| {
+                let dx = b.x as f64 - a.x as f64;
+                let dy = b.y as f64 - a.y as f64;
+                dx.hypot(dy)
+            })
+            .sum()
+    }
+
+    /// True when every turn goes the same way and the boundary winds around
+    /// exactly once, which rules out self-intersecting stars. Collinear
+    /// vertices are allowed, but a polygon with no turns at all is not
+    /// convex.
+    pub fn is_convex(&self) -> bool {
+        let n = self.vertices.len();
+        let mut sign = 0;
+        let mut winding = 0.0;
+        for i in 0..n {
+            let a = self.vertices[i];
+            let b = self.vertices[(i + 1) % n];
+            let c = self.vertices[(i + 2) % n];
+            let (abx, aby) = (b.x as f64 - a.x as f64, b.y as f64 - a.y as f64);
+            let (bcx, bcy) = (c.x as f64 - b.x as f64, c.y as f64 - b.y as f64);
+            let cross = abx * bcy - aby * bcx;
+            let dot = abx * bcx + aby * bcy;
+            if cross != 0.0 {
+                let turn = if cross > 0.0 { 1 } else { -1 };
+                if sign != 0 && sign != turn {
+                    return false;
+                }
+                sign = turn;
+            } else if dot < 0.0 {
+                return false;
+            }
+            winding += cross.atan2(dot);
+        }
+        sign != 0 && (winding.abs() - 2.0 * PI).abs() < 1e-6
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        Polygon::area(self)
+    }
+
+    fn perimeter(&self) -> f64 {
+        Polygon::perimeter(self)
+    }
+
+    fn name(&self) -> &'static str {
+        "polygon"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn fmt_shape(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+
+    /// Every vertex is offset by `origin`.
+    fn to_svg_element(&self, origin: Point) -> String {
+        let points: Vec<String> = self
+            .vertices
+            .iter()
+            .map(|v| {
+                format!(
+                    "{},{}",
+                    v.x as i128 + origin.x as i128,
+                    v.y as i128 + origin.y as i128
+                )
+            })
+            .collect();
+        format!(r#"<polygon points="{}"/>"#, points.join(" "))
+    }
+}
+
+impl Rect {
+    /// The rect's corners, clockwise from `origin` in screen coordinates.
+    /// Fails for zero-sized rects, whose corners coincide.
+    pub fn to_polygon(&self, origin: Point) -> Result<Polygon, PolygonError> {
+        let right = origin
+            .x
+            .checked_add(self.width as i64)
+            .ok_or(PolygonError::CoordinateOverflow)?;
+        let bottom = origin
+            .y
+            .checked_add(self.height as i64)
+            .ok_or(PolygonError::CoordinateOverflow)?;
+        Polygon::new(vec![
+            origin,
+            Point {
+                x: right,
+                y: origin.y,
+            },
+            Point {
+                x: right,
+                y: bottom,
+            },
+            Point {
+                x: origin.x,
+                y: bottom,
+            },
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn poly(points: &[(i64, i64)]) -> Result<Polygon, PolygonError> {
+        Polygon::new(points.iter().map(|&(x, y)| Point { x, y }).collect())
+    }
+
+    #[test]
+    fn unit_square() {
+        let p = poly(&[(0, 0), (1, 0), (1, 1), (0, 1)]).unwrap();
+        assert_eq!(p.area(), 1.0);
+        assert_eq!(p.perimeter(), 4.0);
+        assert!(p.is_convex());
+    }
+
+    #[test]
+    fn triangle_matches_half_base_times_height() {
+        let p = poly(&[(0, 0), (6, 0), (2, 5)]).unwrap();
+        assert_eq!(p.area(), 0.5 * 6.0 * 5.0);
+        assert!(p.is_convex());
+    }
+
+    #[test]
+    fn collinear_polygon_has_zero_area() {
+        let p = poly(&[(0, 0), (1, 1), (2, 2)]).unwrap();
+        assert_eq!(p.area(), 0.0);
+        assert!(!p.is_convex());
+    }
+
+    #[test]
+    fn concave_and_star_polygons_are_not_convex() {
+        let arrow = poly(&[(0, 0), (4, 0), (2, 1), (2, 4)]).unwrap();
+        assert!(!arrow.is_convex());
+        let star = poly(&[(0, 0), (2, 6), (4, 0), (-1, 4), (5, 4)]).unwrap();
+        assert!(!star.is_convex());
+    }
+
+    #[test]
+    fn extreme_coordinates() {
+        let big = 1 << 61;
+        let p = poly(&[(-big, -big), (big, -big), (big, big), (-big, big)]).unwrap();
+        let side = (2 * big) as f64;
+        assert_eq!(p.checked_area(), Ok(side * side));
+
+        let (lo, hi) = (i64::MIN, i64::MAX);
+        let p = poly(&[(lo, lo), (hi, lo), (hi, hi), (lo, hi)]).unwrap();
+        assert_eq!(p.checked_area(), Err(PolygonError::AreaOverflow));
+        let side = hi as f64 - lo as f64;
+        assert!((p.area() - side * side).abs() / (side * side) < 1e-12);
+    }
+
+    #[test]
+    fn constructor_errors() {
+        assert_eq!(
+            poly(&[(0, 0), (1, 1)]),
+            Err(PolygonError::TooFewVertices(2))
+        );
+        assert_eq!(
+            poly(&[(0, 0), (1, 0), (1, 0), (0, 1)]),
+            Err(PolygonError::DuplicateVertex { index: 2 })
+        );
+        assert_eq!(
+            poly(&[(0, 0), (1, 0), (0, 1), (0, 0)]),
+            Err(PolygonError::DuplicateVertex { index: 0 })
+        );
+    }
+
+    #[test]
+    fn rect_to_polygon() {
+        let r = Rect {
+            width: 3,
+            height: 2,
+        };
+        let p = r.to_polygon(Point { x: -1, y: 5 }).unwrap();
+        assert_eq!(p.area(), r.area());
+        assert_eq!(p.perimeter(), r.perimeter());
+        assert!(p.is_convex());
+        assert_eq!(p.vertices()[2], Point { x: 2, y: 7 });
+
+        let line = Rect {
+            width: 0,
+            height: 2,
+        };
+        assert!(line.to_polygon(Point { x: 0, y: 0 }).is_err());
+        assert_eq!(
+            r.to_polygon(Point { x: i64::MAX, y: 0 }),
+            Err(PolygonError::CoordinateOverflow)
+        );
+    }
+}
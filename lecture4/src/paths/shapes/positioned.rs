@@ -0,0 +1,361 @@
+use std::convert::TryFrom;
+
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// How `PositionedRect::clamp_within` brings a rect inside its bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClampPolicy {
+    /// Move the rect without resizing it; fails if it is larger than the
+    /// bounds.
+    Translate,
+    /// Shrink any dimension larger than the bounds, then move it inside.
+    Resize,
+    /// Keep only the part inside the bounds; fails if nothing is.
+    Clip,
+}
+
+/// A `Rect` placed at `origin`, its top-left corner. The rectangle covers
+/// the half-open ranges `x..x + width` and `y..y + height`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionedRect {
+    pub origin: Point,
+    pub size: Rect,
+}
+
+impl PositionedRect {
+    pub fn new(origin: Point, size: Rect) -> PositionedRect {
+        PositionedRect { origin, size }
+    }
+
+    /// Moves the rect by `dx`, `dy`, or `None` if the origin would overflow.
+    pub fn translate(&self, dx: i64, dy: i64) -> Option<PositionedRect> {
+        Some(PositionedRect {
+            origin: Point {
+                x: self.origin.x.checked_add(dx)?,
+                y: self.origin.y.checked_add(dy)?,
+            },
+            size: self.size,
+        })
+    }
+
+    // Edges are computed in i128 so rects near i64::MAX never overflow.
+    fn left(&self) -> i128 {
+        self.origin.x as i128
+    }
+
+    fn top(&self) -> i128 {
+        self.origin.y as i128
+    }
+
+    fn right(&self) -> i128 {
+        self.left() + self.size.width as i128
+    }
+
+    fn bottom(&self) -> i128 {
+        self.top() + self.size.height as i128
+    }
+
+    /// True when the two rects share some area. Rects that only touch along
+    /// an edge or a corner do not intersect, and neither does a zero-area
+    /// rect; see `intersects_inclusive`.
+    pub fn intersects(&self, other: &PositionedRect) -> bool {
+        self.left().max(other.left()) < self.right().min(other.right())
+            && self.top().max(other.top()) < self.bottom().min(other.bottom())
+    }
+
+    /// Like `intersects`, but rects sharing only a border also count.
+    pub fn intersects_inclusive(&self, other: &PositionedRect) -> bool {
+        self.left() <= other.right()
+            && other.left() <= self.right()
+            && self.top() <= other.bottom()
+            && other.top() <= self.bottom()
+    }
+
+    /// The overlapping region, if the rects intersect in the sense of
+    /// `intersects`.
+    pub fn intersection(&self, other: &PositionedRect) -> Option<PositionedRect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let left = self.left().max(other.left());
+        let top = self.top().max(other.top());
+        let right = self.right().min(other.right());
+        let bottom = self.bottom().min(other.bottom());
+        Some(PositionedRect {
+            origin: Point {
+                x: left as i64,
+                y: top as i64,
+            },
+            size: Rect {
+                width: (right - left) as u32,
+                height: (bottom - top) as u32,
+            },
+        })
+    }
+
+    pub fn contains_point(&self, p: Point) -> bool {
+        let (x, y) = (p.x as i128, p.y as i128);
+        self.left() <= x && x < self.right() && self.top() <= y && y < self.bottom()
+    }
+
+    /// The smallest rect covering both `self` and `other`, or `None` when its
+    /// width or height would not fit in a `u32`.
+    pub fn union(&self, other: &PositionedRect) -> Option<PositionedRect> {
+        let left = self.left().min(other.left());
+        let top = self.top().min(other.top());
+        let right = self.right().max(other.right());
+        let bottom = self.bottom().max(other.bottom());
+        Some(PositionedRect {
+            origin: Point {
+                x: left as i64,
+                y: top as i64,
+            },
+            size: Rect {
+                width: u32::try_from(right - left).ok()?,
+                height: u32::try_from(bottom - top).ok()?,
+            },
+        })
+    }
+
+    /// A copy of `self` that lies inside `bounds`, following `policy`. Rects
+    /// already inside are returned unchanged by every policy.
+    pub fn clamp_within(
+        &self,
+        bounds: &PositionedRect,
+        policy: ClampPolicy,
+    ) -> Option<PositionedRect> {
+        match policy {
+            ClampPolicy::Translate => {
+                if self.size.width > bounds.size.width || self.size.height > bounds.size.height {
+                    return None;
+                }
+                Some(self.moved_inside(bounds))
+            }
+            ClampPolicy::Resize => {
+                let shrunk = PositionedRect {
+                    origin: self.origin,
+                    size: Rect {
+                        width: self.size.width.min(bounds.size.width),
+                        height: self.size.height.min(bounds.size.height),
+                    },
+                };
+                Some(shrunk.moved_inside(bounds))
+            }
+            ClampPolicy::Clip => self.intersection(bounds),
+        }
+    }
+
+    // Assumes `self` is no larger than `bounds` in either dimension.
+    fn moved_inside(&self, bounds: &PositionedRect) -> PositionedRect {
+        let clamp = |start: i128, low: i128, high: i128| start.max(low).min(high) as i64;
+        PositionedRect {
+            origin: Point {
+                x: clamp(
+                    self.left(),
+                    bounds.left(),
+                    bounds.right() - self.size.width as i128,
+                ),
+                y: clamp(
+                    self.top(),
+                    bounds.top(),
+                    bounds.bottom() - self.size.height as i128,
+                ),
+            },
+            size: self.size,
+        }
+    }
+}
+
+impl Rect {
+    /// Places the rect with its top-left corner at `origin`.
+    pub fn at(&self, origin: Point) -> PositionedRect {
+        PositionedRect::new(origin, *self)
+    }
+}
+
+/// The union of all `rects`, or `None` when the slice is empty or the
+/// bounding box is too large for a `Rect`.
+pub fn bounding_box(rects: &[PositionedRect]) -> Option<PositionedRect> {
+    let (first, rest) = rects.split_first()?;
+    rest.iter().try_fold(*first, |acc, r| acc.union(r))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prect(x: i64, y: i64, width: u32, height: u32) -> PositionedRect {
+        PositionedRect::new(Point { x, y }, Rect { width, height })
+    }
+
+    #[test]
+    fn fully_contained() {
+        let outer = prect(0, 0, 10, 10);
+        let inner = prect(2, 3, 4, 4);
+        assert!(outer.intersects(&inner));
+        assert!(inner.intersects(&outer));
+        assert_eq!(outer.intersection(&inner), Some(inner));
+    }
+
+    #[test]
+    fn partially_overlapping() {
+        let a = prect(0, 0, 4, 4);
+        let b = prect(2, -1, 4, 4);
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(prect(2, 0, 2, 3)));
+        assert_eq!(a.intersection(&b), b.intersection(&a));
+    }
+
+    #[test]
+    fn disjoint() {
+        let a = prect(0, 0, 2, 2);
+        let b = prect(5, 5, 2, 2);
+        assert!(!a.intersects(&b));
+        assert!(!a.intersects_inclusive(&b));
+        assert_eq!(a.intersection(&b), None);
+    }
+
+    #[test]
+    fn edge_touching_only_counts_when_inclusive() {
+        let a = prect(0, 0, 2, 2);
+        let right = prect(2, 0, 2, 2);
+        let corner = prect(2, 2, 1, 1);
+        assert!(!a.intersects(&right));
+        assert!(a.intersects_inclusive(&right));
+        assert!(!a.intersects(&corner));
+        assert!(a.intersects_inclusive(&corner));
+    }
+
+    #[test]
+    fn degenerate_rects_never_overlap() {
+        let a = prect(0, 0, 4, 4);
+        let line = prect(1, 1, 0, 2);
+        assert!(!a.intersects(&line));
+        assert!(a.intersects_inclusive(&line));
+        assert_eq!(a.intersection(&line), None);
+        assert!(!line.contains_point(Point { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn contains_point_is_half_open() {
+        let a = prect(-2, -2, 4, 4);
+        assert!(a.contains_point(Point { x: -2, y: -2 }));
+        assert!(a.contains_point(Point { x: 1, y: 1 }));
+        assert!(!a.contains_point(Point { x: 2, y: 0 }));
+        assert!(!a.contains_point(Point { x: 0, y: 2 }));
+    }
+
+    #[test]
+    fn extreme_coordinates_do_not_overflow() {
+        let a = prect(i64::MAX - 1, i64::MAX - 1, u32::MAX, u32::MAX);
+        let b = prect(i64::MAX - 1, i64::MAX - 1, 1, 1);
+        assert!(a.intersects(&b));
+        assert!(a.contains_point(Point {
+            x: i64::MAX,
+            y: i64::MAX
+        }));
+    }
+
+    #[test]
+    fn union_with_negative_origins() {
+        let a = prect(-5, -3, 2, 2);
+        let b = prect(1, 1, 3, 1);
+        assert_eq!(a.union(&b), Some(prect(-5, -3, 9, 5)));
+        assert_eq!(a.union(&b), b.union(&a));
+        assert_eq!(a.union(&a), Some(a));
+    }
+
+    #[test]
+    fn union_of_widely_separated_rects() {
+        let a = prect(-2_000_000_000, 0, 1, 1);
+        let b = prect(2_000_000_000, 0, 1, 1);
+        assert_eq!(
+            a.union(&b),
+            Some(prect(-2_000_000_000, 0, 4_000_000_001, 1))
+        );
+
+        let far = prect(i64::MAX - 1, 0, 1, 1);
+        assert_eq!(a.union(&far), None);
+        let extreme = prect(i64::MIN, i64::MIN, 1, 1);
+        assert_eq!(extreme.union(&far), None);
+    }
+
+    #[test]
+    fn translate_policy() {
+        let bounds = prect(-10, -10, 20, 20);
+        let policy = ClampPolicy::Translate;
+        assert_eq!(bounds.clamp_within(&bounds, policy), Some(bounds));
+        assert_eq!(
+            prect(-15, 5, 4, 8).clamp_within(&bounds, policy),
+            Some(prect(-10, 2, 4, 8))
+        );
+        assert_eq!(
+            prect(100, -100, 3, 3).clamp_within(&bounds, policy),
+            Some(prect(7, -10, 3, 3))
+        );
+        assert_eq!(prect(0, 0, 21, 1).clamp_within(&bounds, policy), None);
+        assert_eq!(prect(0, 0, 1, 21).clamp_within(&bounds, policy), None);
+    }
+
+    #[test]
+    fn resize_policy() {
+        let bounds = prect(-10, -10, 20, 20);
+        let policy = ClampPolicy::Resize;
+        assert_eq!(bounds.clamp_within(&bounds, policy), Some(bounds));
+        assert_eq!(
+            prect(-15, 5, 4, 8).clamp_within(&bounds, policy),
+            Some(prect(-10, 2, 4, 8))
+        );
+        assert_eq!(
+            prect(-30, 0, 50, 5).clamp_within(&bounds, policy),
+            Some(prect(-10, 0, 20, 5))
+        );
+        assert_eq!(
+            prect(500, 500, 3, 30).clamp_within(&bounds, policy),
+            Some(prect(7, -10, 3, 20))
+        );
+    }
+
+    #[test]
+    fn clip_policy() {
+        let bounds = prect(-10, -10, 20, 20);
+        let policy = ClampPolicy::Clip;
+        assert_eq!(bounds.clamp_within(&bounds, policy), Some(bounds));
+        assert_eq!(prect(-15, 5, 4, 8).clamp_within(&bounds, policy), None);
+        assert_eq!(
+            prect(-12, 5, 4, 8).clamp_within(&bounds, policy),
+            Some(prect(-10, 5, 2, 5))
+        );
+        assert_eq!(
+            prect(-30, 0, 50, 5).clamp_within(&bounds, policy),
+            Some(prect(-10, 0, 20, 5))
+        );
+        assert_eq!(prect(500, 500, 3, 3).clamp_within(&bounds, policy), None);
+    }
+
+    #[test]
+    fn clamping_near_the_edges_of_i64() {
+        let bounds = prect(i64::MIN, i64::MIN, 10, 10);
+        let far = prect(i64::MAX - 5, i64::MAX - 5, 4, 4);
+        assert_eq!(
+            far.clamp_within(&bounds, ClampPolicy::Translate),
+            Some(prect(i64::MIN + 6, i64::MIN + 6, 4, 4))
+        );
+        assert_eq!(far.clamp_within(&bounds, ClampPolicy::Clip), None);
+    }
+
+    #[test]
+    fn bounding_box_of_slices() {
+        assert_eq!(bounding_box(&[]), None);
+        let a = prect(3, 4, 5, 6);
+        assert_eq!(bounding_box(&[a]), Some(a));
+        let rects = [prect(0, 0, 1, 1), prect(-10, 5, 2, 2), prect(7, -3, 1, 1)];
+        assert_eq!(bounding_box(&rects), Some(prect(-10, -3, 18, 10)));
+    }
+}
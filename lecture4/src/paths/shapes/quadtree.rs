@@ -0,0 +1,205 @@
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+pub const DEFAULT_CAPACITY: usize = 8;
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+fn contains(outer: &PositionedRect, inner: &PositionedRect) -> bool {
+    let edges = |r: &PositionedRect| {
+        let (x, y) = (r.origin.x as i128, r.origin.y as i128);
+        (x, y, x + r.size.width as i128, y + r.size.height as i128)
+    };
+    let (ol, ot, or, ob) = edges(outer);
+    let (il, it, ir, ib) = edges(inner);
+    ol <= il && ot <= it && ir <= or && ib <= ob
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: PositionedRect,
+    depth: usize,
+    items: Vec<PositionedRect>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(bounds: PositionedRect, depth: usize) -> Node {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn insert(&mut self, item: PositionedRect, capacity: usize, max_depth: usize) {
+        if let Some(children) = self.children.as_mut() {
+            if let Some(child) = children.iter_mut().find(|c| contains(&c.bounds, &item)) {
+                child.insert(item, capacity, max_depth);
+                return;
+            }
+        }
+        self.items.push(item);
+        if self.children.is_none() && self.items.len() > capacity && self.depth < max_depth {
+            self.split(capacity, max_depth);
+        }
+    }
+
+    fn split(&mut self, capacity: usize, max_depth: usize) {
+        let Rect { width, height } = self.bounds.size;
+        if width < 2 && height < 2 {
+            return;
+        }
+        let (left_w, top_h) = (width / 2, height / 2);
+        let (x, y) = (self.bounds.origin.x, self.bounds.origin.y);
+        let (mid_x, mid_y) = (x + left_w as i64, y + top_h as i64);
+        let quadrant = |x, y, width, height| {
+            Node::new(
+                PositionedRect::new(Point { x, y }, Rect { width, height }),
+                self.depth + 1,
+            )
+        };
+        self.children = Some(Box::new([
+            quadrant(x, y, left_w, top_h),
+            quadrant(mid_x, y, width - left_w, top_h),
+            quadrant(x, mid_y, left_w, height - top_h),
+            quadrant(mid_x, mid_y, width - left_w, height - top_h),
+        ]));
+        for item in std::mem::take(&mut self.items) {
+            self.insert(item, capacity, max_depth);
+        }
+    }
+
+    fn query<'a>(&'a self, region: &PositionedRect, found: &mut Vec<&'a PositionedRect>) {
+        found.extend(self.items.iter().filter(|item| item.intersects(region)));
+        if let Some(children) = self.children.as_ref() {
+            for child in children.iter() {
+                if child.bounds.intersects(region) {
+                    child.query(region, found);
+                }
+            }
+        }
+    }
+}
+
+/// A region quadtree of `PositionedRect`s.
+///
+/// A node splits into four quadrants once it holds more than `capacity`
+/// items, and each item moves into the quadrant that fully contains it.
+/// Items straddling a quadrant border, and items outside the tree's bounds,
+/// stay in the node above. Nodes at `max_depth` never split, so many
+/// identical rects cannot cause endless splitting.
+#[derive(Debug, Clone)]
+pub struct QuadTree {
+    root: Node,
+    capacity: usize,
+    max_depth: usize,
+    len: usize,
+}
+
+impl QuadTree {
+    pub fn new(bounds: PositionedRect) -> QuadTree {
+        QuadTree::with_limits(bounds, DEFAULT_CAPACITY, DEFAULT_MAX_DEPTH)
+    }
+
+    pub fn with_limits(bounds: PositionedRect, capacity: usize, max_depth: usize) -> QuadTree {
+        QuadTree {
+            root: Node::new(bounds, 0),
+            capacity,
+            max_depth,
+            len: 0,
+        }
+    }
+
+    pub fn insert(&mut self, item: PositionedRect) {
+        self.root.insert(item, self.capacity, self.max_depth);
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Every stored rect that shares some area with `region`, in the sense
+    /// of `PositionedRect::intersects`.
+    pub fn query_region(&self, region: &PositionedRect) -> Vec<&PositionedRect> {
+        let mut found = Vec::new();
+        self.root.query(region, &mut found);
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prect(x: i64, y: i64, width: u32, height: u32) -> PositionedRect {
+        PositionedRect::new(Point { x, y }, Rect { width, height })
+    }
+
+    fn sorted(rects: Vec<&PositionedRect>) -> Vec<(i64, i64, u32, u32)> {
+        let mut keys: Vec<_> = rects
+            .iter()
+            .map(|r| (r.origin.x, r.origin.y, r.size.width, r.size.height))
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut state: u64 = 42;
+        let mut next = |max: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % max
+        };
+        let mut tree = QuadTree::new(prect(0, 0, 1000, 1000));
+        let mut all = Vec::new();
+        for _ in 0..400 {
+            let r = prect(
+                next(1100) as i64 - 50,
+                next(1100) as i64 - 50,
+                next(60) as u32,
+                next(60) as u32,
+            );
+            tree.insert(r);
+            all.push(r);
+        }
+        assert_eq!(tree.len(), 400);
+
+        for _ in 0..50 {
+            let region = prect(
+                next(1000) as i64,
+                next(1000) as i64,
+                next(300) as u32 + 1,
+                next(300) as u32 + 1,
+            );
+            let expected: Vec<_> = all.iter().filter(|r| r.intersects(&region)).collect();
+            assert_eq!(sorted(tree.query_region(&region)), sorted(expected));
+        }
+    }
+
+    #[test]
+    fn identical_rects_stop_at_max_depth() {
+        let mut tree = QuadTree::with_limits(prect(0, 0, 64, 64), 2, 4);
+        for _ in 0..100 {
+            tree.insert(prect(1, 1, 1, 1));
+        }
+        assert_eq!(tree.len(), 100);
+        assert_eq!(tree.query_region(&prect(0, 0, 2, 2)).len(), 100);
+        assert!(tree.query_region(&prect(10, 10, 5, 5)).is_empty());
+    }
+
+    #[test]
+    fn empty_tree() {
+        let tree = QuadTree::new(prect(0, 0, 10, 10));
+        assert!(tree.is_empty());
+        assert!(tree.query_region(&prect(0, 0, 10, 10)).is_empty());
+    }
+}
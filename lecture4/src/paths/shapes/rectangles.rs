@@ -0,0 +1,626 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use super::ascii::{AsciiRenderer, RenderError};
+use super::tiles::{check_split, SplitError, TileIter};
+use super::{Numeric, Point, Shape};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RectError {
+    ZeroWidth,
+    ZeroHeight,
+    AreaOverflow,
+}
+
+impl fmt::Display for RectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RectError::ZeroWidth => write!(f, "width must be non-zero"),
+            RectError::ZeroHeight => write!(f, "height must be non-zero"),
+            RectError::AreaOverflow => write!(f, "area does not fit in a u32"),
+        }
+    }
+}
+
+impl std::error::Error for RectError {}
+
+/// A rectangle measured in `T`, which defaults to `u32`, so a bare `Rect`
+/// keeps meaning `Rect<u32>`.
+///
+/// Equality is structural: a 2x3 and a 3x2 rect are different. Ordering
+/// compares area first and breaks ties by width, then height, so two rects
+/// only compare `Equal` when they are `==`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect<T = u32> {
+    pub width: T,
+    pub height: T,
+}
+
+pub type RectU32 = Rect<u32>;
+
+/// `get_area` and `get_perimeter` use the plain operators, so integer
+/// overflow panics in debug builds and silently wraps in release builds
+/// (e.g. a 65536x65536 `Rect<u32>` has a release-mode area of 0), and a NaN
+/// float dimension makes both results NaN. Use the `_checked` variants to
+/// detect either case, or `get_area_u64`/`get_perimeter_u64` on `Rect<u32>`.
+impl<T: Numeric> Rect<T> {
+    pub fn get_area(&self) -> T {
+        self.width * self.height
+    }
+
+    pub fn get_perimeter(&self) -> T {
+        (self.width + self.height) + (self.width + self.height)
+    }
+
+    pub fn get_area_checked(&self) -> Option<T> {
+        self.width.checked_mul(self.height)
+    }
+
+    pub fn get_perimeter_checked(&self) -> Option<T> {
+        let half = self.width.checked_add(self.height)?;
+        half.checked_add(half)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+
+    pub fn is_landscape(&self) -> bool {
+        self.width > self.height
+    }
+
+    pub fn is_portrait(&self) -> bool {
+        self.height > self.width
+    }
+
+    /// Computed in `f64`, so it cannot overflow.
+    pub fn diagonal(&self) -> f64 {
+        self.width.to_f64().hypot(self.height.to_f64())
+    }
+
+    /// Width divided by height. A zero height gives infinity, or NaN when
+    /// the width is zero too.
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width.to_f64() / self.height.to_f64()
+    }
+}
+
+impl Rect {
+    pub const UNIT: Rect = Rect {
+        width: 1,
+        height: 1,
+    };
+    pub const ZERO: Rect = Rect {
+        width: 0,
+        height: 0,
+    };
+
+    pub const fn unit() -> Rect {
+        Rect::UNIT
+    }
+
+    pub const fn square(side: u32) -> Rect {
+        Rect {
+            width: side,
+            height: side,
+        }
+    }
+
+    /// A `width` x 1 strip.
+    pub const fn wide(width: u32) -> Rect {
+        Rect { width, height: 1 }
+    }
+
+    /// A 1 x `height` strip.
+    pub const fn tall(height: u32) -> Rect {
+        Rect { width: 1, height }
+    }
+
+    /// The area widened to `u64`, which can hold any product of two `u32`s.
+    pub fn get_area_u64(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// The perimeter widened to `u64`; never overflows.
+    pub fn get_perimeter_u64(&self) -> u64 {
+        2 * (self.width as u64 + self.height as u64)
+    }
+
+    /// Scales the rect down, keeping its aspect ratio as closely as whole
+    /// units allow, until it fits inside `bounds`. Rects that already fit are
+    /// returned unchanged. The free dimension is always rounded down, so the
+    /// result never exceeds `bounds`.
+    pub fn fit_within(&self, bounds: &Rect) -> Rect {
+        if self.width <= bounds.width && self.height <= bounds.height {
+            return *self;
+        }
+        if self.width == 0 || self.height == 0 {
+            return Rect {
+                width: self.width.min(bounds.width),
+                height: self.height.min(bounds.height),
+            };
+        }
+        let (w, h) = (self.width as u64, self.height as u64);
+        let (bw, bh) = (bounds.width as u64, bounds.height as u64);
+        if bw * h <= bh * w {
+            Rect {
+                width: bounds.width,
+                height: (h * bw / w) as u32,
+            }
+        } else {
+            Rect {
+                width: (w * bh / h) as u32,
+                height: bounds.height,
+            }
+        }
+    }
+
+    /// Cuts across the height, returning the top part of height `at` and
+    /// the bottom part.
+    pub fn split_horizontal(&self, at: u32) -> Result<(Rect, Rect), SplitError> {
+        check_split(at, self.height)?;
+        Ok((
+            Rect {
+                width: self.width,
+                height: at,
+            },
+            Rect {
+                width: self.width,
+                height: self.height - at,
+            },
+        ))
+    }
+
+    /// Cuts across the width, returning the left part of width `at` and the
+    /// right part.
+    pub fn split_vertical(&self, at: u32) -> Result<(Rect, Rect), SplitError> {
+        check_split(at, self.width)?;
+        Ok((
+            Rect {
+                width: at,
+                height: self.height,
+            },
+            Rect {
+                width: self.width - at,
+                height: self.height,
+            },
+        ))
+    }
+
+    /// Cuts the rect into `tile_w` x `tile_h` tiles; see `TileIter`. A zero
+    /// tile dimension yields no tiles.
+    pub fn tile(&self, tile_w: u32, tile_h: u32) -> TileIter {
+        TileIter::new(*self, tile_w, tile_h)
+    }
+
+    /// Draws the rect with the default `AsciiRenderer`, which refuses
+    /// anything beyond 200x200.
+    pub fn render_ascii(&self) -> Result<String, RenderError> {
+        AsciiRenderer::default().render(self)
+    }
+
+    pub fn render_ascii_into(&self, w: &mut impl fmt::Write) -> Result<(), RenderError> {
+        AsciiRenderer::default().render_into(self, w)
+    }
+}
+
+/// The 1x1 unit rect.
+impl Default for Rect {
+    fn default() -> Rect {
+        Rect::UNIT
+    }
+}
+
+impl From<(u32, u32)> for Rect {
+    fn from((width, height): (u32, u32)) -> Rect {
+        Rect { width, height }
+    }
+}
+
+impl From<[u32; 2]> for Rect {
+    fn from([width, height]: [u32; 2]) -> Rect {
+        Rect { width, height }
+    }
+}
+
+impl From<Rect> for (u32, u32) {
+    fn from(rect: Rect) -> (u32, u32) {
+        (rect.width, rect.height)
+    }
+}
+
+impl From<Rect> for [u32; 2] {
+    fn from(rect: Rect) -> [u32; 2] {
+        [rect.width, rect.height]
+    }
+}
+
+impl Ord for Rect {
+    fn cmp(&self, other: &Rect) -> Ordering {
+        let area = |r: &Rect| r.width as u64 * r.height as u64;
+        area(self)
+            .cmp(&area(other))
+            .then(self.width.cmp(&other.width))
+            .then(self.height.cmp(&other.height))
+    }
+}
+
+impl PartialOrd for Rect {
+    fn partial_cmp(&self, other: &Rect) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats as `WIDTHxHEIGHT`, e.g. `5x6`.
+impl<T: fmt::Display> fmt::Display for Rect<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseRectError {
+    MissingSeparator,
+    InvalidNumber,
+    TrailingGarbage,
+}
+
+impl fmt::Display for ParseRectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRectError::MissingSeparator => write!(f, "expected WIDTHxHEIGHT"),
+            ParseRectError::InvalidNumber => write!(f, "invalid dimension"),
+            ParseRectError::TrailingGarbage => write!(f, "unexpected input after height"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRectError {}
+
+/// Accepts `5x6`, `5 x 6` and `5X6`. Anything left over after the height,
+/// once separated from it by whitespace or another `x`, is
+/// `TrailingGarbage`; characters glued onto a number make it an
+/// `InvalidNumber`.
+impl<T: FromStr> FromStr for Rect<T> {
+    type Err = ParseRectError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let is_sep = |c: char| c == 'x' || c == 'X';
+        let (width, rest) = s
+            .split_once(is_sep)
+            .ok_or(ParseRectError::MissingSeparator)?;
+        let rest = rest.trim_start();
+        let end = rest
+            .find(|c: char| c.is_whitespace() || is_sep(c))
+            .unwrap_or(rest.len());
+        let (height, garbage) = rest.split_at(end);
+
+        let width = width
+            .trim()
+            .parse()
+            .map_err(|_| ParseRectError::InvalidNumber)?;
+        let height = height.parse().map_err(|_| ParseRectError::InvalidNumber)?;
+        if !garbage.trim().is_empty() {
+            return Err(ParseRectError::TrailingGarbage);
+        }
+        Ok(Rect { width, height })
+    }
+}
+
+/// `u32` dimensions convert to `f64` exactly. Their product can exceed 2^53
+/// when both are close to `u32::MAX`, in which case `area` is rounded to the
+/// nearest representable `f64` instead of wrapping like `get_area`.
+impl<T: Numeric> Shape for Rect<T> {
+    fn area(&self) -> f64 {
+        self.width.to_f64() * self.height.to_f64()
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (self.width.to_f64() + self.height.to_f64())
+    }
+
+    fn name(&self) -> &'static str {
+        "rect"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn fmt_shape(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+
+    /// `origin` is the top-left corner.
+    fn to_svg_element(&self, origin: Point) -> String {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+            origin.x,
+            origin.y,
+            self.width.to_f64(),
+            self.height.to_f64()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape_area_does_not_wrap() {
+        let r = Rect {
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        let exact = u32::MAX as u128 * u32::MAX as u128;
+        assert!((r.area() - exact as f64).abs() <= 1.0);
+        assert_eq!(r.perimeter(), 4.0 * u32::MAX as f64);
+        assert_eq!(r.name(), "rect");
+    }
+
+    #[test]
+    fn u32_rect() {
+        let r: RectU32 = Rect {
+            width: 5,
+            height: 6,
+        };
+        assert_eq!(r.get_area(), 30);
+        assert_eq!(r.get_perimeter(), 22);
+        assert_eq!(
+            Rect {
+                width: 65536u32,
+                height: 65536
+            }
+            .get_area_checked(),
+            None
+        );
+    }
+
+    #[test]
+    fn u64_rect() {
+        let r = Rect {
+            width: 65536u64,
+            height: 65536,
+        };
+        assert_eq!(r.get_area(), 1 << 32);
+        assert_eq!(r.get_area_checked(), Some(1 << 32));
+        let big = Rect {
+            width: u64::MAX,
+            height: 1,
+        };
+        assert_eq!(big.get_perimeter_checked(), None);
+    }
+
+    #[test]
+    fn f64_rect() {
+        let r = Rect {
+            width: 1.5f64,
+            height: 2.0,
+        };
+        assert_eq!(r.get_area(), 3.0);
+        assert_eq!(r.get_perimeter(), 7.0);
+
+        let nan = Rect {
+            width: f64::NAN,
+            height: 2.0,
+        };
+        assert!(nan.get_area().is_nan());
+        assert_eq!(nan.get_area_checked(), None);
+
+        let huge = Rect {
+            width: f64::MAX,
+            height: 2.0,
+        };
+        assert_eq!(huge.get_area_checked(), None);
+    }
+
+    #[test]
+    fn display_and_parse() {
+        let r = Rect {
+            width: 5u32,
+            height: 6,
+        };
+        assert_eq!(r.to_string(), "5x6");
+        assert_eq!("5x6".parse::<Rect>(), Ok(r));
+        assert_eq!("5 x 6".parse::<Rect>(), Ok(r));
+        assert_eq!("5X6".parse::<Rect>(), Ok(r));
+        assert_eq!(" 5x6 ".parse::<Rect>(), Ok(r));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("56".parse::<Rect>(), Err(ParseRectError::MissingSeparator));
+        assert_eq!("".parse::<Rect>(), Err(ParseRectError::MissingSeparator));
+        assert_eq!("ax6".parse::<Rect>(), Err(ParseRectError::InvalidNumber));
+        assert_eq!("5x".parse::<Rect>(), Err(ParseRectError::InvalidNumber));
+        assert_eq!("-5x6".parse::<Rect>(), Err(ParseRectError::InvalidNumber));
+        assert_eq!(
+            "5x6x7".parse::<Rect>(),
+            Err(ParseRectError::TrailingGarbage)
+        );
+        assert_eq!(
+            "5x6 7".parse::<Rect>(),
+            Err(ParseRectError::TrailingGarbage)
+        );
+    }
+
+    #[test]
+    fn display_parse_round_trip() {
+        let samples = [0, 1, 2, 7, 10, 99, 1000, 65535, 65536, u32::MAX];
+        for &w in samples.iter() {
+            for &h in samples.iter() {
+                let r = Rect {
+                    width: w,
+                    height: h,
+                };
+                assert_eq!(format!("{}", r).parse::<Rect>(), Ok(r));
+            }
+        }
+        let f = Rect {
+            width: 1.5f64,
+            height: 0.25,
+        };
+        assert_eq!(f.to_string().parse::<Rect<f64>>(), Ok(f));
+    }
+
+    #[test]
+    fn ordering_is_by_area_then_width() {
+        let r = |width, height| Rect { width, height };
+        assert!(r(1, 5) < r(2, 3));
+        assert!(r(2, 3) < r(3, 2));
+        assert!(r(0, 3) < r(0, 5));
+        assert_eq!(r(2, 3).cmp(&r(2, 3)), Ordering::Equal);
+        assert!(r(u32::MAX, u32::MAX) > r(u32::MAX, u32::MAX - 1));
+    }
+
+    #[test]
+    fn area_at_the_overflow_boundary() {
+        let below: Rect = Rect {
+            width: 65535,
+            height: 65536,
+        };
+        assert_eq!(below.get_area_checked(), Some(4_294_901_760));
+        assert_eq!(below.get_area(), 4_294_901_760);
+
+        let at = Rect {
+            width: 65536,
+            height: 65536,
+        };
+        assert_eq!(at.get_area_checked(), None);
+        assert_eq!(at.get_area_u64(), 1 << 32);
+        assert_eq!(at.width.wrapping_mul(at.height), 0);
+
+        let max = Rect {
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        assert_eq!(max.get_area_u64(), u32::MAX as u64 * u32::MAX as u64);
+    }
+
+    #[test]
+    fn perimeter_at_the_overflow_boundary() {
+        let fits = Rect {
+            width: u32::MAX / 4,
+            height: u32::MAX / 4,
+        };
+        assert_eq!(fits.get_perimeter_checked(), Some(u32::MAX / 4 * 4));
+
+        let over = Rect {
+            width: u32::MAX / 2,
+            height: 1,
+        };
+        assert_eq!(over.get_perimeter_checked(), None);
+        assert_eq!(over.get_perimeter_u64(), u32::MAX as u64 + 1);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn get_area_panics_on_overflow_in_debug() {
+        Rect {
+            width: 65536u32,
+            height: 65536,
+        }
+        .get_area();
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn get_area_wraps_on_overflow_in_release() {
+        let r = Rect {
+            width: 65536u32,
+            height: 65536,
+        };
+        assert_eq!(r.get_area(), 0);
+    }
+
+    #[test]
+    fn diagonal_and_aspect_ratio() {
+        let r: Rect = Rect {
+            width: 3,
+            height: 4,
+        };
+        assert_eq!(r.diagonal(), 5.0);
+        assert_eq!(r.aspect_ratio(), 0.75);
+        assert!(r.is_portrait() && !r.is_landscape());
+
+        let max: Rect = Rect {
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        assert!((max.diagonal() - u32::MAX as f64 * 2f64.sqrt()).abs() < 1.0);
+
+        let flat: Rect = Rect {
+            width: 2,
+            height: 0,
+        };
+        assert!(flat.aspect_ratio().is_infinite() && flat.is_landscape());
+        let empty: Rect = Rect {
+            width: 0,
+            height: 0,
+        };
+        assert!(empty.aspect_ratio().is_nan());
+        assert!(!empty.is_landscape() && !empty.is_portrait());
+    }
+
+    #[test]
+    fn fit_within_examples() {
+        let r = |width, height| Rect { width, height };
+        assert_eq!(r(1920, 1080).fit_within(&r(640, 640)), r(640, 360));
+        assert_eq!(r(100, 300).fit_within(&r(50, 50)), r(16, 50));
+        assert_eq!(r(10, 10).fit_within(&r(50, 50)), r(10, 10));
+        assert_eq!(r(0, 10).fit_within(&r(0, 3)), r(0, 3));
+        assert_eq!(r(10, 10).fit_within(&r(0, 3)), r(0, 0));
+    }
+
+    #[test]
+    fn fit_within_never_exceeds_bounds() {
+        let mut gen = super::super::testing::RectGen::new(19).allow_degenerate(true);
+        for _ in 0..10_000 {
+            let rect = gen.next_rect(5000, 5000);
+            let bounds = gen.next_rect(5000, 5000);
+            let fitted = rect.fit_within(&bounds);
+            assert!(fitted.width <= bounds.width && fitted.height <= bounds.height);
+            assert!(fitted.width <= rect.width && fitted.height <= rect.height);
+        }
+    }
+
+    #[test]
+    fn constants_and_named_constructors() {
+        assert_eq!(Rect::default(), Rect::UNIT);
+        assert_eq!(Rect::unit(), Rect::square(1));
+        assert_eq!(Rect::ZERO.get_area(), 0);
+        assert_eq!(Rect::square(4), Rect::from((4, 4)));
+        assert_eq!(Rect::wide(7), Rect::from((7, 1)));
+        assert_eq!(Rect::tall(7), Rect::from((1, 7)));
+    }
+
+    #[test]
+    fn tuple_and_array_conversions() {
+        let r = Rect::from((5, 6));
+        assert_eq!(Rect::from([5, 6]), r);
+        let t: (u32, u32) = r.into();
+        let a: [u32; 2] = r.into();
+        assert_eq!(t, (5, 6));
+        assert_eq!(a, [5, 6]);
+        assert_eq!(Rect::from(t), r);
+        assert_eq!(Rect::from(a), r);
+    }
+
+    #[test]
+    fn rect_as_hash_map_key() {
+        use std::collections::HashMap;
+
+        let mut names = HashMap::new();
+        names.insert(Rect::UNIT, "unit");
+        names.insert(Rect::from((2, 3)), "2x3");
+        names.insert(Rect::from((3, 2)), "3x2");
+        names.insert(Rect::square(1), "square");
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[&Rect::default()], "square");
+        assert_eq!(names[&Rect::from([3, 2])], "3x2");
+    }
+}
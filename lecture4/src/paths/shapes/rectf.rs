@@ -0,0 +1,175 @@
+use std::fmt;
+
+use super::rectangles::Rect;
+use super::transform::Rounding;
+
+/// A rect with floating point dimensions for geometry math.
+pub type RectF = Rect<f64>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConversionError {
+    NotANumber,
+    Negative(f64),
+    TooLarge(f64),
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConversionError::NotANumber => write!(f, "dimension is NaN"),
+            ConversionError::Negative(v) => write!(f, "dimension {} is negative", v),
+            ConversionError::TooLarge(v) => write!(f, "dimension {} does not fit in a u32", v),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError {}
+
+fn to_u32(value: f64, rounding: Rounding) -> Result<u32, ConversionError> {
+    if value.is_nan() {
+        return Err(ConversionError::NotANumber);
+    }
+    let rounded = match rounding {
+        Rounding::Floor => value.floor(),
+        Rounding::Ceil => value.ceil(),
+        Rounding::Nearest => value.round(),
+    };
+    if rounded < 0.0 {
+        Err(ConversionError::Negative(value))
+    } else if rounded > u32::MAX as f64 {
+        Err(ConversionError::TooLarge(value))
+    } else {
+        Ok(rounded as u32)
+    }
+}
+
+impl RectF {
+    /// Rounds each dimension to a whole number of units. A value that
+    /// rounds to a negative number or past `u32::MAX` is rejected, so
+    /// `-0.4` rounds to 0 with `Nearest` but is `Negative` with `Floor`.
+    pub fn to_rect(&self, rounding: Rounding) -> Result<Rect, ConversionError> {
+        Ok(Rect {
+            width: to_u32(self.width, rounding)?,
+            height: to_u32(self.height, rounding)?,
+        })
+    }
+
+    /// True when both dimensions differ by at most `epsilon`. NaN is never
+    /// approximately equal to anything, itself included.
+    pub fn approx_eq(&self, other: &RectF, epsilon: f64) -> bool {
+        (self.width - other.width).abs() <= epsilon && (self.height - other.height).abs() <= epsilon
+    }
+}
+
+impl From<Rect> for RectF {
+    fn from(rect: Rect) -> RectF {
+        Rect {
+            width: rect.width as f64,
+            height: rect.height as f64,
+        }
+    }
+}
+
+/// Asserts that two `RectF`s are equal within an epsilon, which defaults to
+/// `1e-9`.
+#[macro_export]
+macro_rules! assert_rect_approx_eq {
+    ($left:expr, $right:expr) => {
+        $crate::assert_rect_approx_eq!($left, $right, 1e-9)
+    };
+    ($left:expr, $right:expr, $epsilon:expr) => {{
+        let (left, right, epsilon) = (&$left, &$right, $epsilon);
+        assert!(
+            left.approx_eq(right, epsilon),
+            "rects differ by more than {}: {:?} vs {:?}",
+            epsilon,
+            left,
+            right
+        );
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rectf(width: f64, height: f64) -> RectF {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn approx_eq() {
+        assert!(rectf(1.0, 2.0).approx_eq(&rectf(1.0 + 1e-12, 2.0), 1e-9));
+        assert!(!rectf(1.0, 2.0).approx_eq(&rectf(1.1, 2.0), 1e-9));
+        assert_rect_approx_eq!(rectf(0.1 + 0.2, 1.0), rectf(0.3, 1.0));
+        assert_rect_approx_eq!(rectf(1.0, 1.0), rectf(1.05, 0.95), 0.1);
+    }
+
+    #[test]
+    fn nan_is_never_equal() {
+        let nan = rectf(f64::NAN, 1.0);
+        assert!(!nan.approx_eq(&nan, f64::INFINITY));
+        assert!(nan != nan);
+    }
+
+    #[test]
+    #[should_panic(expected = "rects differ by more than")]
+    fn assert_macro_panics_on_mismatch() {
+        assert_rect_approx_eq!(rectf(1.0, 1.0), rectf(1.0, 2.0));
+    }
+
+    #[test]
+    fn rounding_ties() {
+        let r = rectf(0.5, 2.5);
+        assert_eq!(r.to_rect(Rounding::Floor), Ok(Rect::from((0, 2))));
+        assert_eq!(r.to_rect(Rounding::Ceil), Ok(Rect::from((1, 3))));
+        assert_eq!(r.to_rect(Rounding::Nearest), Ok(Rect::from((1, 3))));
+    }
+
+    #[test]
+    fn conversion_errors() {
+        assert_eq!(
+            rectf(f64::NAN, 1.0).to_rect(Rounding::Nearest),
+            Err(ConversionError::NotANumber)
+        );
+        assert_eq!(
+            rectf(1.0, -1.0).to_rect(Rounding::Nearest),
+            Err(ConversionError::Negative(-1.0))
+        );
+        assert_eq!(rectf(-0.4, 0.0).to_rect(Rounding::Nearest), Ok(Rect::ZERO));
+        assert_eq!(
+            rectf(-0.4, 0.0).to_rect(Rounding::Floor),
+            Err(ConversionError::Negative(-0.4))
+        );
+    }
+
+    #[test]
+    fn values_around_u32_max() {
+        let max = u32::MAX as f64;
+        assert_eq!(
+            rectf(max, 0.0).to_rect(Rounding::Ceil),
+            Ok(Rect::from((u32::MAX, 0)))
+        );
+        assert_eq!(
+            rectf(max + 0.25, 0.0).to_rect(Rounding::Floor),
+            Ok(Rect::from((u32::MAX, 0)))
+        );
+        assert_eq!(
+            rectf(max + 0.25, 0.0).to_rect(Rounding::Ceil),
+            Err(ConversionError::TooLarge(max + 0.25))
+        );
+        assert_eq!(
+            rectf(max + 1.0, 0.0).to_rect(Rounding::Floor),
+            Err(ConversionError::TooLarge(max + 1.0))
+        );
+        assert_eq!(
+            rectf(0.0, f64::INFINITY).to_rect(Rounding::Floor),
+            Err(ConversionError::TooLarge(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn from_integer_rect() {
+        assert_eq!(RectF::from(Rect::from((3, 4))), rectf(3.0, 4.0));
+    }
+}
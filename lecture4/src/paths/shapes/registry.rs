@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use super::rectangles::Rect;
+use super::{Shape, ShapeError};
+
+pub type ShapeFactory = Box<dyn Fn(&[f64]) -> Result<Box<dyn Shape>, ShapeError>>;
+
+fn expect_arity(args: &[f64], expected: usize) -> Result<(), ShapeError> {
+    if args.len() == expected {
+        Ok(())
+    } else {
+        Err(ShapeError::ArityMismatch {
+            expected,
+            got: args.len(),
+        })
+    }
+}
+
+/// Accepts only whole numbers that fit in a `u32`.
+fn dimension(value: f64) -> Result<u32, ShapeError> {
+    if value.fract() == 0.0 && value >= 0.0 && value <= u32::MAX as f64 {
+        Ok(value as u32)
+    } else {
+        Err(ShapeError::InvalidDimension(value))
+    }
+}
+
+/// Builds shapes by name. `"rect"` (width, height) and `"square"` (side)
+/// are registered up front. Registering a name that is already taken is an
+/// error unless overrides have been allowed.
+pub struct Registry {
+    factories: HashMap<String, ShapeFactory>,
+    allow_overrides: bool,
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        let mut registry = Registry::empty();
+        registry
+            .register("rect", |args| {
+                expect_arity(args, 2)?;
+                Ok(Box::new(Rect {
+                    width: dimension(args[0])?,
+                    height: dimension(args[1])?,
+                }))
+            })
+            .unwrap();
+        registry
+            .register("square", |args| {
+                expect_arity(args, 1)?;
+                Ok(Box::new(Rect::square(dimension(args[0])?)))
+            })
+            .unwrap();
+        registry
+    }
+
+    /// A registry without the built-in shapes.
+    pub fn empty() -> Registry {
+        Registry {
+            factories: HashMap::new(),
+            allow_overrides: false,
+        }
+    }
+
+    pub fn allow_overrides(&mut self, allow: bool) {
+        self.allow_overrides = allow;
+    }
+
+    pub fn register<F>(&mut self, name: &str, factory: F) -> Result<(), ShapeError>
+    where
+        F: Fn(&[f64]) -> Result<Box<dyn Shape>, ShapeError> + 'static,
+    {
+        if !self.allow_overrides && self.factories.contains_key(name) {
+            return Err(ShapeError::AlreadyRegistered(name.to_string()));
+        }
+        self.factories.insert(name.to_string(), Box::new(factory));
+        Ok(())
+    }
+
+    pub fn create(&self, name: &str, args: &[f64]) -> Result<Box<dyn Shape>, ShapeError> {
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| ShapeError::UnknownShape(name.to_string()))?;
+        factory(args)
+    }
+
+    /// Parses a spec such as `rect 5 6`: a name followed by
+    /// whitespace-separated numeric arguments.
+    pub fn create_from_str(&self, spec: &str) -> Result<Box<dyn Shape>, ShapeError> {
+        let mut words = spec.split_whitespace();
+        let name = words.next().unwrap_or("");
+        let args = words
+            .map(|w| {
+                w.parse()
+                    .map_err(|_| ShapeError::InvalidArgument(w.to_string()))
+            })
+            .collect::<Result<Vec<f64>, ShapeError>>()?;
+        self.create(name, &args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::circles::Circle;
+    use super::*;
+
+    #[test]
+    fn builtins() {
+        let registry = Registry::new();
+        let rect = registry.create("rect", &[5.0, 6.0]).unwrap();
+        assert_eq!(rect.as_any().downcast_ref(), Some(&Rect::from((5, 6))));
+        let square = registry.create("square", &[3.0]).unwrap();
+        assert_eq!(square.area(), 9.0);
+        assert_eq!(registry.create_from_str(" rect 2  4 ").unwrap().area(), 8.0);
+    }
+
+    #[test]
+    fn unknown_names() {
+        let registry = Registry::new();
+        assert_eq!(
+            registry.create("circle", &[1.0]).unwrap_err(),
+            ShapeError::UnknownShape("circle".to_string())
+        );
+        assert_eq!(
+            registry.create_from_str("").unwrap_err(),
+            ShapeError::UnknownShape(String::new())
+        );
+        assert!(Registry::empty().create("rect", &[1.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn argument_errors() {
+        let registry = Registry::new();
+        assert_eq!(
+            registry.create("rect", &[5.0]).unwrap_err(),
+            ShapeError::ArityMismatch {
+                expected: 2,
+                got: 1
+            }
+        );
+        assert_eq!(
+            registry.create("square", &[]).unwrap_err(),
+            ShapeError::ArityMismatch {
+                expected: 1,
+                got: 0
+            }
+        );
+        assert_eq!(
+            registry.create("rect", &[1.5, 2.0]).unwrap_err(),
+            ShapeError::InvalidDimension(1.5)
+        );
+        assert_eq!(
+            registry.create("square", &[-1.0]).unwrap_err(),
+            ShapeError::InvalidDimension(-1.0)
+        );
+        assert_eq!(
+            registry.create_from_str("rect 5 six").unwrap_err(),
+            ShapeError::InvalidArgument("six".to_string())
+        );
+    }
+
+    #[test]
+    fn user_registered_shapes() {
+        let mut registry = Registry::new();
+        registry
+            .register("circle", |args| {
+                expect_arity(args, 1)?;
+                Ok(Box::new(Circle::new(args[0])?))
+            })
+            .unwrap();
+        assert_eq!(registry.create("circle", &[0.0]).unwrap().name(), "circle");
+        assert_eq!(
+            registry.create("circle", &[-2.0]).unwrap_err(),
+            ShapeError::InvalidRadius(-2.0)
+        );
+    }
+
+    #[test]
+    fn overriding_builtins_needs_permission() {
+        let mut registry = Registry::new();
+        let unit = |_: &[f64]| -> Result<Box<dyn Shape>, ShapeError> { Ok(Box::new(Rect::UNIT)) };
+        assert_eq!(
+            registry.register("rect", unit),
+            Err(ShapeError::AlreadyRegistered("rect".to_string()))
+        );
+        assert_eq!(registry.create("rect", &[5.0, 6.0]).unwrap().area(), 30.0);
+
+        registry.allow_overrides(true);
+        registry.register("rect", unit).unwrap();
+        assert_eq!(registry.create("rect", &[]).unwrap().area(), 1.0);
+    }
+}
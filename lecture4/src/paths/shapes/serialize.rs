@@ -0,0 +1,305 @@
+//! Hand-written JSON for shapes, e.g. `{"width":5,"height":6}` for a `Rect`
+//! and `{"radius":2.5}` for a `Circle`. Only flat objects with numeric
+//! values are understood.
+
+use std::fmt;
+
+use super::circles::Circle;
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnexpectedChar(char),
+    UnexpectedEnd,
+    UnknownField(String),
+    DuplicateField(String),
+    MissingField(&'static str),
+    InvalidNumber,
+    InvalidValue,
+}
+
+/// A parse failure at byte `offset` of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ShapeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected {:?}", c)?,
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input")?,
+            ParseErrorKind::UnknownField(name) => write!(f, "unknown field {:?}", name)?,
+            ParseErrorKind::DuplicateField(name) => write!(f, "duplicate field {:?}", name)?,
+            ParseErrorKind::MissingField(name) => write!(f, "missing field {:?}", name)?,
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number")?,
+            ParseErrorKind::InvalidValue => write!(f, "value out of range for this shape")?,
+        }
+        write!(f, " at byte {}", self.offset)
+    }
+}
+
+impl std::error::Error for ShapeParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    OpenBrace,
+    CloseBrace,
+    Colon,
+    Comma,
+    Str(&'a str),
+    Number(&'a str),
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Tokenizer<'a> {
+        Tokenizer { input, pos: 0 }
+    }
+
+    fn error(&self, offset: usize, kind: ParseErrorKind) -> ShapeParseError {
+        ShapeParseError { offset, kind }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// The next token and the byte offset it starts at, or `None` at the
+    /// end of the input.
+    fn next_token(&mut self) -> Result<Option<(usize, Token<'a>)>, ShapeParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let c = match self.input[start..].chars().next() {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let token = match c {
+            '{' => Token::OpenBrace,
+            '}' => Token::CloseBrace,
+            ':' => Token::Colon,
+            ',' => Token::Comma,
+            '"' => {
+                let len = self.input[start + 1..]
+                    .find('"')
+                    .ok_or_else(|| self.error(self.input.len(), ParseErrorKind::UnexpectedEnd))?;
+                self.pos = start + 1 + len + 1;
+                return Ok(Some((
+                    start,
+                    Token::Str(&self.input[start + 1..start + 1 + len]),
+                )));
+            }
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '.' => {
+                let len = self.input[start..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+                    .unwrap_or(self.input.len() - start);
+                self.pos = start + len;
+                return Ok(Some((
+                    start,
+                    Token::Number(&self.input[start..start + len]),
+                )));
+            }
+            c => return Err(self.error(start, ParseErrorKind::UnexpectedChar(c))),
+        };
+        self.pos += c.len_utf8();
+        Ok(Some((start, token)))
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), ShapeParseError> {
+        match self.next_token()? {
+            Some((_, ref t)) if *t == expected => Ok(()),
+            other => Err(self.unexpected(other)),
+        }
+    }
+
+    fn unexpected(&self, found: Option<(usize, Token)>) -> ShapeParseError {
+        match found {
+            Some((offset, _)) => {
+                let c = self.input[offset..].chars().next().unwrap();
+                self.error(offset, ParseErrorKind::UnexpectedChar(c))
+            }
+            None => self.error(self.input.len(), ParseErrorKind::UnexpectedEnd),
+        }
+    }
+}
+
+/// Parses a flat object whose keys are exactly `fields`, returning each
+/// field's raw number text and its offset, in the order of `fields`.
+fn parse_object<'a>(
+    input: &'a str,
+    fields: &[&'static str],
+) -> Result<Vec<(usize, &'a str)>, ShapeParseError> {
+    let mut tokens = Tokenizer::new(input);
+    let mut values: Vec<Option<(usize, &str)>> = vec![None; fields.len()];
+
+    tokens.expect(Token::OpenBrace)?;
+    let mut first = true;
+    loop {
+        let key = match tokens.next_token()? {
+            Some((_, Token::CloseBrace)) if first => break,
+            Some((offset, Token::Str(key))) => (offset, key),
+            other => return Err(tokens.unexpected(other)),
+        };
+        tokens.expect(Token::Colon)?;
+        let value = match tokens.next_token()? {
+            Some((offset, Token::Number(n))) => (offset, n),
+            other => return Err(tokens.unexpected(other)),
+        };
+        let slot = fields
+            .iter()
+            .position(|f| *f == key.1)
+            .ok_or_else(|| tokens.error(key.0, ParseErrorKind::UnknownField(key.1.to_string())))?;
+        if values[slot].is_some() {
+            return Err(tokens.error(key.0, ParseErrorKind::DuplicateField(key.1.to_string())));
+        }
+        values[slot] = Some(value);
+        first = false;
+
+        match tokens.next_token()? {
+            Some((_, Token::Comma)) => continue,
+            Some((_, Token::CloseBrace)) => break,
+            other => return Err(tokens.unexpected(other)),
+        }
+    }
+    if let Some(extra) = tokens.next_token()? {
+        return Err(tokens.unexpected(Some(extra)));
+    }
+
+    fields
+        .iter()
+        .zip(values)
+        .map(|(field, value)| {
+            value.ok_or_else(|| tokens.error(input.len(), ParseErrorKind::MissingField(field)))
+        })
+        .collect()
+}
+
+fn parse_number<T: std::str::FromStr>((offset, text): (usize, &str)) -> Result<T, ShapeParseError> {
+    text.parse().map_err(|_| ShapeParseError {
+        offset,
+        kind: ParseErrorKind::InvalidNumber,
+    })
+}
+
+pub fn to_json(rect: &Rect) -> String {
+    format!(r#"{{"width":{},"height":{}}}"#, rect.width, rect.height)
+}
+
+pub fn from_json(input: &str) -> Result<Rect, ShapeParseError> {
+    let values = parse_object(input, &["width", "height"])?;
+    Ok(Rect {
+        width: parse_number(values[0])?,
+        height: parse_number(values[1])?,
+    })
+}
+
+pub fn circle_to_json(circle: &Circle) -> String {
+    format!(r#"{{"radius":{}}}"#, circle.radius)
+}
+
+pub fn circle_from_json(input: &str) -> Result<Circle, ShapeParseError> {
+    let values = parse_object(input, &["radius"])?;
+    Circle::new(parse_number(values[0])?).map_err(|_| ShapeParseError {
+        offset: values[0].0,
+        kind: ParseErrorKind::InvalidValue,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn err(offset: usize, kind: ParseErrorKind) -> ShapeParseError {
+        ShapeParseError { offset, kind }
+    }
+
+    #[test]
+    fn rect_round_trip() {
+        for &(width, height) in [(0, 0), (5, 6), (u32::MAX, 1)].iter() {
+            let r = Rect { width, height };
+            assert_eq!(from_json(&to_json(&r)), Ok(r));
+        }
+        assert_eq!(
+            to_json(&Rect {
+                width: 5,
+                height: 6
+            }),
+            r#"{"width":5,"height":6}"#
+        );
+    }
+
+    #[test]
+    fn circle_round_trip() {
+        for &radius in [0.0, 1.0, 2.5, 1e-300, std::f64::consts::PI].iter() {
+            let c = Circle::new(radius).unwrap();
+            assert_eq!(circle_from_json(&circle_to_json(&c)), Ok(c));
+        }
+    }
+
+    #[test]
+    fn whitespace_and_field_order_are_flexible() {
+        let r = from_json(" {\n  \"height\" : 6 ,\t\"width\":5 } ").unwrap();
+        assert_eq!(
+            r,
+            Rect {
+                width: 5,
+                height: 6
+            }
+        );
+    }
+
+    #[test]
+    fn missing_field() {
+        assert_eq!(
+            from_json(r#"{"width":5}"#),
+            Err(err(11, ParseErrorKind::MissingField("height")))
+        );
+    }
+
+    #[test]
+    fn duplicate_field() {
+        assert_eq!(
+            from_json(r#"{"width":5,"width":6,"height":1}"#),
+            Err(err(11, ParseErrorKind::DuplicateField("width".to_string())))
+        );
+    }
+
+    #[test]
+    fn trailing_comma() {
+        assert_eq!(
+            from_json(r#"{"width":5,"height":6,}"#),
+            Err(err(22, ParseErrorKind::UnexpectedChar('}')))
+        );
+    }
+
+    #[test]
+    fn other_malformed_input() {
+        assert_eq!(from_json(""), Err(err(0, ParseErrorKind::UnexpectedEnd)));
+        assert_eq!(
+            from_json(r#"{"width":-5,"height":6}"#),
+            Err(err(9, ParseErrorKind::InvalidNumber))
+        );
+        assert_eq!(
+            from_json(r#"{"width":5,"height":6,"depth":1}"#),
+            Err(err(22, ParseErrorKind::UnknownField("depth".to_string())))
+        );
+        assert_eq!(
+            from_json(r#"{"width":5,"height":6} x"#),
+            Err(err(23, ParseErrorKind::UnexpectedChar('x')))
+        );
+        assert_eq!(
+            from_json(r#"{"width":5 "height":6}"#),
+            Err(err(11, ParseErrorKind::UnexpectedChar('"')))
+        );
+        assert_eq!(
+            circle_from_json(r#"{"radius": -1}"#),
+            Err(err(11, ParseErrorKind::InvalidValue))
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use super::rectangles::Rect;
+
+pub trait Solid {
+    fn volume(&self) -> f64;
+    fn surface_area(&self) -> f64;
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub width: u32,
+    pub height: u32,
+    pub depth: u32,
+}
+
+/// Three `u32` factors can need up to 96 bits, so the exact measurements
+/// are returned as `u128` and can never overflow.
+impl Cuboid {
+    pub fn get_volume(&self) -> u128 {
+        self.width as u128 * self.height as u128 * self.depth as u128
+    }
+
+    pub fn get_surface_area(&self) -> u128 {
+        let (w, h, d) = (self.width as u128, self.height as u128, self.depth as u128);
+        2 * (w * h + h * d + w * d)
+    }
+}
+
+/// Extrudes the rect `depth` units deep.
+impl From<(Rect, u32)> for Cuboid {
+    fn from((rect, depth): (Rect, u32)) -> Cuboid {
+        Cuboid {
+            width: rect.width,
+            height: rect.height,
+            depth,
+        }
+    }
+}
+
+impl Solid for Cuboid {
+    fn volume(&self) -> f64 {
+        self.get_volume() as f64
+    }
+
+    fn surface_area(&self) -> f64 {
+        self.get_surface_area() as f64
+    }
+
+    fn name(&self) -> &'static str {
+        "cuboid"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::new_rect;
+    use super::*;
+
+    #[test]
+    fn extrude_rect() {
+        let c = Cuboid::from((new_rect(5, 6), 7));
+        assert_eq!(
+            c,
+            Cuboid {
+                width: 5,
+                height: 6,
+                depth: 7
+            }
+        );
+        assert_eq!(c.get_volume(), 210);
+        assert_eq!(c.get_surface_area(), 2 * (30 + 42 + 35));
+        assert_eq!(c.volume(), 210.0);
+        assert_eq!(c.name(), "cuboid");
+    }
+
+    #[test]
+    fn volume_at_the_u32_boundary() {
+        let m = u32::MAX as u128;
+        let c = Cuboid {
+            width: u32::MAX,
+            height: u32::MAX,
+            depth: u32::MAX,
+        };
+        assert_eq!(c.get_volume(), m * m * m);
+        assert_eq!(c.get_surface_area(), 6 * m * m);
+        assert!(c.get_volume() > u64::MAX as u128);
+
+        let max = Rect {
+            width: u32::MAX,
+            height: u32::MAX,
+        };
+        let flat = Cuboid::from((max, 0));
+        assert_eq!(flat.get_volume(), 0);
+        assert_eq!(flat.get_surface_area(), 2 * m * m);
+    }
+}
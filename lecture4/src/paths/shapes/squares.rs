@@ -0,0 +1,99 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Square {
+    pub side: u32,
+}
+
+impl Square {
+    pub fn get_area(&self) -> u32 {
+        self.side * self.side
+    }
+
+    pub fn get_perimeter(&self) -> u32 {
+        self.side * 4
+    }
+}
+
+impl From<Square> for Rect {
+    fn from(square: Square) -> Rect {
+        Rect {
+            width: square.side,
+            height: square.side,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotSquareError {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl fmt::Display for NotSquareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a {}x{} rectangle is not a square: width and height differ",
+            self.width, self.height
+        )
+    }
+}
+
+impl std::error::Error for NotSquareError {}
+
+impl TryFrom<Rect> for Square {
+    type Error = NotSquareError;
+
+    fn try_from(rect: Rect) -> Result<Square, NotSquareError> {
+        if rect.is_square() {
+            Ok(Square { side: rect.width })
+        } else {
+            Err(NotSquareError {
+                width: rect.width,
+                height: rect.height,
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_and_rect_agree() {
+        for &side in [0, 1, 5, 1000].iter() {
+            let square = Square { side };
+            let rect = Rect::from(square);
+            assert!(rect.is_square());
+            assert_eq!(square.get_area(), rect.get_area());
+            assert_eq!(square.get_perimeter(), rect.get_perimeter());
+            assert_eq!(Square::try_from(rect), Ok(square));
+        }
+    }
+
+    #[test]
+    fn non_square_rect_is_rejected() {
+        let rect = Rect {
+            width: 5,
+            height: 6,
+        };
+        assert!(!rect.is_square());
+        let err = Square::try_from(rect).unwrap_err();
+        assert_eq!(
+            err,
+            NotSquareError {
+                width: 5,
+                height: 6
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "a 5x6 rectangle is not a square: width and height differ"
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Descriptive statistics over the areas of a set of rects.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::thread;
+
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsError {
+    ZeroBucketSize,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StatsError::ZeroBucketSize => write!(f, "bucket size must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+/// Counts rects per bucket, keyed by `area / bucket_size`. A rect whose area
+/// is an exact multiple of `bucket_size` starts a new bucket.
+pub fn area_histogram(
+    rects: &[Rect],
+    bucket_size: u64,
+) -> Result<BTreeMap<u64, usize>, StatsError> {
+    if bucket_size == 0 {
+        return Err(StatsError::ZeroBucketSize);
+    }
+    let mut buckets = BTreeMap::new();
+    for r in rects {
+        *buckets.entry(r.get_area_u64() / bucket_size).or_insert(0) += 1;
+    }
+    Ok(buckets)
+}
+
+/// Area statistics. For an even number of rects the median is the mean of
+/// the two middle areas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub min: u64,
+    pub max: u64,
+    pub mean: f64,
+    pub median: f64,
+}
+
+/// `None` for an empty slice.
+pub fn summary(rects: &[Rect]) -> Option<Summary> {
+    let mut areas: Vec<u64> = Vec::with_capacity(rects.len());
+    let (mut min, mut max, mut sum) = (u64::MAX, 0, 0u128);
+    for r in rects {
+        let area = r.get_area_u64();
+        min = min.min(area);
+        max = max.max(area);
+        sum += area as u128;
+        areas.push(area);
+    }
+    if areas.is_empty() {
+        return None;
+    }
+    areas.sort_unstable();
+    Some(Summary {
+        min,
+        max,
+        mean: sum as f64 / areas.len() as f64,
+        median: median_of_sorted(&areas),
+    })
+}
+
+fn median_of_sorted(areas: &[u64]) -> f64 {
+    let mid = areas.len() / 2;
+    if areas.len().is_multiple_of(2) {
+        (areas[mid - 1] as f64 + areas[mid] as f64) / 2.0
+    } else {
+        areas[mid] as f64
+    }
+}
+
+/// Summary statistics for part of a slice. Partials combine with `merge` in
+/// any grouping, so chunks can be summarised on separate threads. The sorted
+/// areas are kept so the merged median is exact.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Partial {
+    pub count: usize,
+    pub sum_area: u128,
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    sorted_areas: Vec<u64>,
+}
+
+impl Partial {
+    pub fn from_rect(rect: &Rect) -> Partial {
+        let area = rect.get_area_u64();
+        Partial {
+            count: 1,
+            sum_area: area as u128,
+            min: Some(area),
+            max: Some(area),
+            sorted_areas: vec![area],
+        }
+    }
+
+    pub fn from_rects(rects: &[Rect]) -> Partial {
+        let mut sorted_areas: Vec<u64> = rects.iter().map(Rect::get_area_u64).collect();
+        sorted_areas.sort_unstable();
+        Partial {
+            count: sorted_areas.len(),
+            sum_area: sorted_areas.iter().map(|&a| a as u128).sum(),
+            min: sorted_areas.first().copied(),
+            max: sorted_areas.last().copied(),
+            sorted_areas,
+        }
+    }
+
+    pub fn merge(self, other: Partial) -> Partial {
+        let mut sorted_areas = Vec::with_capacity(self.count + other.count);
+        let (mut a, mut b) = (
+            self.sorted_areas.into_iter().peekable(),
+            other.sorted_areas.into_iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if x <= y {
+                sorted_areas.extend(a.next());
+            } else {
+                sorted_areas.extend(b.next());
+            }
+        }
+        sorted_areas.extend(a);
+        sorted_areas.extend(b);
+        Partial {
+            count: self.count + other.count,
+            sum_area: self.sum_area + other.sum_area,
+            min: self.min.into_iter().chain(other.min).min(),
+            max: self.max.into_iter().chain(other.max).max(),
+            sorted_areas,
+        }
+    }
+
+    /// `None` when no rects were seen.
+    pub fn finish(&self) -> Option<Summary> {
+        Some(Summary {
+            min: self.min?,
+            max: self.max?,
+            mean: self.sum_area as f64 / self.count as f64,
+            median: median_of_sorted(&self.sorted_areas),
+        })
+    }
+}
+
+/// Same result as `summary`, with the slice split across `threads` threads.
+/// Zero threads is treated as one.
+pub fn parallel_summary(rects: &[Rect], threads: usize) -> Option<Summary> {
+    let chunk_size = rects.len().div_ceil(threads.max(1)).max(1);
+    thread::scope(|scope| {
+        let handles: Vec<_> = rects
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || Partial::from_rects(chunk)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .fold(Partial::default(), Partial::merge)
+    })
+    .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(area_histogram(&[], 10).unwrap().is_empty());
+        assert_eq!(summary(&[]), None);
+    }
+
+    #[test]
+    fn zero_bucket_size_is_rejected() {
+        assert_eq!(
+            area_histogram(&[rect(1, 1)], 0),
+            Err(StatsError::ZeroBucketSize)
+        );
+    }
+
+    #[test]
+    fn single_element() {
+        let rects = [rect(3, 4)];
+        let histogram = area_histogram(&rects, 5).unwrap();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), vec![(2, 1)]);
+        assert_eq!(
+            summary(&rects),
+            Some(Summary {
+                min: 12,
+                max: 12,
+                mean: 12.0,
+                median: 12.0
+            })
+        );
+    }
+
+    #[test]
+    fn ties_at_bucket_boundaries() {
+        // Areas 9, 10, 10, 19, 20.
+        let rects = [rect(3, 3), rect(2, 5), rect(10, 1), rect(19, 1), rect(4, 5)];
+        let histogram = area_histogram(&rects, 10).unwrap();
+        assert_eq!(
+            histogram.into_iter().collect::<Vec<_>>(),
+            vec![(0, 1), (1, 3), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn even_count_median() {
+        let s = summary(&[rect(1, 1), rect(1, 4), rect(1, 2), rect(1, 7)]).unwrap();
+        assert_eq!((s.min, s.max), (1, 7));
+        assert_eq!(s.mean, 3.5);
+        assert_eq!(s.median, 3.0);
+    }
+
+    #[test]
+    fn large_areas_do_not_overflow_the_mean() {
+        let big = rect(u32::MAX, u32::MAX);
+        let s = summary(&[big, big, big]).unwrap();
+        assert_eq!(s.mean, big.get_area_u64() as f64);
+    }
+
+    #[test]
+    fn partials_merge_in_any_order() {
+        let rects = [rect(5, 1), rect(1, 1), rect(3, 3), rect(2, 2)];
+        let parts: Vec<Partial> = rects.iter().map(Partial::from_rect).collect();
+        let forward = parts
+            .iter()
+            .cloned()
+            .fold(Partial::default(), Partial::merge);
+        let backward = parts
+            .into_iter()
+            .rev()
+            .fold(Partial::default(), Partial::merge);
+        assert_eq!(forward, backward);
+        assert_eq!(forward, Partial::from_rects(&rects));
+        assert_eq!(forward.finish(), summary(&rects));
+        assert_eq!(Partial::default().finish(), None);
+    }
+
+    #[test]
+    fn parallel_matches_serial() {
+        let rects = crate::paths::shapes::testing::RectGen::new(38).rects(101, 1000, 1000);
+        let serial = summary(&rects);
+        for threads in 0..=12 {
+            assert_eq!(
+                parallel_summary(&rects, threads),
+                serial,
+                "{} threads",
+                threads
+            );
+        }
+        assert_eq!(parallel_summary(&rects, 500), serial);
+        assert_eq!(parallel_summary(&rects[..3], 8), summary(&rects[..3]));
+        assert_eq!(parallel_summary(&[], 4), None);
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+use super::{Point, Shape};
+
+/// Wraps each shape's `to_svg_element` in an `<svg>` document with a
+/// `width` x `height` viewBox. Shapes outside the viewBox are still
+/// emitted and left to the viewer to clip.
+pub fn to_svg(shapes: &[(Point, &dyn Shape)], width: u32, height: u32) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = width,
+        h = height
+    )
+    .unwrap();
+    for (origin, shape) in shapes {
+        writeln!(out, "  {}", shape.to_svg_element(*origin)).unwrap();
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::polygons::Polygon;
+    use super::super::{new_circle, new_rect};
+    use super::*;
+
+    #[test]
+    fn golden_rect_and_circle() {
+        let rect = new_rect(5, 6);
+        let circle = new_circle(2.5).unwrap();
+        let shapes: Vec<(Point, &dyn Shape)> = vec![
+            (Point { x: 1, y: 2 }, &rect),
+            (Point { x: 10, y: 10 }, &circle),
+        ];
+        assert_eq!(
+            to_svg(&shapes, 20, 15),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="15" viewBox="0 0 20 15">
+  <rect x="1" y="2" width="5" height="6"/>
+  <circle cx="10" cy="10" r="2.5"/>
+</svg>
+"#
+        );
+    }
+
+    #[test]
+    fn golden_polygon_outside_the_view_box() {
+        let triangle = Polygon::new(vec![
+            Point { x: 0, y: 0 },
+            Point { x: 4, y: 0 },
+            Point { x: 0, y: 3 },
+        ])
+        .unwrap();
+        let shapes: Vec<(Point, &dyn Shape)> = vec![(Point { x: -2, y: 50 }, &triangle)];
+        assert_eq!(
+            to_svg(&shapes, 10, 10),
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10" viewBox="0 0 10 10">
+  <polygon points="-2,50 2,50 -2,53"/>
+</svg>
+"#
+        );
+    }
+
+    #[test]
+    fn empty_document() {
+        assert_eq!(
+            to_svg(&[], 1, 1),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"1\" height=\"1\" viewBox=\"0 0 1 1\">\n</svg>\n"
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use super::rectangles::Rect;
+
+/// A deterministic rect generator for property-style tests, backed by a
+/// xorshift64* PRNG. The same seed always produces the same rects.
+#[derive(Debug, Clone)]
+pub struct RectGen {
+    state: u64,
+    allow_degenerate: bool,
+}
+
+impl RectGen {
+    pub fn new(seed: u64) -> RectGen {
+        RectGen {
+            // xorshift gets stuck at 0, so that seed is swapped for a fixed
+            // non-zero one.
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+            allow_degenerate: false,
+        }
+    }
+
+    /// Lets generated rects have zero width or height. Off by default.
+    pub fn allow_degenerate(mut self, allow: bool) -> RectGen {
+        self.allow_degenerate = allow;
+        self
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn next_dim(&mut self, max: u32) -> u32 {
+        let min = if self.allow_degenerate { 0 } else { 1 };
+        assert!(
+            max >= min,
+            "cannot generate a non-degenerate dimension no larger than 0"
+        );
+        // `max - min + 1` overflows `u32` when degenerate and `max` is `u32::MAX`
+        min + (self.next_u64() % (u64::from(max - min) + 1)) as u32
+    }
+
+    /// A rect with `width <= max_w` and `height <= max_h`. Both dimensions
+    /// are at least 1 unless degenerate rects are allowed, in which case a
+    /// zero maximum is fine too.
+    ///
+    /// The area always fits in a `u32`, so non-degenerate rects are ones
+    /// `try_new_rect` accepts: the height is drawn from at most
+    /// `u32::MAX / width`. That makes tall rects rarer when both maxima
+    /// are large enough for their product to overflow.
+    pub fn next_rect(&mut self, max_w: u32, max_h: u32) -> Rect {
+        let width = self.next_dim(max_w);
+        let max_h = match u32::MAX.checked_div(width) {
+            Some(limit) => max_h.min(limit),
+            None => max_h,
+        };
+        Rect {
+            width,
+            height: self.next_dim(max_h),
+        }
+    }
+
+    pub fn rects(&mut self, n: usize, max_w: u32, max_h: u32) -> Vec<Rect> {
+        (0..n).map(|_| self.next_rect(max_w, max_h)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::try_new_rect;
+    use super::*;
+
+    #[test]
+    fn same_seed_same_rects() {
+        let a = RectGen::new(7).rects(100, 1000, 1000);
+        let b = RectGen::new(7).rects(100, 1000, 1000);
+        let c = RectGen::new(8).rects(100, 1000, 1000);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(
+            RectGen::new(0).rects(5, 9, 9),
+            RectGen::new(0).rects(5, 9, 9)
+        );
+    }
+
+    #[test]
+    fn respects_bounds_and_degeneracy() {
+        let mut gen = RectGen::new(1);
+        for r in gen.rects(1000, 3, 5) {
+            assert!((1..=3).contains(&r.width) && (1..=5).contains(&r.height));
+        }
+        let mut gen = RectGen::new(1).allow_degenerate(true);
+        let rects = gen.rects(1000, 2, 2);
+        assert!(rects.iter().any(|r| r.width == 0 || r.height == 0));
+        assert_eq!(gen.next_rect(0, 0), Rect::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-degenerate")]
+    fn zero_max_without_degenerate_panics() {
+        RectGen::new(1).next_rect(0, 5);
+    }
+
+    #[test]
+    fn largest_maxima() {
+        let mut gen = RectGen::new(3).allow_degenerate(true);
+        for _ in 0..1000 {
+            let r = gen.next_rect(u32::MAX, u32::MAX);
+            assert_eq!(r.get_area_u64(), r.get_area() as u64);
+        }
+    }
+
+    #[test]
+    fn areas_fit_like_try_new_rect() {
+        let mut gen = RectGen::new(5);
+        let rects = gen.rects(10_000, u32::MAX, u32::MAX);
+        for r in &rects {
+            assert_eq!(try_new_rect(r.width, r.height), Ok(*r));
+            assert_eq!(r.get_area() as u64, r.get_area_u64());
+        }
+        assert!(rects.iter().any(|r| r.width > 1 << 31));
+        assert!(rects.iter().any(|r| r.height > 1));
+    }
+
+    #[test]
+    fn area_and_perimeter_invariants() {
+        let mut gen = RectGen::new(2024);
+        for r in gen.rects(10_000, 60_000, 60_000) {
+            let area = r.get_area();
+            let perimeter = r.get_perimeter();
+            assert_eq!(perimeter % 2, 0);
+            assert!(area >= r.width.max(r.height));
+            assert!(perimeter >= 4);
+            assert_eq!(area as u64, r.get_area_u64());
+            assert_eq!(r.split_vertical(1).is_ok(), r.width > 1);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitError {
+    AtZero,
+    AtOrBeyondEdge { at: u32, edge: u32 },
+}
+
+impl fmt::Display for SplitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SplitError::AtZero => write!(f, "cannot split at 0"),
+            SplitError::AtOrBeyondEdge { at, edge } => {
+                write!(f, "cannot split at {}, the edge is at {}", at, edge)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+pub(crate) fn check_split(at: u32, edge: u32) -> Result<(), SplitError> {
+    if at == 0 {
+        Err(SplitError::AtZero)
+    } else if at >= edge {
+        Err(SplitError::AtOrBeyondEdge { at, edge })
+    } else {
+        Ok(())
+    }
+}
+
+/// Iterator returned by `Rect::tile`.
+///
+/// Tiles are yielded row by row, left to right, positioned relative to the
+/// top-left corner of the tiled rect. When a dimension is not a multiple of
+/// the tile size, the last column or row is made of smaller tiles covering
+/// the remainder, so the tiles always cover the rect exactly.
+#[derive(Debug, Clone)]
+pub struct TileIter {
+    rect: Rect,
+    tile_w: u32,
+    tile_h: u32,
+    cols: u64,
+    next: u64,
+    end: u64,
+}
+
+impl TileIter {
+    pub(crate) fn new(rect: Rect, tile_w: u32, tile_h: u32) -> TileIter {
+        let (cols, rows) = if tile_w == 0 || tile_h == 0 {
+            (0, 0)
+        } else {
+            (
+                (rect.width as u64).div_ceil(tile_w as u64),
+                (rect.height as u64).div_ceil(tile_h as u64),
+            )
+        };
+        TileIter {
+            rect,
+            tile_w,
+            tile_h,
+            cols,
+            next: 0,
+            end: cols * rows,
+        }
+    }
+}
+
+impl Iterator for TileIter {
+    type Item = PositionedRect;
+
+    fn next(&mut self) -> Option<PositionedRect> {
+        if self.next >= self.end {
+            return None;
+        }
+        let col = self.next % self.cols;
+        let row = self.next / self.cols;
+        self.next += 1;
+
+        let x = col * self.tile_w as u64;
+        let y = row * self.tile_h as u64;
+        let width = (self.rect.width as u64 - x).min(self.tile_w as u64) as u32;
+        let height = (self.rect.height as u64 - y).min(self.tile_h as u64) as u32;
+        Some(PositionedRect::new(
+            Point {
+                x: x as i64,
+                y: y as i64,
+            },
+            Rect { width, height },
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.end - self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for TileIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn split_horizontal_and_vertical() {
+        let r = rect(10, 4);
+        assert_eq!(r.split_horizontal(1), Ok((rect(10, 1), rect(10, 3))));
+        assert_eq!(r.split_vertical(7), Ok((rect(7, 4), rect(3, 4))));
+    }
+
+    #[test]
+    fn split_rejects_edges() {
+        let r = rect(10, 4);
+        assert_eq!(r.split_horizontal(0), Err(SplitError::AtZero));
+        assert_eq!(
+            r.split_horizontal(4),
+            Err(SplitError::AtOrBeyondEdge { at: 4, edge: 4 })
+        );
+        assert_eq!(
+            r.split_vertical(11),
+            Err(SplitError::AtOrBeyondEdge { at: 11, edge: 10 })
+        );
+    }
+
+    #[test]
+    fn tiles_divide_exactly() {
+        let tiles: Vec<_> = rect(4, 4).tile(2, 2).collect();
+        assert_eq!(tiles.len(), 4);
+        assert!(tiles.iter().all(|t| t.size == rect(2, 2)));
+        assert_eq!(tiles[1].origin, Point { x: 2, y: 0 });
+        assert_eq!(tiles[2].origin, Point { x: 0, y: 2 });
+    }
+
+    #[test]
+    fn tiles_with_remainder_are_smaller_at_the_edges() {
+        let mut iter = rect(5, 3).tile(2, 2);
+        assert_eq!(iter.len(), 6);
+        let tiles: Vec<_> = iter.by_ref().collect();
+        assert_eq!(iter.len(), 0);
+        let sizes: Vec<_> = tiles.iter().map(|t| t.size).collect();
+        assert_eq!(
+            sizes,
+            vec![
+                rect(2, 2),
+                rect(2, 2),
+                rect(1, 2),
+                rect(2, 1),
+                rect(2, 1),
+                rect(1, 1)
+            ]
+        );
+        let covered: u32 = sizes.iter().map(|s| s.get_area()).sum();
+        assert_eq!(covered, 15);
+    }
+
+    #[test]
+    fn tile_larger_than_rect_yields_the_rect() {
+        let tiles: Vec<_> = rect(3, 2).tile(10, 10).collect();
+        assert_eq!(
+            tiles,
+            vec![PositionedRect::new(Point { x: 0, y: 0 }, rect(3, 2))]
+        );
+    }
+
+    #[test]
+    fn zero_sized_tiles_or_rects_yield_nothing() {
+        assert_eq!(rect(3, 2).tile(0, 1).len(), 0);
+        assert_eq!(rect(0, 2).tile(1, 1).count(), 0);
+    }
+}
@@ -0,0 +1,256 @@
+use std::convert::TryFrom;
+
+use super::positioned::{Point, PositionedRect};
+use super::rectangles::Rect;
+
+/// How a fractional result is turned back into a whole number of units.
+/// `Nearest` rounds halves up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+    Nearest,
+}
+
+fn scale_dim(value: u32, num: u32, den: u32, rounding: Rounding) -> Option<u32> {
+    if den == 0 {
+        return None;
+    }
+    let product = value as u64 * num as u64;
+    let den = den as u64;
+    let scaled = match rounding {
+        Rounding::Floor => product / den,
+        Rounding::Ceil => product.div_ceil(den),
+        Rounding::Nearest => product / den + u64::from(product % den * 2 >= den),
+    };
+    u32::try_from(scaled).ok()
+}
+
+impl Rect {
+    /// Multiplies both dimensions by `num / den`, saturating at `u32::MAX`.
+    /// Panics if `den` is zero.
+    pub fn scale(&self, num: u32, den: u32, rounding: Rounding) -> Rect {
+        assert!(den != 0, "cannot scale a rect by {}/0", num);
+        Rect {
+            width: scale_dim(self.width, num, den, rounding).unwrap_or(u32::MAX),
+            height: scale_dim(self.height, num, den, rounding).unwrap_or(u32::MAX),
+        }
+    }
+
+    /// Like `scale`, but `None` when `den` is zero or a dimension overflows.
+    pub fn checked_scale(&self, num: u32, den: u32, rounding: Rounding) -> Option<Rect> {
+        Some(Rect {
+            width: scale_dim(self.width, num, den, rounding)?,
+            height: scale_dim(self.height, num, den, rounding)?,
+        })
+    }
+
+    /// Adds `dx` to the width and `dy` to the height, saturating at
+    /// `u32::MAX`.
+    pub fn grow(&self, dx: u32, dy: u32) -> Rect {
+        Rect {
+            width: self.width.saturating_add(dx),
+            height: self.height.saturating_add(dy),
+        }
+    }
+
+    pub fn checked_grow(&self, dx: u32, dy: u32) -> Option<Rect> {
+        Some(Rect {
+            width: self.width.checked_add(dx)?,
+            height: self.height.checked_add(dy)?,
+        })
+    }
+
+    /// Subtracts `dx` from the width and `dy` from the height, stopping at 0.
+    pub fn shrink(&self, dx: u32, dy: u32) -> Rect {
+        Rect {
+            width: self.width.saturating_sub(dx),
+            height: self.height.saturating_sub(dy),
+        }
+    }
+
+    /// Like `shrink`, but `None` instead of going below zero.
+    pub fn try_shrink(&self, dx: u32, dy: u32) -> Option<Rect> {
+        Some(Rect {
+            width: self.width.checked_sub(dx)?,
+            height: self.height.checked_sub(dy)?,
+        })
+    }
+}
+
+impl Rect {
+    /// Swaps width and height.
+    pub fn rotate90(&self) -> Rect {
+        Rect {
+            width: self.height,
+            height: self.width,
+        }
+    }
+}
+
+fn to_point(x: i128, y: i128) -> Option<Point> {
+    Some(Point {
+        x: i64::try_from(x).ok()?,
+        y: i64::try_from(y).ok()?,
+    })
+}
+
+/// The transforms work in screen coordinates, with y growing downwards, and
+/// return `None` when the new origin does not fit in an `i64`.
+impl PositionedRect {
+    /// Rotates the rect a quarter turn clockwise around `pivot`. The result
+    /// is the rotated area, with its origin at the top-left of that area.
+    pub fn rotate90_around(&self, pivot: Point) -> Option<PositionedRect> {
+        let (px, py) = (pivot.x as i128, pivot.y as i128);
+        let left = self.origin.x as i128;
+        let bottom = self.origin.y as i128 + self.size.height as i128;
+        Some(PositionedRect::new(
+            to_point(px + py - bottom, py - px + left)?,
+            self.size.rotate90(),
+        ))
+    }
+
+    /// Mirrors the rect across the vertical line `x = axis`.
+    pub fn reflect_x(&self, axis: i64) -> Option<PositionedRect> {
+        let right = self.origin.x as i128 + self.size.width as i128;
+        Some(PositionedRect::new(
+            to_point(2 * axis as i128 - right, self.origin.y as i128)?,
+            self.size,
+        ))
+    }
+
+    /// Mirrors the rect across the horizontal line `y = axis`.
+    pub fn reflect_y(&self, axis: i64) -> Option<PositionedRect> {
+        let bottom = self.origin.y as i128 + self.size.height as i128;
+        Some(PositionedRect::new(
+            to_point(self.origin.x as i128, 2 * axis as i128 - bottom)?,
+            self.size,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(width: u32, height: u32) -> Rect {
+        Rect { width, height }
+    }
+
+    #[test]
+    fn scale_by_half_with_each_rounding() {
+        let r = rect(5, 4);
+        assert_eq!(r.scale(1, 2, Rounding::Floor), rect(2, 2));
+        assert_eq!(r.scale(1, 2, Rounding::Ceil), rect(3, 2));
+        assert_eq!(r.scale(1, 2, Rounding::Nearest), rect(3, 2));
+        assert_eq!(rect(7, 7).scale(1, 3, Rounding::Nearest), rect(2, 2));
+        assert_eq!(rect(8, 8).scale(1, 3, Rounding::Nearest), rect(3, 3));
+    }
+
+    #[test]
+    fn scale_by_zero() {
+        assert_eq!(rect(5, 4).scale(0, 3, Rounding::Ceil), rect(0, 0));
+        assert_eq!(rect(5, 4).checked_scale(3, 0, Rounding::Floor), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot scale a rect by 3/0")]
+    fn scale_by_zero_denominator_panics() {
+        rect(5, 4).scale(3, 0, Rounding::Floor);
+    }
+
+    #[test]
+    fn scale_that_would_overflow() {
+        let r = rect(u32::MAX, 10);
+        assert_eq!(r.checked_scale(2, 1, Rounding::Floor), None);
+        assert_eq!(r.scale(2, 1, Rounding::Floor), rect(u32::MAX, 20));
+        assert_eq!(
+            r.checked_scale(u32::MAX, u32::MAX, Rounding::Nearest),
+            Some(r)
+        );
+    }
+
+    #[test]
+    fn grow_and_shrink() {
+        let r = rect(5, 4);
+        assert_eq!(r.grow(1, 2), rect(6, 6));
+        assert_eq!(r.grow(u32::MAX, 0), rect(u32::MAX, 4));
+        assert_eq!(r.checked_grow(u32::MAX, 0), None);
+        assert_eq!(r.shrink(2, 1), rect(3, 3));
+        assert_eq!(r.try_shrink(2, 1), Some(rect(3, 3)));
+    }
+
+    #[test]
+    fn shrink_to_zero() {
+        let r = rect(5, 4);
+        assert_eq!(r.shrink(5, 4), rect(0, 0));
+        assert_eq!(r.shrink(10, 1), rect(0, 3));
+        assert_eq!(r.try_shrink(5, 4), Some(rect(0, 0)));
+        assert_eq!(r.try_shrink(6, 0), None);
+    }
+
+    fn prect(x: i64, y: i64, width: u32, height: u32) -> PositionedRect {
+        PositionedRect::new(Point { x, y }, rect(width, height))
+    }
+
+    #[test]
+    fn rotate90_swaps_dimensions() {
+        assert_eq!(rect(2, 5).rotate90(), rect(5, 2));
+        assert_eq!(rect(2, 5).rotate90().rotate90(), rect(2, 5));
+    }
+
+    #[test]
+    fn rotate_around_pivot() {
+        let p = prect(1, 0, 3, 1);
+        let origin = Point { x: 0, y: 0 };
+        assert_eq!(p.rotate90_around(origin), Some(prect(-1, 1, 1, 3)));
+        assert_eq!(
+            prect(0, 0, 2, 2).rotate90_around(Point { x: 1, y: 1 }),
+            Some(prect(0, 0, 2, 2))
+        );
+    }
+
+    #[test]
+    fn four_rotations_are_the_identity() {
+        let pivots = [
+            Point { x: 0, y: 0 },
+            Point { x: -7, y: 3 },
+            Point { x: 100, y: -50 },
+        ];
+        for p in [prect(1, 2, 3, 4), prect(-5, -5, 0, 7), prect(9, -3, 1, 1)].iter() {
+            for &pivot in pivots.iter() {
+                let mut r = *p;
+                for _ in 0..4 {
+                    r = r.rotate90_around(pivot).unwrap();
+                }
+                assert_eq!(r, *p);
+            }
+        }
+    }
+
+    #[test]
+    fn reflections_are_involutions() {
+        let p = prect(2, -3, 4, 5);
+        assert_eq!(p.reflect_x(0), Some(prect(-6, -3, 4, 5)));
+        assert_eq!(p.reflect_y(1), Some(prect(2, 0, 4, 5)));
+        for &axis in [0, 5, -9].iter() {
+            assert_eq!(p.reflect_x(axis).unwrap().reflect_x(axis), Some(p));
+            assert_eq!(p.reflect_y(axis).unwrap().reflect_y(axis), Some(p));
+        }
+    }
+
+    #[test]
+    fn overflow_near_the_coordinate_limits() {
+        let p = prect(i64::MIN, 0, 1, 1);
+        assert_eq!(p.reflect_x(i64::MAX), None);
+        assert_eq!(p.rotate90_around(Point { x: 10, y: 0 }), None);
+        let edge = prect(i64::MAX - 1, i64::MAX - 1, 1, 1);
+        assert_eq!(
+            edge.rotate90_around(Point {
+                x: i64::MAX - 1,
+                y: i64::MAX - 1
+            }),
+            Some(prect(i64::MAX - 2, i64::MAX - 1, 1, 1))
+        );
+    }
+}
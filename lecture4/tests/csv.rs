@@ -0,0 +1,41 @@
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use lecture4_lib::paths::shapes::csv::{load_rects, save_rects, LoadError};
+use lecture4_lib::paths::shapes::testing::RectGen;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("lecture4-{}-{}.csv", name, std::process::id()))
+}
+
+#[test]
+fn round_trips_through_a_file() {
+    let rects = RectGen::new(34).rects(1000, u32::MAX, u32::MAX);
+    let path = temp_path("round-trip");
+
+    save_rects(BufWriter::new(File::create(&path).unwrap()), &rects).unwrap();
+    let loaded = load_rects(BufReader::new(File::open(&path).unwrap()));
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.unwrap(), rects);
+}
+
+#[test]
+fn malformed_line_reports_its_number() {
+    let mut contents = String::from("# header\n\n");
+    for i in 1..=40 {
+        contents.push_str(&format!("{},{}\n", i, i + 1));
+    }
+    contents.push_str("7,-3\n1,1\n");
+    let path = temp_path("malformed");
+    fs::write(&path, contents).unwrap();
+
+    let result = load_rects(BufReader::new(File::open(&path).unwrap()));
+    fs::remove_file(&path).unwrap();
+
+    match result {
+        Err(LoadError::Parse { line, .. }) => assert_eq!(line, 43),
+        other => panic!("expected a parse error, got {:?}", other),
+    }
+}
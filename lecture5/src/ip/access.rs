@@ -0,0 +1,192 @@
+//! Allow/deny lists of networks. The text format has one rule per line,
+//! `allow 10.0.0.0/8` or `deny 10.1.0.0/16`; blank lines and `#` comments
+//! are ignored.
+
+use std::fmt;
+use std::io::{self, BufRead};
+
+use super::route_table::RouteTable;
+use super::{IpAddr, IpNetwork, NetworkParseError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Decision {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug)]
+pub enum FilterError {
+    Io(io::Error),
+    /// The first word on `line` is neither `allow` nor `deny`.
+    UnknownAction {
+        line: usize,
+        action: String,
+    },
+    /// `line` does not have exactly one network after the action.
+    WrongFieldCount {
+        line: usize,
+    },
+    InvalidNetwork {
+        line: usize,
+        source: NetworkParseError,
+    },
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterError::Io(e) => write!(f, "read failed: {}", e),
+            FilterError::UnknownAction { line, action } => {
+                write!(f, "line {}: unknown action {:?}", line, action)
+            }
+            FilterError::WrongFieldCount { line } => {
+                write!(f, "line {}: expected `allow|deny NETWORK`", line)
+            }
+            FilterError::InvalidNetwork { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FilterError::Io(e) => Some(e),
+            FilterError::InvalidNetwork { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FilterError {
+    fn from(e: io::Error) -> Self {
+        FilterError::Io(e)
+    }
+}
+
+/// Decides by the most specific rule containing an address. Only one rule
+/// can be kept per network, and deny wins a tie: allowing a network that is
+/// already denied has no effect, while denying replaces an allow. Addresses
+/// no rule covers are denied.
+#[derive(Debug, Clone, Default)]
+pub struct IpFilter {
+    rules: RouteTable<Decision>,
+}
+
+impl IpFilter {
+    pub fn new() -> IpFilter {
+        IpFilter::default()
+    }
+
+    pub fn allow(&mut self, network: IpNetwork) -> &mut Self {
+        if let Some(Decision::Deny) = self.rules.insert(network, Decision::Allow) {
+            self.rules.insert(network, Decision::Deny);
+        }
+        self
+    }
+
+    pub fn deny(&mut self, network: IpNetwork) -> &mut Self {
+        self.rules.insert(network, Decision::Deny);
+        self
+    }
+
+    pub fn check(&self, addr: &IpAddr) -> Decision {
+        self.rules
+            .lookup(addr)
+            .map_or(Decision::Deny, |(_, decision)| *decision)
+    }
+
+    /// Reads rules in the format described in the module docs. Line numbers
+    /// in errors start at 1.
+    pub fn from_lines(reader: impl BufRead) -> Result<IpFilter, FilterError> {
+        let mut filter = IpFilter::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let number = index + 1;
+            let rule = line.split('#').next().unwrap_or("");
+            let fields: Vec<&str> = rule.split_whitespace().collect();
+            if fields.is_empty() {
+                continue;
+            }
+            if fields.len() != 2 {
+                return Err(FilterError::WrongFieldCount { line: number });
+            }
+            let network = fields[1]
+                .parse()
+                .map_err(|source| FilterError::InvalidNetwork {
+                    line: number,
+                    source,
+                })?;
+            match fields[0] {
+                "allow" => filter.allow(network),
+                "deny" => filter.deny(network),
+                action => {
+                    return Err(FilterError::UnknownAction {
+                        line: number,
+                        action: action.to_string(),
+                    })
+                }
+            };
+        }
+        Ok(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(filter: &IpFilter, addr: &str) -> Decision {
+        filter.check(&addr.parse().unwrap())
+    }
+
+    #[test]
+    fn nested_rules() {
+        let filter = IpFilter::from_lines(
+            "# office\n\
+             allow 10.0.0.0/8\n\
+             deny 10.1.0.0/16   # lab\n\
+             \n\
+             allow 10.1.2.0/24\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(check(&filter, "10.9.9.9"), Decision::Allow);
+        assert_eq!(check(&filter, "10.1.9.9"), Decision::Deny);
+        assert_eq!(check(&filter, "10.1.2.9"), Decision::Allow);
+        assert_eq!(check(&filter, "11.0.0.0"), Decision::Deny);
+        assert_eq!(check(&filter, "::a00:1"), Decision::Deny);
+    }
+
+    #[test]
+    fn deny_wins_ties() {
+        let network: IpNetwork = "192.168.0.0/16".parse().unwrap();
+        let mut filter = IpFilter::new();
+        filter.deny(network).allow(network);
+        assert_eq!(check(&filter, "192.168.1.1"), Decision::Deny);
+
+        let mut filter = IpFilter::new();
+        filter.allow(network).deny(network);
+        assert_eq!(check(&filter, "192.168.1.1"), Decision::Deny);
+    }
+
+    #[test]
+    fn reports_the_bad_line() {
+        let err = |input: &str| IpFilter::from_lines(input.as_bytes()).unwrap_err();
+        match err("allow 10.0.0.0/8\n# ok\npermit 1.2.3.0/24\n") {
+            FilterError::UnknownAction { line: 3, action } => assert_eq!(action, "permit"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match err("\n\nallow 10.0.0.0/40\n") {
+            FilterError::InvalidNetwork { line: 3, .. } => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        match err("deny\n") {
+            FilterError::WrongFieldCount { line: 1 } => {}
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            err("allow 1.0.0.0/8\ndeny 1.2.3.4/32 extra\n").to_string(),
+            "line 2: expected `allow|deny NETWORK`"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use super::IpAddr;
+
+impl IpAddr {
+    /// The largest value the family's address bits can hold.
+    fn max_bits(&self) -> u128 {
+        u128::MAX >> (128 - self.bit_len() as u32)
+    }
+
+    /// The next address, or `None` after the last one in the family.
+    pub fn succ(&self) -> Option<IpAddr> {
+        self.checked_add(1)
+    }
+
+    /// The previous address, or `None` before the first one in the family.
+    pub fn pred(&self) -> Option<IpAddr> {
+        let bits = self.to_bits().checked_sub(1)?;
+        Some(self.with_bits(bits))
+    }
+
+    /// The address `n` places later, or `None` past the end of the family.
+    pub fn checked_add(&self, n: u128) -> Option<IpAddr> {
+        let bits = self.to_bits().checked_add(n)?;
+        if bits > self.max_bits() {
+            return None;
+        }
+        Some(self.with_bits(bits))
+    }
+
+    /// How many places apart the two addresses are, or `None` for different
+    /// families.
+    pub fn distance(&self, other: &IpAddr) -> Option<u128> {
+        if !self.same_family(other) {
+            return None;
+        }
+        let (a, b) = (self.to_bits(), other.to_bits());
+        Some(a.max(b) - a.min(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn carries_across_v4_octets() {
+        assert_eq!(addr("1.2.3.255").succ(), Some(addr("1.2.4.0")));
+        assert_eq!(addr("1.2.255.255").succ(), Some(addr("1.3.0.0")));
+        assert_eq!(addr("1.255.255.255").succ(), Some(addr("2.0.0.0")));
+        assert_eq!(addr("2.0.0.0").pred(), Some(addr("1.255.255.255")));
+        assert_eq!(addr("1.3.0.0").pred(), Some(addr("1.2.255.255")));
+        assert_eq!(addr("1.2.4.0").pred(), Some(addr("1.2.3.255")));
+    }
+
+    #[test]
+    fn ends_of_the_v4_space() {
+        assert_eq!(addr("255.255.255.255").succ(), None);
+        assert_eq!(
+            addr("255.255.255.254").succ(),
+            Some(addr("255.255.255.255"))
+        );
+        assert_eq!(addr("0.0.0.0").pred(), None);
+        assert_eq!(
+            addr("255.255.255.0").checked_add(255),
+            Some(addr("255.255.255.255"))
+        );
+        assert_eq!(addr("255.255.255.0").checked_add(256), None);
+        assert_eq!(addr("0.0.0.0").checked_add(u128::MAX), None);
+    }
+
+    #[test]
+    fn carries_across_v6_segments() {
+        assert_eq!(addr("::ffff").succ(), Some(addr("::1:0")));
+        assert_eq!(
+            addr("1:ffff:ffff:ffff:ffff:ffff:ffff:ffff").succ(),
+            Some(addr("2::"))
+        );
+        assert_eq!(
+            addr("2::").pred(),
+            Some(addr("1:ffff:ffff:ffff:ffff:ffff:ffff:ffff"))
+        );
+        assert_eq!(addr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").succ(), None);
+        assert_eq!(addr("::").pred(), None);
+        assert_eq!(
+            addr("::").checked_add(u128::MAX),
+            Some(addr("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"))
+        );
+    }
+
+    #[test]
+    fn distances() {
+        assert_eq!(addr("10.0.0.0").distance(&addr("10.0.1.0")), Some(256));
+        assert_eq!(addr("10.0.1.0").distance(&addr("10.0.0.0")), Some(256));
+        assert_eq!(addr("10.0.0.0").distance(&addr("10.0.0.0")), Some(0));
+        assert_eq!(
+            addr("0.0.0.0").distance(&addr("255.255.255.255")),
+            Some(u32::MAX as u128)
+        );
+        assert_eq!(addr("::").distance(&addr("::1:0")), Some(65536));
+        assert_eq!(addr("::").distance(&addr("0.0.0.0")), None);
+    }
+}
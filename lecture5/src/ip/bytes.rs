@@ -0,0 +1,137 @@
+//! Raw byte forms of an address. On the wire an address is a one-byte family
+//! tag, 4 or 6, followed by its octets in network order.
+
+use std::io::{self, Read, Write};
+
+use super::IpAddr;
+
+const V4_TAG: u8 = 4;
+const V6_TAG: u8 = 6;
+
+/// The octets of an address, in network order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddrOctets {
+    V4([u8; 4]),
+    V6([u8; 16]),
+}
+
+impl AddrOctets {
+    pub fn as_slice(&self) -> &[u8] {
+        match self {
+            AddrOctets::V4(octets) => octets,
+            AddrOctets::V6(octets) => octets,
+        }
+    }
+}
+
+impl From<[u8; 4]> for IpAddr {
+    fn from([a, b, c, d]: [u8; 4]) -> IpAddr {
+        IpAddr::V4(a, b, c, d)
+    }
+}
+
+impl From<[u8; 16]> for IpAddr {
+    fn from(octets: [u8; 16]) -> IpAddr {
+        IpAddr::V6_UNSPECIFIED.with_bits(u128::from_be_bytes(octets))
+    }
+}
+
+impl IpAddr {
+    pub fn octets(&self) -> AddrOctets {
+        match *self {
+            IpAddr::V4(a, b, c, d) => AddrOctets::V4([a, b, c, d]),
+            IpAddr::V6(_) => AddrOctets::V6(self.to_bits().to_be_bytes()),
+        }
+    }
+
+    /// Writes the tagged form, returning the number of bytes written.
+    pub fn write_to(&self, w: &mut impl Write) -> io::Result<usize> {
+        let octets = self.octets();
+        let tag = match octets {
+            AddrOctets::V4(_) => V4_TAG,
+            AddrOctets::V6(_) => V6_TAG,
+        };
+        w.write_all(&[tag])?;
+        w.write_all(octets.as_slice())?;
+        Ok(1 + octets.as_slice().len())
+    }
+
+    /// Reads one address written by `write_to`. A short read fails with
+    /// `UnexpectedEof` and an unknown tag with `InvalidData`.
+    pub fn read_from(r: &mut impl Read) -> io::Result<IpAddr> {
+        let mut tag = [0u8; 1];
+        r.read_exact(&mut tag)?;
+        match tag[0] {
+            V4_TAG => {
+                let mut octets = [0u8; 4];
+                r.read_exact(&mut octets)?;
+                Ok(octets.into())
+            }
+            V6_TAG => {
+                let mut octets = [0u8; 16];
+                r.read_exact(&mut octets)?;
+                Ok(octets.into())
+            }
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown address family tag {}", other),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn array_conversions() {
+        assert_eq!(IpAddr::from([10, 0, 0, 1]), IpAddr::V4(10, 0, 0, 1));
+        let mut loopback = [0u8; 16];
+        loopback[15] = 1;
+        assert_eq!(IpAddr::from(loopback), IpAddr::V6_LOOPBACK);
+        assert_eq!(IpAddr::V6_LOOPBACK.octets(), AddrOctets::V6(loopback));
+        assert_eq!(
+            IpAddr::V4(1, 2, 3, 4).octets(),
+            AddrOctets::V4([1, 2, 3, 4])
+        );
+    }
+
+    #[test]
+    fn round_trips_through_a_buffer() {
+        let addrs: Vec<IpAddr> = ["192.0.2.1", "2001:db8::1", "::", "255.255.255.255"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        let mut buf = Vec::new();
+        let written: usize = addrs.iter().map(|a| a.write_to(&mut buf).unwrap()).sum();
+        assert_eq!(written, 5 + 17 + 17 + 5);
+        assert_eq!(buf.len(), written);
+
+        let mut cursor = Cursor::new(buf);
+        for addr in &addrs {
+            assert_eq!(IpAddr::read_from(&mut cursor).unwrap(), *addr);
+        }
+        let end = IpAddr::read_from(&mut cursor).unwrap_err();
+        assert_eq!(end.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn truncated_input() {
+        let mut buf = Vec::new();
+        IpAddr::V6_LOOPBACK.write_to(&mut buf).unwrap();
+        buf.truncate(10);
+        let err = IpAddr::read_from(&mut Cursor::new(buf)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let err = IpAddr::read_from(&mut Cursor::new(vec![4, 1, 2])).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn unknown_tag() {
+        let err = IpAddr::read_from(&mut Cursor::new(vec![5, 1, 2, 3, 4])).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
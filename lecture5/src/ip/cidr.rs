@@ -0,0 +1,128 @@
+use super::{IpAddr, IpNetwork};
+
+/// The fewest networks covering exactly the addresses from `start` to `end`
+/// inclusive, where both are address bits of a family `bit_len` wide.
+fn cover_range(family: IpAddr, mut start: u128, end: u128, out: &mut Vec<IpNetwork>) {
+    let bit_len = family.bit_len() as u32;
+    loop {
+        let span = end - start;
+        let fits = match span.checked_add(1) {
+            Some(count) => 127 - count.leading_zeros(),
+            None => 128,
+        };
+        let host_bits = start.trailing_zeros().min(fits).min(bit_len);
+        let prefix_len = (bit_len - host_bits) as u8;
+        out.push(IpNetwork::new(family.with_bits(start), prefix_len).unwrap());
+        let last = start
+            + 1u128
+                .checked_shl(host_bits)
+                .map_or(u128::MAX, |size| size - 1);
+        if last >= end {
+            return;
+        }
+        start = last + 1;
+    }
+}
+
+/// The minimal set of networks covering exactly `addrs`, in address order.
+/// V4 and V6 addresses are summarized separately, so a mixed list gives the
+/// V4 networks followed by the V6 ones. Duplicates are ignored.
+pub fn summarize(addrs: &[IpAddr]) -> Vec<IpNetwork> {
+    let mut sorted = addrs.to_vec();
+    super::sort_and_dedup(&mut sorted);
+
+    let mut networks = Vec::new();
+    let mut run: Option<(IpAddr, u128, u128)> = None;
+    for addr in sorted {
+        let bits = addr.to_bits();
+        run = match run {
+            Some((family, start, end)) if family.same_family(&addr) && end + 1 == bits => {
+                Some((family, start, bits))
+            }
+            Some((family, start, end)) => {
+                cover_range(family, start, end, &mut networks);
+                Some((addr, bits, bits))
+            }
+            None => Some((addr, bits, bits)),
+        };
+    }
+    if let Some((family, start, end)) = run {
+        cover_range(family, start, end, &mut networks);
+    }
+    networks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn strings(networks: Vec<IpNetwork>) -> Vec<String> {
+        networks.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn consecutive_block_collapses() {
+        let mut addrs: Vec<IpAddr> = (0..=255).map(|d| IpAddr::V4(192, 168, 7, d)).collect();
+        addrs.reverse();
+        assert_eq!(strings(summarize(&addrs)), ["192.168.7.0/24"]);
+    }
+
+    #[test]
+    fn aligned_four_collapse_to_slash_30() {
+        let addrs = [
+            addr("10.0.0.6"),
+            addr("10.0.0.4"),
+            addr("10.0.0.7"),
+            addr("10.0.0.5"),
+        ];
+        assert_eq!(strings(summarize(&addrs)), ["10.0.0.4/30"]);
+    }
+
+    #[test]
+    fn unaligned_pair_stays_split() {
+        let addrs = [addr("10.0.0.1"), addr("10.0.0.2")];
+        assert_eq!(strings(summarize(&addrs)), ["10.0.0.1/32", "10.0.0.2/32"]);
+    }
+
+    #[test]
+    fn unaligned_run() {
+        let addrs: Vec<IpAddr> = (3..=12).map(|d| IpAddr::V4(10, 0, 0, d)).collect();
+        assert_eq!(
+            strings(summarize(&addrs)),
+            ["10.0.0.3/32", "10.0.0.4/30", "10.0.0.8/30", "10.0.0.12/32"]
+        );
+    }
+
+    #[test]
+    fn duplicates_and_mixed_families() {
+        let addrs = [
+            addr("::1"),
+            addr("10.0.0.1"),
+            addr("::"),
+            addr("10.0.0.0"),
+            addr("10.0.0.1"),
+            addr("255.255.255.255"),
+        ];
+        assert_eq!(
+            strings(summarize(&addrs)),
+            ["10.0.0.0/31", "255.255.255.255/32", "::/127"]
+        );
+        assert!(summarize(&[]).is_empty());
+    }
+
+    #[test]
+    fn whole_spaces() {
+        let mut networks = Vec::new();
+        cover_range(addr("0.0.0.0"), 0, u32::MAX as u128, &mut networks);
+        cover_range(addr("::"), 0, u128::MAX, &mut networks);
+        cover_range(addr("::"), 1, u128::MAX, &mut networks);
+        assert_eq!(strings(networks.clone())[..2], ["0.0.0.0/0", "::/0"]);
+        assert_eq!(networks.len(), 2 + 128);
+        assert_eq!(networks[2].to_string(), "::1/128");
+        assert_eq!(networks[129].to_string(), "8000::/1");
+    }
+}
@@ -0,0 +1,257 @@
+use super::IpAddr;
+
+/// The category an address falls into, from `IpAddr::classify`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddrKind {
+    Unspecified,
+    Loopback,
+    Private,
+    LinkLocal,
+    Multicast,
+    Global,
+}
+
+/// A special-purpose block reported by `IpAddr::special_use`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpecialUse {
+    Documentation,
+    Benchmarking,
+    Shared,
+    Reserved,
+}
+
+impl IpAddr {
+    /// `0.0.0.0` or `::`.
+    pub fn is_unspecified(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, c, d) => a == 0 && b == 0 && c == 0 && d == 0,
+            IpAddr::V6(segments) => segments == [0; 8],
+        }
+    }
+
+    /// `127.0.0.0/8` or `::1`.
+    pub fn is_loopback(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, ..) => a == 127,
+            IpAddr::V6(segments) => segments == [0, 0, 0, 0, 0, 0, 0, 1],
+        }
+    }
+
+    /// `10.0.0.0/8`, `172.16.0.0/12`, `192.168.0.0/16` or the V6 unique
+    /// local range `fc00::/7`.
+    pub fn is_private(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => {
+                a == 10 || (a == 172 && b & 0xf0 == 16) || (a == 192 && b == 168)
+            }
+            IpAddr::V6(segments) => segments[0] & 0xfe00 == 0xfc00,
+        }
+    }
+
+    /// `224.0.0.0/4` or `ff00::/8`.
+    pub fn is_multicast(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, ..) => a & 0xf0 == 224,
+            IpAddr::V6(segments) => segments[0] & 0xff00 == 0xff00,
+        }
+    }
+
+    /// `169.254.0.0/16` or `fe80::/10`.
+    pub fn is_link_local(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => a == 169 && b == 254,
+            IpAddr::V6(segments) => segments[0] & 0xffc0 == 0xfe80,
+        }
+    }
+
+    /// The documentation ranges `192.0.2.0/24`, `198.51.100.0/24`,
+    /// `203.0.113.0/24` and `2001:db8::/32`.
+    pub fn is_documentation(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, c, _) => {
+                matches!((a, b, c), (192, 0, 2) | (198, 51, 100) | (203, 0, 113))
+            }
+            IpAddr::V6(segments) => segments[0] == 0x2001 && segments[1] == 0xdb8,
+        }
+    }
+
+    /// `198.18.0.0/15`; always false for V6.
+    pub fn is_benchmarking(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => a == 198 && b & 0xfe == 18,
+            IpAddr::V6(_) => false,
+        }
+    }
+
+    /// The carrier-grade NAT range `100.64.0.0/10`; always false for V6.
+    pub fn is_shared(&self) -> bool {
+        match *self {
+            IpAddr::V4(a, b, ..) => a == 100 && b & 0xc0 == 64,
+            IpAddr::V6(_) => false,
+        }
+    }
+
+    /// `240.0.0.0/4` except the broadcast address `255.255.255.255`;
+    /// always false for V6.
+    pub fn is_reserved(&self) -> bool {
+        match *self {
+            IpAddr::V4(255, 255, 255, 255) => false,
+            IpAddr::V4(a, ..) => a & 0xf0 == 240,
+            IpAddr::V6(_) => false,
+        }
+    }
+
+    /// Which special-purpose block, if any, the address belongs to. The
+    /// blocks don't overlap, so at most one applies.
+    pub fn special_use(&self) -> Option<SpecialUse> {
+        if self.is_documentation() {
+            Some(SpecialUse::Documentation)
+        } else if self.is_benchmarking() {
+            Some(SpecialUse::Benchmarking)
+        } else if self.is_shared() {
+            Some(SpecialUse::Shared)
+        } else if self.is_reserved() {
+            Some(SpecialUse::Reserved)
+        } else {
+            None
+        }
+    }
+
+    /// The first matching category in the order of `AddrKind`'s variants,
+    /// with `Global` for everything else.
+    pub fn classify(&self) -> AddrKind {
+        if self.is_unspecified() {
+            AddrKind::Unspecified
+        } else if self.is_loopback() {
+            AddrKind::Loopback
+        } else if self.is_private() {
+            AddrKind::Private
+        } else if self.is_link_local() {
+            AddrKind::LinkLocal
+        } else if self.is_multicast() {
+            AddrKind::Multicast
+        } else {
+            AddrKind::Global
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind(s: &str) -> AddrKind {
+        s.parse::<IpAddr>().unwrap().classify()
+    }
+
+    #[test]
+    fn unspecified_and_loopback() {
+        assert_eq!(kind("0.0.0.0"), AddrKind::Unspecified);
+        assert_eq!(kind("0.0.0.1"), AddrKind::Global);
+        assert_eq!(kind("::"), AddrKind::Unspecified);
+        assert_eq!(kind("126.255.255.255"), AddrKind::Global);
+        assert_eq!(kind("127.0.0.0"), AddrKind::Loopback);
+        assert_eq!(kind("127.255.255.255"), AddrKind::Loopback);
+        assert_eq!(kind("128.0.0.0"), AddrKind::Global);
+        assert_eq!(kind("::1"), AddrKind::Loopback);
+        assert_eq!(kind("::2"), AddrKind::Global);
+        assert_eq!(kind("1::1"), AddrKind::Global);
+    }
+
+    #[test]
+    fn private_boundaries() {
+        assert_eq!(kind("9.255.255.255"), AddrKind::Global);
+        assert_eq!(kind("10.0.0.0"), AddrKind::Private);
+        assert_eq!(kind("10.255.255.255"), AddrKind::Private);
+        assert_eq!(kind("11.0.0.0"), AddrKind::Global);
+        assert_eq!(kind("172.15.255.255"), AddrKind::Global);
+        assert_eq!(kind("172.16.0.0"), AddrKind::Private);
+        assert_eq!(kind("172.31.255.255"), AddrKind::Private);
+        assert_eq!(kind("172.32.0.0"), AddrKind::Global);
+        assert_eq!(kind("192.167.255.255"), AddrKind::Global);
+        assert_eq!(kind("192.168.0.0"), AddrKind::Private);
+        assert_eq!(kind("192.168.255.255"), AddrKind::Private);
+        assert_eq!(kind("192.169.0.0"), AddrKind::Global);
+        assert_eq!(kind("fbff:ffff::"), AddrKind::Global);
+        assert_eq!(kind("fc00::"), AddrKind::Private);
+        assert_eq!(kind("fdff:ffff::1"), AddrKind::Private);
+        assert_eq!(kind("fe00::"), AddrKind::Global);
+    }
+
+    #[test]
+    fn link_local_boundaries() {
+        assert_eq!(kind("169.253.255.255"), AddrKind::Global);
+        assert_eq!(kind("169.254.0.0"), AddrKind::LinkLocal);
+        assert_eq!(kind("169.254.255.255"), AddrKind::LinkLocal);
+        assert_eq!(kind("169.255.0.0"), AddrKind::Global);
+        assert_eq!(kind("fe7f:ffff::"), AddrKind::Global);
+        assert_eq!(kind("fe80::1"), AddrKind::LinkLocal);
+        assert_eq!(kind("febf:ffff::"), AddrKind::LinkLocal);
+        assert_eq!(kind("fec0::"), AddrKind::Global);
+    }
+
+    #[test]
+    fn multicast_boundaries() {
+        assert_eq!(kind("223.255.255.255"), AddrKind::Global);
+        assert_eq!(kind("224.0.0.0"), AddrKind::Multicast);
+        assert_eq!(kind("239.255.255.255"), AddrKind::Multicast);
+        assert_eq!(kind("240.0.0.0"), AddrKind::Global);
+        assert_eq!(kind("feff::"), AddrKind::Global);
+        assert_eq!(kind("ff00::"), AddrKind::Multicast);
+        assert_eq!(kind("ff02::1"), AddrKind::Multicast);
+    }
+
+    #[test]
+    fn predicates_agree_with_classify() {
+        let addr: IpAddr = "10.1.2.3".parse().unwrap();
+        assert!(addr.is_private());
+        assert!(!addr.is_loopback() && !addr.is_multicast() && !addr.is_link_local());
+        assert!(!addr.is_unspecified());
+    }
+
+    fn special(s: &str) -> Option<SpecialUse> {
+        s.parse::<IpAddr>().unwrap().special_use()
+    }
+
+    #[test]
+    fn documentation_boundaries() {
+        assert_eq!(special("192.0.1.255"), None);
+        assert_eq!(special("192.0.2.0"), Some(SpecialUse::Documentation));
+        assert_eq!(special("192.0.2.255"), Some(SpecialUse::Documentation));
+        assert_eq!(special("192.0.3.0"), None);
+        assert_eq!(special("198.51.100.7"), Some(SpecialUse::Documentation));
+        assert_eq!(special("198.51.101.0"), None);
+        assert_eq!(special("203.0.113.255"), Some(SpecialUse::Documentation));
+        assert_eq!(special("203.0.112.255"), None);
+        assert_eq!(special("2001:db8::"), Some(SpecialUse::Documentation));
+        assert_eq!(
+            special("2001:db8:ffff:ffff:ffff:ffff:ffff:ffff"),
+            Some(SpecialUse::Documentation)
+        );
+        assert_eq!(special("2001:db9::"), None);
+        assert_eq!(special("2001:db7:ffff::"), None);
+    }
+
+    #[test]
+    fn benchmarking_and_shared_boundaries() {
+        assert_eq!(special("198.17.255.255"), None);
+        assert_eq!(special("198.18.0.0"), Some(SpecialUse::Benchmarking));
+        assert_eq!(special("198.19.255.255"), Some(SpecialUse::Benchmarking));
+        assert_eq!(special("198.20.0.0"), None);
+        assert_eq!(special("100.63.255.255"), None);
+        assert_eq!(special("100.64.0.0"), Some(SpecialUse::Shared));
+        assert_eq!(special("100.127.255.255"), Some(SpecialUse::Shared));
+        assert_eq!(special("100.128.0.0"), None);
+        let v6: IpAddr = "::ffff:198.18.0.1".parse().unwrap();
+        assert!(!v6.is_benchmarking() && !v6.is_shared() && !v6.is_reserved());
+    }
+
+    #[test]
+    fn reserved_boundaries() {
+        assert_eq!(special("239.255.255.255"), None);
+        assert_eq!(special("240.0.0.0"), Some(SpecialUse::Reserved));
+        assert_eq!(special("255.255.255.254"), Some(SpecialUse::Reserved));
+        assert_eq!(special("255.255.255.255"), None);
+        assert_eq!(special("8.8.8.8"), None);
+    }
+}
@@ -0,0 +1,94 @@
+//! Conversions to and from the `std::net` address types. `From` goes both
+//! ways, so `TryFrom<IpAddr> for std::net::IpAddr` comes from the standard
+//! blanket impl and can never fail.
+
+use std::net::{self, Ipv4Addr, Ipv6Addr};
+
+use super::IpAddr;
+
+impl From<Ipv4Addr> for IpAddr {
+    fn from(addr: Ipv4Addr) -> IpAddr {
+        let [a, b, c, d] = addr.octets();
+        IpAddr::V4(a, b, c, d)
+    }
+}
+
+impl From<Ipv6Addr> for IpAddr {
+    fn from(addr: Ipv6Addr) -> IpAddr {
+        IpAddr::V6(addr.segments())
+    }
+}
+
+impl From<net::IpAddr> for IpAddr {
+    fn from(addr: net::IpAddr) -> IpAddr {
+        IpAddr::from_std(addr)
+    }
+}
+
+impl From<IpAddr> for net::IpAddr {
+    fn from(addr: IpAddr) -> net::IpAddr {
+        addr.to_std()
+    }
+}
+
+impl IpAddr {
+    pub fn to_std(&self) -> net::IpAddr {
+        match *self {
+            IpAddr::V4(a, b, c, d) => net::IpAddr::V4(Ipv4Addr::new(a, b, c, d)),
+            IpAddr::V6(s) => net::IpAddr::V6(Ipv6Addr::new(
+                s[0], s[1], s[2], s[3], s[4], s[5], s[6], s[7],
+            )),
+        }
+    }
+
+    pub fn from_std(addr: net::IpAddr) -> IpAddr {
+        match addr {
+            net::IpAddr::V4(v4) => v4.into(),
+            net::IpAddr::V6(v6) => v6.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_both_families() {
+        for s in [
+            "127.0.0.1",
+            "255.0.10.1",
+            "::",
+            "::1",
+            "2001:db8::8a2e:370:7334",
+        ]
+        .iter()
+        {
+            let ours: IpAddr = s.parse().unwrap();
+            let std: net::IpAddr = s.parse().unwrap();
+            assert_eq!(ours.to_std(), std);
+            assert_eq!(IpAddr::from_std(std), ours);
+            assert_eq!(net::IpAddr::from(ours).to_string(), ours.to_string());
+        }
+    }
+
+    #[test]
+    fn from_specific_families() {
+        assert_eq!(
+            IpAddr::from(Ipv4Addr::new(10, 0, 0, 1)),
+            IpAddr::V4(10, 0, 0, 1)
+        );
+        assert_eq!(IpAddr::from(Ipv6Addr::LOCALHOST), IpAddr::V6_LOOPBACK);
+    }
+
+    #[test]
+    fn binds_a_listener() {
+        let listener = net::TcpListener::bind((IpAddr::V4(127, 0, 0, 1).to_std(), 0)).unwrap();
+        let local = listener.local_addr().unwrap();
+        assert_eq!(IpAddr::from(local.ip()), IpAddr::V4(127, 0, 0, 1));
+        assert_eq!(
+            format!("{}:{}", IpAddr::from(local.ip()), local.port()),
+            local.to_string()
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use super::{AddrParseError, IpAddr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointParseError {
+    MissingPort,
+    InvalidPort,
+    PortOutOfRange,
+    /// A V6 address must be written in brackets, as in `[::1]:443`.
+    MissingBrackets,
+    /// Brackets around a V4 address.
+    UnexpectedBrackets,
+    UnclosedBracket,
+    Addr(AddrParseError),
+}
+
+impl fmt::Display for EndpointParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EndpointParseError::MissingPort => write!(f, "missing `:port`"),
+            EndpointParseError::InvalidPort => write!(f, "invalid port"),
+            EndpointParseError::PortOutOfRange => write!(f, "port larger than 65535"),
+            EndpointParseError::MissingBrackets => {
+                write!(f, "V6 addresses must be bracketed, e.g. [::1]:443")
+            }
+            EndpointParseError::UnexpectedBrackets => {
+                write!(f, "V4 addresses must not be bracketed")
+            }
+            EndpointParseError::UnclosedBracket => write!(f, "missing `]`"),
+            EndpointParseError::Addr(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for EndpointParseError {}
+
+/// An address and port, written `1.2.3.4:8080` or `[::1]:443`.
+///
+/// Endpoints order by address, then port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SocketEndpoint {
+    pub addr: IpAddr,
+    pub port: u16,
+}
+
+impl SocketEndpoint {
+    pub fn new(addr: IpAddr, port: u16) -> SocketEndpoint {
+        SocketEndpoint { addr, port }
+    }
+
+    /// Port 0, which asks the OS to pick a free port when binding.
+    pub fn is_ephemeral_request(&self) -> bool {
+        self.port == 0
+    }
+
+    pub fn to_std(&self) -> SocketAddr {
+        SocketAddr::new(self.addr.to_std(), self.port)
+    }
+}
+
+fn parse_port(s: &str) -> Result<u16, EndpointParseError> {
+    if s.is_empty() {
+        return Err(EndpointParseError::MissingPort);
+    }
+    if !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(EndpointParseError::InvalidPort);
+    }
+    s.parse().map_err(|_| EndpointParseError::PortOutOfRange)
+}
+
+impl FromStr for SocketEndpoint {
+    type Err = EndpointParseError;
+
+    fn from_str(s: &str) -> Result<SocketEndpoint, EndpointParseError> {
+        let (addr, port) = if let Some(rest) = s.strip_prefix('[') {
+            let close = rest.find(']').ok_or(EndpointParseError::UnclosedBracket)?;
+            let port = rest[close + 1..]
+                .strip_prefix(':')
+                .ok_or(EndpointParseError::MissingPort)?;
+            let addr = &rest[..close];
+            if let Ok(IpAddr::V4(..)) = addr.parse() {
+                return Err(EndpointParseError::UnexpectedBrackets);
+            }
+            let addr = IpAddr::v6_from_str(addr).map_err(EndpointParseError::Addr)?;
+            (addr, port)
+        } else {
+            let colon = s.rfind(':').ok_or(EndpointParseError::MissingPort)?;
+            if s[..colon].contains(':') {
+                return Err(EndpointParseError::MissingBrackets);
+            }
+            let addr = s[..colon].parse().map_err(EndpointParseError::Addr)?;
+            (addr, &s[colon + 1..])
+        };
+        Ok(SocketEndpoint {
+            addr,
+            port: parse_port(port)?,
+        })
+    }
+}
+
+impl fmt::Display for SocketEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.addr {
+            IpAddr::V4(..) => write!(f, "{}:{}", self.addr, self.port),
+            IpAddr::V6(_) => write!(f, "[{}]:{}", self.addr, self.port),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<SocketEndpoint, EndpointParseError> {
+        s.parse()
+    }
+
+    #[test]
+    fn round_trips() {
+        for s in [
+            "1.2.3.4:8080",
+            "[::1]:443",
+            "[2001:db8::1]:0",
+            "0.0.0.0:65535",
+        ]
+        .iter()
+        {
+            let endpoint = parse(s).unwrap();
+            assert_eq!(endpoint.to_string(), *s);
+            assert_eq!(endpoint.to_std().to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn port_zero_is_allowed_but_flagged() {
+        let endpoint = parse("127.0.0.1:0").unwrap();
+        assert!(endpoint.is_ephemeral_request());
+        assert!(!parse("127.0.0.1:1").unwrap().is_ephemeral_request());
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!(parse(":::80"), Err(EndpointParseError::MissingBrackets));
+        assert_eq!(parse("::1:80"), Err(EndpointParseError::MissingBrackets));
+        assert_eq!(parse("1.2.3.4:"), Err(EndpointParseError::MissingPort));
+        assert_eq!(parse("1.2.3.4"), Err(EndpointParseError::MissingPort));
+        assert_eq!(parse("[::1]443"), Err(EndpointParseError::MissingPort));
+        assert_eq!(parse("[::1]"), Err(EndpointParseError::MissingPort));
+        assert_eq!(parse("[::1:443"), Err(EndpointParseError::UnclosedBracket));
+        assert_eq!(
+            parse("[1.2.3.4]:80"),
+            Err(EndpointParseError::UnexpectedBrackets)
+        );
+        assert_eq!(
+            parse("1.2.3.4:65536"),
+            Err(EndpointParseError::PortOutOfRange)
+        );
+        assert_eq!(
+            parse("1.2.3.4:99999999999999999999"),
+            Err(EndpointParseError::PortOutOfRange)
+        );
+        assert_eq!(parse("1.2.3.4:+80"), Err(EndpointParseError::InvalidPort));
+        assert_eq!(parse("1.2.3.4:8o"), Err(EndpointParseError::InvalidPort));
+        assert_eq!(
+            parse("1.2.3:80"),
+            Err(EndpointParseError::Addr(AddrParseError::TooFewOctets))
+        );
+    }
+
+    #[test]
+    fn orders_by_address_then_port() {
+        let mut endpoints: Vec<SocketEndpoint> =
+            ["[::1]:1", "10.0.0.1:80", "10.0.0.1:22", "9.0.0.1:443"]
+                .iter()
+                .map(|s| s.parse().unwrap())
+                .collect();
+        endpoints.sort();
+        let sorted: Vec<String> = endpoints.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            sorted,
+            ["9.0.0.1:443", "10.0.0.1:22", "10.0.0.1:80", "[::1]:1"]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! A hosts-file-like format: each line is `ADDRESS NAME [ALIASES...]`,
+//! separated by whitespace. `#` starts a comment and blank lines are
+//! ignored.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::io::{self, BufRead};
+
+use super::{AddrParseError, IpAddr};
+
+#[derive(Debug)]
+pub enum HostsError {
+    Io(io::Error),
+    InvalidAddress {
+        line: usize,
+        token: String,
+        source: AddrParseError,
+    },
+    /// An address with no name after it.
+    MissingName {
+        line: usize,
+        token: String,
+    },
+}
+
+impl fmt::Display for HostsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostsError::Io(e) => write!(f, "read failed: {}", e),
+            HostsError::InvalidAddress {
+                line,
+                token,
+                source,
+            } => write!(f, "line {}: invalid address {:?}: {}", line, token, source),
+            HostsError::MissingName { line, token } => {
+                write!(f, "line {}: no name for {}", line, token)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HostsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HostsError::Io(e) => Some(e),
+            HostsError::InvalidAddress { source, .. } => Some(source),
+            HostsError::MissingName { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for HostsError {
+    fn from(e: io::Error) -> Self {
+        HostsError::Io(e)
+    }
+}
+
+/// Maps every name and alias to its addresses, in the order they first
+/// appear. Line numbers in errors start at 1.
+pub fn parse<R: BufRead>(reader: R) -> Result<HashMap<String, Vec<IpAddr>>, HostsError> {
+    let mut hosts: HashMap<String, Vec<IpAddr>> = HashMap::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let number = index + 1;
+        let content = line.split('#').next().unwrap_or("");
+        let mut tokens = content.split_whitespace();
+        let token = match tokens.next() {
+            Some(token) => token,
+            None => continue,
+        };
+        let addr: IpAddr = token.parse().map_err(|source| HostsError::InvalidAddress {
+            line: number,
+            token: token.to_string(),
+            source,
+        })?;
+        let mut names = tokens.peekable();
+        if names.peek().is_none() {
+            return Err(HostsError::MissingName {
+                line: number,
+                token: token.to_string(),
+            });
+        }
+        for name in names {
+            let addrs = hosts.entry(name.to_string()).or_default();
+            if !addrs.contains(&addr) {
+                addrs.push(addr);
+            }
+        }
+    }
+    Ok(hosts)
+}
+
+/// One line per address in address order, each listing its names sorted.
+/// This normalizes the order of each name's addresses: parsing the output
+/// gives every name its addresses sorted, not in the order `hosts` had
+/// them.
+pub fn format(hosts: &HashMap<String, Vec<IpAddr>>) -> String {
+    let mut by_addr: BTreeMap<IpAddr, BTreeSet<&str>> = BTreeMap::new();
+    for (name, addrs) in hosts {
+        for addr in addrs {
+            by_addr.entry(*addr).or_default().insert(name);
+        }
+    }
+    let mut out = String::new();
+    for (addr, names) in by_addr {
+        out.push_str(&addr.to_string());
+        for name in names {
+            out.push(' ');
+            out.push_str(name);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    const SAMPLE: &str = "\
+# static entries
+127.0.0.1   localhost
+::1         localhost ip6-localhost   # both families
+
+10.0.0.5    build build.lan
+10.0.0.6    build
+10.0.0.5    build
+";
+
+    #[test]
+    fn parses_names_and_aliases() {
+        let hosts = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(hosts.len(), 4);
+        assert_eq!(hosts["localhost"], vec![addr("127.0.0.1"), addr("::1")]);
+        assert_eq!(hosts["ip6-localhost"], vec![addr("::1")]);
+        assert_eq!(hosts["build"], vec![addr("10.0.0.5"), addr("10.0.0.6")]);
+        assert_eq!(hosts["build.lan"], vec![addr("10.0.0.5")]);
+    }
+
+    #[test]
+    fn formats_sorted() {
+        let hosts = parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!(
+            format(&hosts),
+            "10.0.0.5 build build.lan\n\
+             10.0.0.6 build\n\
+             127.0.0.1 localhost\n\
+             ::1 ip6-localhost localhost\n"
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let hosts = parse(SAMPLE.as_bytes()).unwrap();
+        let text = format(&hosts);
+        let reparsed = parse(text.as_bytes()).unwrap();
+        assert_eq!(reparsed, hosts);
+        assert_eq!(format(&reparsed), text);
+    }
+
+    #[test]
+    fn round_trip_sorts_each_names_addresses() {
+        let unsorted = "\
+10.0.0.9 db
+::1      db localhost
+10.0.0.2 db
+127.0.0.1 localhost
+";
+        let hosts = parse(unsorted.as_bytes()).unwrap();
+        assert_eq!(
+            hosts["db"],
+            vec![addr("10.0.0.9"), addr("::1"), addr("10.0.0.2")]
+        );
+        let text = format(&hosts);
+        let reparsed = parse(text.as_bytes()).unwrap();
+        assert_eq!(
+            reparsed["db"],
+            vec![addr("10.0.0.2"), addr("10.0.0.9"), addr("::1")]
+        );
+        assert_eq!(reparsed["localhost"], vec![addr("127.0.0.1"), addr("::1")]);
+        let mut sorted = hosts;
+        for addrs in sorted.values_mut() {
+            addrs.sort();
+        }
+        assert_eq!(reparsed, sorted);
+        assert_eq!(format(&reparsed), text);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        match parse("127.0.0.1 localhost\n# ok\n10.0.0.300 bad\n".as_bytes()) {
+            Err(HostsError::InvalidAddress {
+                line: 3,
+                token,
+                source: AddrParseError::OctetOutOfRange,
+            }) => assert_eq!(token, "10.0.0.300"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match parse("\n  10.0.0.1   # no name\n".as_bytes()) {
+            Err(HostsError::MissingName { line: 2, token }) => assert_eq!(token, "10.0.0.1"),
+            other => panic!("unexpected {:?}", other),
+        }
+        let err = parse("localhost 127.0.0.1\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1: invalid address \"localhost\": invalid character at byte 0"
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use super::{IpAddr, IpNetwork};
+
+/// One bit position in the trie. A full node covers every address below it
+/// and never has children; a node whose two children become full is
+/// collapsed into a full node itself.
+#[derive(Debug, Clone, Default)]
+struct Node {
+    full: bool,
+    children: [Option<Box<Node>>; 2],
+}
+
+/// Whether the bit at `depth`, counting from the most significant of
+/// `bit_len`, is set in `bits`.
+fn bit_at(bits: u128, depth: u32, bit_len: u32) -> usize {
+    (bits >> (bit_len - 1 - depth)) as usize & 1
+}
+
+impl Node {
+    /// Marks the first `prefix_len` bits of `bits` as full, returning
+    /// whether this node is full afterwards.
+    fn insert(&mut self, bits: u128, depth: u32, prefix_len: u32, bit_len: u32) -> bool {
+        if self.full {
+            return true;
+        }
+        if depth == prefix_len {
+            self.full = true;
+            self.children = [None, None];
+            return true;
+        }
+        let bit = bit_at(bits, depth, bit_len);
+        let child = self.children[bit].get_or_insert_with(Default::default);
+        let child_full = child.insert(bits, depth + 1, prefix_len, bit_len);
+        let sibling_full = self.children[1 - bit].as_ref().is_some_and(|s| s.full);
+        if child_full && sibling_full {
+            self.full = true;
+            self.children = [None, None];
+        }
+        self.full
+    }
+
+    /// The number of addresses below this node, which sits `depth` bits into
+    /// a `bit_len`-bit address.
+    fn len(&self, depth: u32, bit_len: u32) -> u128 {
+        if self.full {
+            return 1u128.checked_shl(bit_len - depth).unwrap_or(u128::MAX);
+        }
+        self.children.iter().flatten().fold(0u128, |sum, child| {
+            sum.saturating_add(child.len(depth + 1, bit_len))
+        })
+    }
+}
+
+/// A set of addresses stored as one binary trie per family, keyed on address
+/// bits. Lookups take at most `bit_len` steps no matter how many networks
+/// have been inserted.
+#[derive(Debug, Clone, Default)]
+pub struct IpSet {
+    v4: Node,
+    v6: Node,
+}
+
+impl IpSet {
+    pub fn new() -> IpSet {
+        IpSet::default()
+    }
+
+    fn root(&self, family: &IpAddr) -> &Node {
+        match family {
+            IpAddr::V4(..) => &self.v4,
+            IpAddr::V6(_) => &self.v6,
+        }
+    }
+
+    pub fn insert(&mut self, addr: IpAddr) {
+        let network = IpNetwork::new(addr, addr.bit_len()).unwrap();
+        self.insert_network(network);
+    }
+
+    /// Adds every address in `network`. Networks already covered by the set
+    /// leave it unchanged.
+    pub fn insert_network(&mut self, network: IpNetwork) {
+        let addr = network.network();
+        let root = match addr {
+            IpAddr::V4(..) => &mut self.v4,
+            IpAddr::V6(_) => &mut self.v6,
+        };
+        let bit_len = addr.bit_len() as u32;
+        root.insert(addr.to_bits(), 0, network.prefix_len() as u32, bit_len);
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.lookup(addr).0
+    }
+
+    /// Whether `addr` is in the set, and how many nodes were visited to
+    /// find out: at most one more than the address's bit length.
+    fn lookup(&self, addr: &IpAddr) -> (bool, usize) {
+        let bits = addr.to_bits();
+        let bit_len = addr.bit_len() as u32;
+        let mut node = self.root(addr);
+        for depth in 0..bit_len {
+            if node.full {
+                return (true, depth as usize + 1);
+            }
+            match &node.children[bit_at(bits, depth, bit_len)] {
+                Some(child) => node = child,
+                None => return (false, depth as usize + 1),
+            }
+        }
+        (node.full, bit_len as usize + 1)
+    }
+
+    /// The number of individual addresses in the set. Like
+    /// `IpNetwork::num_addresses`, this saturates at `u128::MAX` when the
+    /// whole V6 space is included.
+    pub fn len(&self) -> u128 {
+        self.v4.len(0, 32).saturating_add(self.v6.len(0, 128))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The minimal set of networks covering exactly the addresses in the
+    /// set, in address order with V4 first.
+    pub fn iter_networks(&self) -> Networks<'_> {
+        Networks {
+            stack: vec![
+                (&self.v6, IpAddr::V6_UNSPECIFIED, 0, 0),
+                (&self.v4, IpAddr::V4(0, 0, 0, 0), 0, 0),
+            ],
+        }
+    }
+}
+
+/// Iterator returned by `IpSet::iter_networks`.
+#[derive(Debug, Clone)]
+pub struct Networks<'a> {
+    // Nodes still to visit, with their family, address bits and depth. The
+    // next one to visit is on top.
+    stack: Vec<(&'a Node, IpAddr, u128, u32)>,
+}
+
+impl<'a> Iterator for Networks<'a> {
+    type Item = IpNetwork;
+
+    fn next(&mut self) -> Option<IpNetwork> {
+        while let Some((node, family, bits, depth)) = self.stack.pop() {
+            if node.full {
+                let network = IpNetwork::new(family.with_bits(bits), depth as u8).unwrap();
+                return Some(network);
+            }
+            let bit_len = family.bit_len() as u32;
+            for bit in (0..2).rev() {
+                if let Some(child) = &node.children[bit] {
+                    let child_bits = bits | (bit as u128) << (bit_len - 1 - depth);
+                    self.stack.push((child, family, child_bits, depth + 1));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ip::testing::AddrGen;
+    use std::collections::HashSet;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn net(s: &str) -> IpNetwork {
+        s.parse().unwrap()
+    }
+
+    fn networks(set: &IpSet) -> Vec<String> {
+        set.iter_networks().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn insert_and_contains() {
+        let mut set = IpSet::new();
+        assert!(set.is_empty());
+        set.insert(addr("10.0.0.1"));
+        set.insert_network(net("192.168.0.0/16"));
+        set.insert_network(net("2001:db8::/32"));
+        assert!(set.contains(&addr("10.0.0.1")));
+        assert!(!set.contains(&addr("10.0.0.2")));
+        assert!(set.contains(&addr("192.168.255.255")));
+        assert!(!set.contains(&addr("192.169.0.0")));
+        assert!(set.contains(&addr("2001:db8:1::1")));
+        assert!(!set.contains(&addr("::ffff:10.0.0.1")));
+        assert_eq!(set.len(), 1 + 65536 + (1 << 96));
+    }
+
+    #[test]
+    fn iter_networks_is_minimal() {
+        let mut set = IpSet::new();
+        for last in 0..8 {
+            set.insert(IpAddr::V4(10, 0, 0, last));
+        }
+        set.insert(addr("10.0.0.9"));
+        set.insert_network(net("::/1"));
+        set.insert_network(net("8000::/1"));
+        set.insert_network(net("10.0.0.0/30"));
+        assert_eq!(networks(&set), ["10.0.0.0/29", "10.0.0.9/32", "::/0"]);
+        assert_eq!(set.len(), u128::MAX);
+
+        set.insert_network(net("0.0.0.0/0"));
+        assert_eq!(networks(&set), ["0.0.0.0/0", "::/0"]);
+    }
+
+    #[test]
+    fn matches_hash_set_baseline() {
+        let space = net("10.0.0.0/20");
+        let mut gen = AddrGen::new(62).within(&space);
+        let mut set = IpSet::new();
+        let mut baseline = HashSet::new();
+        for addr in gen.addrs(2000) {
+            set.insert(addr);
+            baseline.insert(addr);
+        }
+        for addr in gen.addrs(20) {
+            let block = IpNetwork::new(addr, 28).unwrap();
+            set.insert_network(block);
+            let first = block.network().to_bits();
+            baseline.extend((first..first + 16).map(|bits| addr.with_bits(bits)));
+        }
+        assert_eq!(set.len(), baseline.len() as u128);
+        let first = space.network().to_bits();
+        for bits in first..first + space.num_addresses() {
+            let addr = space.network().with_bits(bits);
+            assert_eq!(set.contains(&addr), baseline.contains(&addr), "{}", addr);
+        }
+        let covered: u128 = set.iter_networks().map(|n| n.num_addresses()).sum();
+        assert_eq!(covered, set.len());
+    }
+
+    #[test]
+    fn visits_fewer_nodes_than_a_linear_scan() {
+        let addrs = AddrGen::new(7).within(&net("0.0.0.0/0")).addrs(10_000);
+        let mut set = IpSet::new();
+        let mut list = Vec::new();
+        for addr in &addrs {
+            set.insert(*addr);
+            list.push(IpNetwork::new(*addr, 32).unwrap());
+        }
+        let queries = &addrs[..1000];
+
+        let mut visited = 0;
+        for addr in queries {
+            let (found, nodes) = set.lookup(addr);
+            assert!(found);
+            assert!(nodes <= 33);
+            visited += nodes;
+        }
+
+        let compared: usize = queries
+            .iter()
+            .map(|a| list.iter().position(|n| n.contains(a)).unwrap() + 1)
+            .sum();
+
+        assert!(
+            visited * 10 < compared,
+            "trie visited {}, linear scan compared {}",
+            visited,
+            compared
+        );
+    }
+}
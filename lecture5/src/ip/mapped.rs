@@ -0,0 +1,88 @@
+use super::IpAddr;
+
+impl IpAddr {
+    /// `::ffff:a.b.c.d` for a V4 address; V6 addresses are returned as is.
+    pub fn to_ipv6_mapped(&self) -> IpAddr {
+        match *self {
+            IpAddr::V4(a, b, c, d) => IpAddr::V6([
+                0,
+                0,
+                0,
+                0,
+                0,
+                0xffff,
+                u16::from_be_bytes([a, b]),
+                u16::from_be_bytes([c, d]),
+            ]),
+            IpAddr::V6(_) => *self,
+        }
+    }
+
+    /// True for V6 addresses in `::ffff:0:0/96`.
+    pub fn is_ipv4_mapped(&self) -> bool {
+        matches!(self, IpAddr::V6([0, 0, 0, 0, 0, 0xffff, _, _]))
+    }
+
+    /// The V4 address embedded in a mapped (`::ffff:a.b.c.d`) or compatible
+    /// (`::a.b.c.d`) V6 address. As with `std`, `::` and `::1` count as
+    /// compatible. V4 addresses are returned as is.
+    pub fn to_ipv4(&self) -> Option<IpAddr> {
+        match *self {
+            IpAddr::V4(..) => Some(*self),
+            IpAddr::V6([0, 0, 0, 0, 0, 0xffff, hi, lo])
+            | IpAddr::V6([0, 0, 0, 0, 0, 0, hi, lo]) => {
+                Some(IpAddr::v4_from_u32((hi as u32) << 16 | lo as u32))
+            }
+            IpAddr::V6(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn round_trips_through_mapped() {
+        let v4 = IpAddr::V4(192, 0, 2, 128);
+        let mapped = v4.to_ipv6_mapped();
+        assert_eq!(
+            mapped.segments(),
+            Some([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0280])
+        );
+        assert!(mapped.is_ipv4_mapped());
+        assert_eq!(mapped.to_ipv4(), Some(v4));
+        assert_eq!(mapped.to_ipv6_mapped(), mapped);
+        assert!(!v4.is_ipv4_mapped());
+    }
+
+    #[test]
+    fn compatible_addresses() {
+        assert_eq!(addr("::1.2.3.4").to_ipv4(), Some(IpAddr::V4(1, 2, 3, 4)));
+        assert_eq!(IpAddr::V6_LOOPBACK.to_ipv4(), Some(IpAddr::V4(0, 0, 0, 1)));
+        assert!(!addr("::1.2.3.4").is_ipv4_mapped());
+    }
+
+    #[test]
+    fn plain_v6_has_no_v4() {
+        assert_eq!(addr("2001:db8::1").to_ipv4(), None);
+        assert_eq!(addr("::fffe:1.2.3.4").to_ipv4(), None);
+        assert_eq!(addr("1::ffff:1.2.3.4").to_ipv4(), None);
+    }
+
+    #[test]
+    fn displays_mixed_notation() {
+        assert_eq!(
+            IpAddr::V4(1, 2, 3, 4).to_ipv6_mapped().to_string(),
+            "::ffff:1.2.3.4"
+        );
+        assert_eq!(addr("::FFFF:0102:0304").to_string(), "::ffff:1.2.3.4");
+        assert_eq!(addr("::ffff:0.0.0.0").to_string(), "::ffff:0.0.0.0");
+        assert_eq!(addr("::ffff:1.2.3.4").to_string(), "::ffff:1.2.3.4");
+        assert_eq!(addr("::fffe:1.2.3.4").to_string(), "::fffe:102:304");
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+pub mod access;
+mod arith;
+mod bytes;
+pub mod cidr;
+mod classify;
+mod convert;
+mod endpoint;
+pub mod hosts;
+pub mod ip_set;
+mod mapped;
+mod network;
+mod parse;
+mod pattern;
+mod ptr;
+mod range;
+pub mod route_table;
+mod scoped;
+pub mod testing;
+mod visit;
+
+pub use bytes::AddrOctets;
+pub use classify::{AddrKind, SpecialUse};
+pub use endpoint::{EndpointParseError, SocketEndpoint};
+pub use network::{Hosts, IpNetwork, NetworkError, NetworkParseError, SubnetError, SubnetIter};
+pub use parse::AddrParseError;
+pub use pattern::{IpPattern, OctetPattern, PatternError};
+pub use ptr::PtrParseError;
+pub use range::{IpRange, RangeError, RangeIter};
+pub use scoped::ScopedV6;
+
+/// The eight 16-bit segments of a V6 address, most significant first.
+pub type Ipv6Repr = [u16; 8];
+
+/// An IP address. Every spelling of a V6 address parses to the same value.
+///
+/// Addresses are totally ordered: all V4 addresses come before all V6 ones,
+/// and within a family they compare numerically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpAddr {
+    V4(u8, u8, u8, u8),
+    V6(Ipv6Repr),
+}
+
+impl IpAddr {
+    /// `::1`
+    pub const V6_LOOPBACK: IpAddr = IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]);
+    /// `::`
+    pub const V6_UNSPECIFIED: IpAddr = IpAddr::V6([0; 8]);
+
+    /// The segments of a V6 address, or `None` for V4.
+    pub fn segments(&self) -> Option<[u16; 8]> {
+        match self {
+            IpAddr::V4(..) => None,
+            IpAddr::V6(segments) => Some(*segments),
+        }
+    }
+
+    /// The 16 bytes of a V6 address in network order, or `None` for V4.
+    pub fn octets_v6(&self) -> Option<[u8; 16]> {
+        let segments = self.segments()?;
+        let mut octets = [0u8; 16];
+        for (i, segment) in segments.iter().enumerate() {
+            octets[2 * i..2 * i + 2].copy_from_slice(&segment.to_be_bytes());
+        }
+        Some(octets)
+    }
+
+    /// 32 for V4, 128 for V6.
+    pub fn bit_len(&self) -> u8 {
+        match self {
+            IpAddr::V4(..) => 32,
+            IpAddr::V6(..) => 128,
+        }
+    }
+
+    /// The V4 address as a big-endian integer, or `None` for V6.
+    pub fn v4_to_u32(&self) -> Option<u32> {
+        match *self {
+            IpAddr::V4(a, b, c, d) => Some(u32::from_be_bytes([a, b, c, d])),
+            IpAddr::V6(_) => None,
+        }
+    }
+
+    pub fn v4_from_u32(bits: u32) -> IpAddr {
+        let [a, b, c, d] = bits.to_be_bytes();
+        IpAddr::V4(a, b, c, d)
+    }
+
+    /// Keeps the top `prefix_len` bits and clears the rest, or `None` when
+    /// the prefix is longer than the address.
+    pub fn apply_mask(&self, prefix_len: u8) -> Option<IpAddr> {
+        if prefix_len > self.bit_len() {
+            return None;
+        }
+        Some(self.with_bits(self.to_bits() & network::prefix_mask(prefix_len, self.bit_len())))
+    }
+
+    /// How many leading bits the two addresses share, or `None` when they
+    /// are of different families.
+    pub fn common_prefix_len(&self, other: &IpAddr) -> Option<u8> {
+        if !self.same_family(other) {
+            return None;
+        }
+        let unused = 128 - self.bit_len() as u32;
+        let differing = (self.to_bits() ^ other.to_bits()) << unused;
+        Some(differing.leading_zeros().min(self.bit_len() as u32) as u8)
+    }
+
+    pub(crate) fn to_bits(self) -> u128 {
+        match self {
+            IpAddr::V4(a, b, c, d) => u32::from_be_bytes([a, b, c, d]) as u128,
+            IpAddr::V6(segments) => segments
+                .iter()
+                .fold(0, |bits, &segment| bits << 16 | segment as u128),
+        }
+    }
+
+    /// An address of the same family as `self` with the given bits. For V4
+    /// only the low 32 bits are used.
+    pub(crate) fn with_bits(self, bits: u128) -> IpAddr {
+        match self {
+            IpAddr::V4(..) => IpAddr::v4_from_u32(bits as u32),
+            IpAddr::V6(_) => {
+                let mut segments = [0u16; 8];
+                for (i, segment) in segments.iter_mut().enumerate() {
+                    *segment = (bits >> (112 - 16 * i)) as u16;
+                }
+                IpAddr::V6(segments)
+            }
+        }
+    }
+
+    pub(crate) fn same_family(&self, other: &IpAddr) -> bool {
+        self.bit_len() == other.bit_len()
+    }
+}
+
+/// Sorts `addrs` and removes duplicates.
+pub fn sort_and_dedup(addrs: &mut Vec<IpAddr>) {
+    addrs.sort();
+    addrs.dedup();
+}
+
+pub fn unique(addrs: impl IntoIterator<Item = IpAddr>) -> BTreeSet<IpAddr> {
+    addrs.into_iter().collect()
+}
+
+/// The longest run of at least two zero segments, as `(start, len)`. Ties go
+/// to the first run.
+fn longest_zero_run(segments: &[u16; 8]) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut i = 0;
+    while i < 8 {
+        if segments[i] != 0 {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < 8 && segments[i] == 0 {
+            i += 1;
+        }
+        let len = i - start;
+        if len >= 2 && best.is_none_or(|(_, best_len)| len > best_len) {
+            best = Some((start, len));
+        }
+    }
+    best
+}
+
+fn write_groups(f: &mut fmt::Formatter, segments: &[u16]) -> fmt::Result {
+    for (i, segment) in segments.iter().enumerate() {
+        if i > 0 {
+            write!(f, ":")?;
+        }
+        write!(f, "{:x}", segment)?;
+    }
+    Ok(())
+}
+
+/// V4 prints as `a.b.c.d`. V6 prints in the RFC 5952 canonical form:
+/// lowercase hex without leading zeros, with the longest run of two or more
+/// zero segments replaced by `::`. IPv4-mapped addresses use the mixed
+/// notation `::ffff:a.b.c.d`.
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IpAddr::V4(a, b, c, d) => write!(f, "{}.{}.{}.{}", a, b, c, d),
+            IpAddr::V6(_) if self.is_ipv4_mapped() => {
+                write!(f, "::ffff:{}", self.to_ipv4().unwrap())
+            }
+            IpAddr::V6(segments) => match longest_zero_run(segments) {
+                Some((start, len)) => {
+                    write_groups(f, &segments[..start])?;
+                    write!(f, "::")?;
+                    write_groups(f, &segments[start + len..])
+                }
+                None => write_groups(f, segments),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_round_trips() {
+        for s in [
+            "127.0.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "2001:db8::1",
+            "1::",
+            "1:2:3:4:5:6:7:8",
+            "2001:db8:0:1:1:1:1:1",
+            "2001:db8::1:0:0:1",
+            "1:0:0:2::3",
+            "::3:4:5:6:7:8",
+        ]
+        .iter()
+        {
+            let addr: IpAddr = s.parse().unwrap();
+            assert_eq!(addr.to_string(), *s);
+        }
+    }
+
+    #[test]
+    fn display_is_canonical() {
+        let canonical = |s: &str| s.parse::<IpAddr>().unwrap().to_string();
+        assert_eq!(canonical("0:0:0:0:0:0:0:1"), "::1");
+        assert_eq!(
+            canonical("2001:0DB8:0000:0000:0000:0000:0000:0001"),
+            "2001:db8::1"
+        );
+        // A single zero segment is not compressed.
+        assert_eq!(canonical("2001:db8::1:1:1:1:1"), "2001:db8:0:1:1:1:1:1");
+        // The longer of two zero runs is compressed, or the first on a tie.
+        assert_eq!(canonical("1:0:0:2:0:0:0:3"), "1:0:0:2::3");
+        assert_eq!(canonical("1:0:0:2:3:0:0:4"), "1::2:3:0:0:4");
+    }
+
+    #[test]
+    fn bits_round_trip() {
+        let v4 = IpAddr::V4(192, 168, 1, 2);
+        assert_eq!(v4.to_bits(), 0xc0a8_0102);
+        assert_eq!(v4.with_bits(v4.to_bits()), v4);
+        let v6: IpAddr = "2001:db8::ff00:42:8329".parse().unwrap();
+        assert_eq!(v6.to_bits(), 0x2001_0db8_0000_0000_0000_ff00_0042_8329);
+        assert_eq!(v6.with_bits(v6.to_bits()), v6);
+    }
+
+    #[test]
+    fn u32_round_trip() {
+        for &bits in [0, 1, 0x7f00_0001, 0xc0a8_0101, u32::MAX].iter() {
+            let addr = IpAddr::v4_from_u32(bits);
+            assert_eq!(addr.v4_to_u32(), Some(bits));
+        }
+        assert_eq!(IpAddr::v4_from_u32(0x0a00_0102), IpAddr::V4(10, 0, 1, 2));
+        assert_eq!(IpAddr::V6_LOOPBACK.v4_to_u32(), None);
+    }
+
+    #[test]
+    fn masks() {
+        let addr = IpAddr::V4(192, 168, 37, 201);
+        assert_eq!(addr.apply_mask(0), Some(IpAddr::V4(0, 0, 0, 0)));
+        assert_eq!(addr.apply_mask(32), Some(addr));
+        assert_eq!(addr.apply_mask(20), Some(IpAddr::V4(192, 168, 32, 0)));
+        assert_eq!(addr.apply_mask(33), None);
+        let v6: IpAddr = "2001:db8:1234:5678::1".parse().unwrap();
+        assert_eq!(v6.apply_mask(40), "2001:db8:1200::".parse().ok());
+        assert_eq!(v6.apply_mask(128), Some(v6));
+        assert_eq!(v6.apply_mask(129), None);
+    }
+
+    #[test]
+    fn common_prefixes() {
+        let v4 = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert_eq!(v4("10.0.0.0").common_prefix_len(&v4("10.0.0.1")), Some(31));
+        assert_eq!(v4("10.0.0.1").common_prefix_len(&v4("10.0.0.2")), Some(30));
+        assert_eq!(v4("10.0.0.1").common_prefix_len(&v4("10.0.0.1")), Some(32));
+        assert_eq!(v4("0.0.0.0").common_prefix_len(&v4("128.0.0.0")), Some(0));
+        assert_eq!(v4("::").common_prefix_len(&v4("::")), Some(128));
+        assert_eq!(v4("::").common_prefix_len(&v4("::1")), Some(127));
+        assert_eq!(v4("::").common_prefix_len(&v4("0.0.0.0")), None);
+    }
+
+    fn parse_all(addrs: &[&str]) -> Vec<IpAddr> {
+        addrs.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn v4_sorts_before_v6_then_numerically() {
+        let mut addrs = parse_all(&[
+            "::1",
+            "10.0.0.2",
+            "9.255.255.255",
+            "::",
+            "10.0.0.10",
+            "2001:db8::",
+        ]);
+        addrs.sort();
+        assert_eq!(
+            addrs,
+            parse_all(&[
+                "9.255.255.255",
+                "10.0.0.2",
+                "10.0.0.10",
+                "::",
+                "::1",
+                "2001:db8::"
+            ])
+        );
+        assert!(IpAddr::V4(255, 255, 255, 255) < IpAddr::V6_UNSPECIFIED);
+    }
+
+    #[test]
+    fn dedup_sees_through_spelling() {
+        let mut addrs = parse_all(&["0:0:0:0:0:0:0:1", "1.1.1.1", "::1", "::0001", "1.1.1.1"]);
+        sort_and_dedup(&mut addrs);
+        assert_eq!(addrs, parse_all(&["1.1.1.1", "::1"]));
+
+        let set = unique(parse_all(&["2001:DB8::1", "2001:db8:0::1", "10.0.0.1"]));
+        assert_eq!(
+            set.into_iter().collect::<Vec<_>>(),
+            parse_all(&["10.0.0.1", "2001:db8::1"])
+        );
+    }
+
+    #[test]
+    fn equal_spellings_hash_the_same() {
+        use std::collections::HashSet;
+        let set: HashSet<IpAddr> =
+            parse_all(&["::ffff:0:1", "0::ffff:0:0:1", "0:0:0:0:0:ffff:0:1"])
+                .into_iter()
+                .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn segments_and_octets() {
+        let addr = IpAddr::v6_from_str("2001:db8::ff01").unwrap();
+        assert_eq!(
+            addr.segments(),
+            Some([0x2001, 0xdb8, 0, 0, 0, 0, 0, 0xff01])
+        );
+        let octets = addr.octets_v6().unwrap();
+        assert_eq!(&octets[..4], &[0x20, 0x01, 0x0d, 0xb8]);
+        assert_eq!(&octets[14..], &[0xff, 0x01]);
+        assert_eq!(IpAddr::V4(1, 2, 3, 4).segments(), None);
+        assert_eq!(IpAddr::V4(1, 2, 3, 4).octets_v6(), None);
+    }
+}
@@ -0,0 +1,521 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{AddrParseError, IpAddr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkError {
+    PrefixTooLong { prefix_len: u8, max: u8 },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkError::PrefixTooLong { prefix_len, max } => {
+                write!(f, "prefix length {} exceeds {}", prefix_len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubnetError {
+    /// The new prefix is shorter than the network's own.
+    PrefixTooShort {
+        new_prefix_len: u8,
+        prefix_len: u8,
+    },
+    PrefixTooLong {
+        new_prefix_len: u8,
+        max: u8,
+    },
+    /// There would be more subnets than a `usize` can count, so the
+    /// iterator couldn't report its length.
+    TooManySubnets {
+        new_prefix_len: u8,
+        prefix_len: u8,
+    },
+}
+
+impl fmt::Display for SubnetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubnetError::PrefixTooShort {
+                new_prefix_len,
+                prefix_len,
+            } => write!(
+                f,
+                "cannot split a /{} into /{} networks",
+                prefix_len, new_prefix_len
+            ),
+            SubnetError::PrefixTooLong {
+                new_prefix_len,
+                max,
+            } => write!(f, "prefix length {} exceeds {}", new_prefix_len, max),
+            SubnetError::TooManySubnets {
+                new_prefix_len,
+                prefix_len,
+            } => write!(
+                f,
+                "splitting a /{} into /{} networks gives too many to count",
+                prefix_len, new_prefix_len
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SubnetError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkParseError {
+    MissingPrefix,
+    InvalidPrefix,
+    Addr(AddrParseError),
+    Network(NetworkError),
+}
+
+impl fmt::Display for NetworkParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NetworkParseError::MissingPrefix => write!(f, "missing `/prefix`"),
+            NetworkParseError::InvalidPrefix => write!(f, "invalid prefix length"),
+            NetworkParseError::Addr(e) => write!(f, "{}", e),
+            NetworkParseError::Network(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for NetworkParseError {}
+
+/// The mask with the top `prefix_len` of `bit_len` bits set.
+pub(crate) fn prefix_mask(prefix_len: u8, bit_len: u8) -> u128 {
+    let all = u128::MAX >> (128 - bit_len as u32);
+    let host = all.checked_shr(prefix_len as u32).unwrap_or(0);
+    all & !host
+}
+
+/// A CIDR block such as `192.168.0.0/24`. The stored address is always the
+/// network address, with every host bit cleared.
+///
+/// Networks order by address, then by prefix length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    /// Any host bits set in `addr` are cleared, so `10.1.2.3/8` becomes
+    /// `10.0.0.0/8`.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Result<IpNetwork, NetworkError> {
+        let addr = addr
+            .apply_mask(prefix_len)
+            .ok_or(NetworkError::PrefixTooLong {
+                prefix_len,
+                max: addr.bit_len(),
+            })?;
+        Ok(IpNetwork { addr, prefix_len })
+    }
+
+    pub fn network(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    fn mask(&self) -> u128 {
+        prefix_mask(self.prefix_len, self.addr.bit_len())
+    }
+
+    /// The last address in the block.
+    fn last(&self) -> IpAddr {
+        let host = !self.mask() & (u128::MAX >> (128 - self.addr.bit_len() as u32));
+        self.addr.with_bits(self.addr.to_bits() | host)
+    }
+
+    /// False for an address of the other family.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.addr.same_family(addr) && addr.to_bits() & self.mask() == self.addr.to_bits()
+    }
+
+    /// The V4 broadcast address. `/31` and `/32` blocks have none (RFC 3021),
+    /// and neither does V6.
+    pub fn broadcast(&self) -> Option<IpAddr> {
+        match self.addr {
+            IpAddr::V4(..) if self.prefix_len <= 30 => Some(self.last()),
+            _ => None,
+        }
+    }
+
+    /// The number of addresses in the block. A V6 `/0` holds 2^128, which
+    /// does not fit, so it saturates at `u128::MAX`.
+    pub fn num_addresses(&self) -> u128 {
+        let host_bits = (self.addr.bit_len() - self.prefix_len) as u32;
+        1u128.checked_shl(host_bits).unwrap_or(u128::MAX)
+    }
+
+    /// The usable V4 host addresses: every address but the network and
+    /// broadcast ones, except that a `/31` has two hosts and a `/32` one
+    /// (RFC 3021). Empty for V6.
+    pub fn hosts(&self) -> Hosts {
+        let (first, last) = (self.addr.to_bits() as u64, self.last().to_bits() as u64);
+        match self.addr {
+            IpAddr::V6(_) => Hosts { next: 1, last: 0 },
+            IpAddr::V4(..) if self.prefix_len >= 31 => Hosts { next: first, last },
+            IpAddr::V4(..) => Hosts {
+                next: first + 1,
+                last: last - 1,
+            },
+        }
+    }
+
+    /// The networks of `new_prefix_len` that make up this one, in address
+    /// order. Splitting into the same prefix length yields just `self`.
+    /// Fails if there would be more than `usize::MAX` of them, such as a
+    /// V6 `/0` split into `/64`s on a 64-bit target.
+    pub fn subnets(&self, new_prefix_len: u8) -> Result<SubnetIter, SubnetError> {
+        let max = self.addr.bit_len();
+        if new_prefix_len > max {
+            return Err(SubnetError::PrefixTooLong {
+                new_prefix_len,
+                max,
+            });
+        }
+        if new_prefix_len < self.prefix_len {
+            return Err(SubnetError::PrefixTooShort {
+                new_prefix_len,
+                prefix_len: self.prefix_len,
+            });
+        }
+        if u32::from(new_prefix_len - self.prefix_len) >= usize::BITS {
+            return Err(SubnetError::TooManySubnets {
+                new_prefix_len,
+                prefix_len: self.prefix_len,
+            });
+        }
+        let host_bits = (max - new_prefix_len) as u32;
+        Ok(SubnetIter {
+            family: self.addr,
+            prefix_len: new_prefix_len,
+            next: Some(self.addr.to_bits()),
+            last: self
+                .last()
+                .to_bits()
+                .checked_shr(host_bits)
+                .map_or(0, |bits| bits << host_bits),
+            step: 1u128.checked_shl(host_bits).unwrap_or(0),
+        })
+    }
+
+    /// The network one bit shorter that contains this one, or `None` for a
+    /// `/0`.
+    pub fn supernet(&self) -> Option<IpNetwork> {
+        let prefix_len = self.prefix_len.checked_sub(1)?;
+        IpNetwork::new(self.addr, prefix_len).ok()
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// Parses `address/prefix_len`, e.g. `10.0.0.0/8`. Host bits in the address
+/// are cleared as in `IpNetwork::new`.
+impl FromStr for IpNetwork {
+    type Err = NetworkParseError;
+
+    fn from_str(s: &str) -> Result<IpNetwork, NetworkParseError> {
+        let slash = s.find('/').ok_or(NetworkParseError::MissingPrefix)?;
+        let addr: IpAddr = s[..slash].parse().map_err(NetworkParseError::Addr)?;
+        let prefix = &s[slash + 1..];
+        if prefix.is_empty() || !prefix.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(NetworkParseError::InvalidPrefix);
+        }
+        let prefix_len = prefix
+            .parse()
+            .map_err(|_| NetworkParseError::InvalidPrefix)?;
+        IpNetwork::new(addr, prefix_len).map_err(NetworkParseError::Network)
+    }
+}
+
+/// Iterator returned by `IpNetwork::hosts`.
+#[derive(Debug, Clone)]
+pub struct Hosts {
+    next: u64,
+    last: u64,
+}
+
+impl Iterator for Hosts {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<IpAddr> {
+        if self.next > self.last {
+            return None;
+        }
+        let [a, b, c, d] = (self.next as u32).to_be_bytes();
+        self.next += 1;
+        Some(IpAddr::V4(a, b, c, d))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.last + 1).saturating_sub(self.next) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Hosts {}
+
+/// Iterator returned by `IpNetwork::subnets`, which only builds one when
+/// the number of subnets fits in a `usize`.
+#[derive(Debug, Clone)]
+pub struct SubnetIter {
+    family: IpAddr,
+    prefix_len: u8,
+    next: Option<u128>,
+    last: u128,
+    step: u128,
+}
+
+impl Iterator for SubnetIter {
+    type Item = IpNetwork;
+
+    fn next(&mut self) -> Option<IpNetwork> {
+        let bits = self.next?;
+        self.next = if bits == self.last {
+            None
+        } else {
+            Some(bits + self.step)
+        };
+        Some(IpNetwork {
+            addr: self.family.with_bits(bits),
+            prefix_len: self.prefix_len,
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = match self.next {
+            None => Some(0),
+            // A zero step means a single /0 network.
+            Some(_) if self.step == 0 => Some(1),
+            Some(next) => ((self.last - next) / self.step).checked_add(1),
+        };
+        // `subnets` checked the count fits, and it only goes down
+        let len = remaining.and_then(|n| usize::try_from(n).ok()).unwrap();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for SubnetIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn net(s: &str, prefix_len: u8) -> IpNetwork {
+        IpNetwork::new(addr(s), prefix_len).unwrap()
+    }
+
+    #[test]
+    fn rejects_long_prefixes() {
+        assert_eq!(
+            IpNetwork::new(addr("1.2.3.4"), 33),
+            Err(NetworkError::PrefixTooLong {
+                prefix_len: 33,
+                max: 32
+            })
+        );
+        assert!(IpNetwork::new(addr("::"), 128).is_ok());
+        assert!(IpNetwork::new(addr("::"), 129).is_err());
+    }
+
+    #[test]
+    fn normalizes_to_network_address() {
+        assert_eq!(net("10.1.2.3", 8).network(), addr("10.0.0.0"));
+        assert_eq!(net("10.1.2.3", 8).to_string(), "10.0.0.0/8");
+        assert_eq!(net("2001:db8::1", 32).network(), addr("2001:db8::"));
+    }
+
+    #[test]
+    fn slash_24() {
+        let n = net("192.168.1.77", 24);
+        assert!(n.contains(&addr("192.168.1.0")));
+        assert!(n.contains(&addr("192.168.1.255")));
+        assert!(!n.contains(&addr("192.168.2.0")));
+        assert!(!n.contains(&addr("192.168.0.255")));
+        assert_eq!(n.broadcast(), Some(addr("192.168.1.255")));
+        assert_eq!(n.num_addresses(), 256);
+        let hosts: Vec<_> = n.hosts().collect();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(n.hosts().len(), 254);
+        assert_eq!(hosts[0], addr("192.168.1.1"));
+        assert_eq!(hosts[253], addr("192.168.1.254"));
+    }
+
+    #[test]
+    fn slash_0() {
+        let n = net("8.8.8.8", 0);
+        assert_eq!(n.network(), addr("0.0.0.0"));
+        assert!(n.contains(&addr("255.255.255.255")));
+        assert_eq!(n.broadcast(), Some(addr("255.255.255.255")));
+        assert_eq!(n.num_addresses(), 1 << 32);
+        assert_eq!(n.hosts().len(), (1 << 32) - 2);
+
+        let all_v6 = net("::", 0);
+        assert!(all_v6.contains(&addr("ffff::1")));
+        assert_eq!(all_v6.num_addresses(), u128::MAX);
+    }
+
+    #[test]
+    fn slash_31_and_32() {
+        let n = net("10.0.0.1", 31);
+        assert_eq!(n.broadcast(), None);
+        assert_eq!(n.num_addresses(), 2);
+        assert_eq!(
+            n.hosts().collect::<Vec<_>>(),
+            vec![addr("10.0.0.0"), addr("10.0.0.1")]
+        );
+
+        let n = net("255.255.255.255", 32);
+        assert_eq!(n.broadcast(), None);
+        assert_eq!(n.num_addresses(), 1);
+        assert_eq!(n.hosts().collect::<Vec<_>>(), vec![addr("255.255.255.255")]);
+        assert!(n.contains(&addr("255.255.255.255")));
+        assert!(!n.contains(&addr("255.255.255.254")));
+    }
+
+    #[test]
+    fn v6_prefixes() {
+        let n = net("2001:db8:abcd::1", 48);
+        assert!(n.contains(&addr("2001:db8:abcd:ffff::")));
+        assert!(!n.contains(&addr("2001:db8:abce::")));
+        assert_eq!(n.broadcast(), None);
+        assert_eq!(n.num_addresses(), 1 << 80);
+        assert_eq!(n.hosts().count(), 0);
+
+        let n = net("fe80::1", 10);
+        assert_eq!(n.network(), addr("fe80::"));
+        assert!(n.contains(&addr("febf::")));
+        assert!(!n.contains(&addr("fec0::")));
+    }
+
+    #[test]
+    fn families_never_mix() {
+        assert!(!net("0.0.0.0", 0).contains(&addr("::")));
+        assert!(!net("::", 0).contains(&addr("0.0.0.0")));
+    }
+
+    #[test]
+    fn splits_a_slash_24_into_slash_26s() {
+        let subnets: Vec<_> = net("192.168.0.0", 24).subnets(26).unwrap().collect();
+        assert_eq!(
+            subnets,
+            vec![
+                net("192.168.0.0", 26),
+                net("192.168.0.64", 26),
+                net("192.168.0.128", 26),
+                net("192.168.0.192", 26),
+            ]
+        );
+        assert_eq!(net("192.168.0.0", 24).subnets(26).unwrap().len(), 4);
+        assert_eq!(
+            net("192.168.0.0", 24)
+                .subnets(24)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            vec![net("192.168.0.0", 24)]
+        );
+    }
+
+    #[test]
+    fn subnet_errors() {
+        assert_eq!(
+            net("10.0.0.1", 32).subnets(33).unwrap_err(),
+            SubnetError::PrefixTooLong {
+                new_prefix_len: 33,
+                max: 32
+            }
+        );
+        assert_eq!(
+            net("10.0.0.0", 16).subnets(8).unwrap_err(),
+            SubnetError::PrefixTooShort {
+                new_prefix_len: 8,
+                prefix_len: 16
+            }
+        );
+    }
+
+    #[test]
+    fn subnets_at_the_extremes() {
+        assert_eq!(net("0.0.0.0", 0).subnets(32).unwrap().len(), 1 << 32);
+        let top = net("255.255.0.0", 16).subnets(32).unwrap();
+        assert_eq!(top.len(), 1 << 16);
+        assert_eq!(top.last(), Some(net("255.255.255.255", 32)));
+        assert_eq!(net("::", 0).subnets(0).unwrap().len(), 1);
+        assert_eq!(net("::", 0).subnets(1).unwrap().len(), 2);
+
+        let bits = usize::BITS as u8;
+        let mut largest = net("::", 0).subnets(bits - 1).unwrap();
+        assert_eq!(largest.len(), 1 << (bits - 1));
+        assert_eq!(largest.next(), Some(net("::", bits - 1)));
+        assert_eq!(largest.len(), (1 << (bits - 1)) - 1);
+        let mut small = net("ffff::", 128 - (bits - 1)).subnets(128).unwrap();
+        assert_eq!(small.len(), 1 << (bits - 1));
+        assert_eq!(small.next(), Some(net("ffff::", 128)));
+        for (prefix_len, new_prefix_len) in [(0, 128), (0, bits), (64, 128)] {
+            assert_eq!(
+                net("::", prefix_len).subnets(new_prefix_len).unwrap_err(),
+                SubnetError::TooManySubnets {
+                    new_prefix_len,
+                    prefix_len
+                }
+            );
+        }
+
+        let v6: Vec<_> = net("2001:db8::", 32).subnets(34).unwrap().collect();
+        assert_eq!(v6[3], net("2001:db8:c000::", 34));
+    }
+
+    #[test]
+    fn parses_cidr_notation() {
+        assert_eq!("10.1.2.3/8".parse(), Ok(net("10.0.0.0", 8)));
+        assert_eq!("2001:db8::/32".parse(), Ok(net("2001:db8::", 32)));
+        let parse = |s: &str| s.parse::<IpNetwork>().unwrap_err();
+        assert_eq!(parse("10.0.0.0"), NetworkParseError::MissingPrefix);
+        assert_eq!(parse("10.0.0.0/"), NetworkParseError::InvalidPrefix);
+        assert_eq!(parse("10.0.0.0/+8"), NetworkParseError::InvalidPrefix);
+        assert_eq!(parse("10.0.0.0/999"), NetworkParseError::InvalidPrefix);
+        assert_eq!(
+            parse("10.0.0/8"),
+            NetworkParseError::Addr(AddrParseError::TooFewOctets)
+        );
+        assert_eq!(
+            parse("10.0.0.0/33"),
+            NetworkParseError::Network(NetworkError::PrefixTooLong {
+                prefix_len: 33,
+                max: 32
+            })
+        );
+    }
+
+    #[test]
+    fn supernets() {
+        assert_eq!(
+            net("192.168.1.0", 24).supernet(),
+            Some(net("192.168.0.0", 23))
+        );
+        assert_eq!(net("10.0.0.1", 32).supernet(), Some(net("10.0.0.0", 31)));
+        assert_eq!(net("0.0.0.0", 0).supernet(), None);
+        assert_eq!(net("::", 0).supernet(), None);
+    }
+}
@@ -0,0 +1,294 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddrParseError {
+    Empty,
+    TooManyOctets,
+    TooFewOctets,
+    OctetOutOfRange,
+    TooManyGroups,
+    TooFewGroups,
+    /// A group of more than four hex digits.
+    GroupTooLong,
+    /// Two separators with nothing between them, other than a single `::`.
+    EmptyGroup,
+    /// More than one `::` in a V6 address.
+    MultipleCompressions,
+    /// An unexpected character at byte `pos`.
+    InvalidCharacter {
+        pos: usize,
+    },
+    /// A `%zone` suffix on an address that is not V6 link-local.
+    ZoneNotAllowed,
+    EmptyZone,
+}
+
+impl fmt::Display for AddrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddrParseError::Empty => write!(f, "empty address"),
+            AddrParseError::TooManyOctets => write!(f, "more than four octets"),
+            AddrParseError::TooFewOctets => write!(f, "fewer than four octets"),
+            AddrParseError::OctetOutOfRange => write!(f, "octet larger than 255"),
+            AddrParseError::TooManyGroups => write!(f, "more than eight groups"),
+            AddrParseError::TooFewGroups => write!(f, "fewer than eight groups"),
+            AddrParseError::GroupTooLong => write!(f, "group longer than four hex digits"),
+            AddrParseError::EmptyGroup => write!(f, "empty group"),
+            AddrParseError::MultipleCompressions => write!(f, "more than one `::`"),
+            AddrParseError::InvalidCharacter { pos } => {
+                write!(f, "invalid character at byte {}", pos)
+            }
+            AddrParseError::ZoneNotAllowed => {
+                write!(f, "zones are only allowed on link-local V6 addresses")
+            }
+            AddrParseError::EmptyZone => write!(f, "empty zone"),
+        }
+    }
+}
+
+impl std::error::Error for AddrParseError {}
+
+fn parse_v4(s: &str) -> Result<IpAddr, AddrParseError> {
+    let mut octets = [0u8; 4];
+    let mut count = 0;
+    let mut start = 0;
+    for part in s.split('.') {
+        if part.is_empty() {
+            return Err(AddrParseError::EmptyGroup);
+        }
+        if count == 4 {
+            return Err(AddrParseError::TooManyOctets);
+        }
+        let mut value = 0u32;
+        for (i, c) in part.char_indices() {
+            let digit = c
+                .to_digit(10)
+                .ok_or(AddrParseError::InvalidCharacter { pos: start + i })?;
+            value = (value * 10 + digit).min(256);
+        }
+        if value > 255 {
+            return Err(AddrParseError::OctetOutOfRange);
+        }
+        octets[count] = value as u8;
+        count += 1;
+        start += part.len() + 1;
+    }
+    if count < 4 {
+        return Err(AddrParseError::TooFewOctets);
+    }
+    let [a, b, c, d] = octets;
+    Ok(IpAddr::V4(a, b, c, d))
+}
+
+/// Parses the groups on one side of a `::` into `out`, returning how many
+/// there are. `offset` is where `s` starts in the whole address.
+fn parse_v6_groups(s: &str, offset: usize, out: &mut [u16; 8]) -> Result<usize, AddrParseError> {
+    if s.is_empty() {
+        return Ok(0);
+    }
+    let mut count = 0;
+    let mut start = offset;
+    for group in s.split(':') {
+        if group.is_empty() {
+            return Err(AddrParseError::EmptyGroup);
+        }
+        if let Some((i, _)) = group.char_indices().find(|(_, c)| !c.is_ascii_hexdigit()) {
+            return Err(AddrParseError::InvalidCharacter { pos: start + i });
+        }
+        if group.len() > 4 {
+            return Err(AddrParseError::GroupTooLong);
+        }
+        if count == 8 {
+            return Err(AddrParseError::TooManyGroups);
+        }
+        out[count] = u16::from_str_radix(group, 16).unwrap();
+        count += 1;
+        start += group.len() + 1;
+    }
+    Ok(count)
+}
+
+impl IpAddr {
+    /// Parses a V6 address in full (`2001:db8:0:0:0:0:0:1`) or compressed
+    /// (`2001:db8::1`) form. Hex digits may be in either case, and the last
+    /// two groups may be written as a dotted quad (`::ffff:1.2.3.4`).
+    pub fn v6_from_str(s: &str) -> Result<IpAddr, AddrParseError> {
+        if s.is_empty() {
+            return Err(AddrParseError::Empty);
+        }
+        if let Some(colon) = s.rfind(':') {
+            let tail = &s[colon + 1..];
+            if tail.contains('.') {
+                let bits = parse_v4(tail)
+                    .map_err(|e| match e {
+                        AddrParseError::InvalidCharacter { pos } => {
+                            AddrParseError::InvalidCharacter {
+                                pos: colon + 1 + pos,
+                            }
+                        }
+                        e => e,
+                    })?
+                    .to_bits();
+                let hex = format!("{}{:x}:{:x}", &s[..=colon], bits >> 16, bits & 0xffff);
+                return IpAddr::v6_from_str(&hex);
+            }
+        }
+        let mut segments = [0u16; 8];
+        match s.find("::") {
+            Some(at) => {
+                let tail = &s[at + 2..];
+                if tail.contains("::") {
+                    return Err(AddrParseError::MultipleCompressions);
+                }
+                let mut head_groups = [0u16; 8];
+                let mut tail_groups = [0u16; 8];
+                let head_len = parse_v6_groups(&s[..at], 0, &mut head_groups)?;
+                let tail_len = parse_v6_groups(tail, at + 2, &mut tail_groups)?;
+                // `::` stands for at least one zero group.
+                if head_len + tail_len > 7 {
+                    return Err(AddrParseError::TooManyGroups);
+                }
+                segments[..head_len].copy_from_slice(&head_groups[..head_len]);
+                segments[8 - tail_len..].copy_from_slice(&tail_groups[..tail_len]);
+            }
+            None => {
+                if parse_v6_groups(s, 0, &mut segments)? < 8 {
+                    return Err(AddrParseError::TooFewGroups);
+                }
+            }
+        }
+        Ok(IpAddr::V6(segments))
+    }
+}
+
+/// Parses dotted-quad V4 addresses such as `127.0.0.1`. Anything containing
+/// a `:` is parsed as V6, with at most one `::` standing for a run of zero
+/// groups.
+impl FromStr for IpAddr {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<IpAddr, AddrParseError> {
+        if s.is_empty() {
+            Err(AddrParseError::Empty)
+        } else if s.contains(':') {
+            IpAddr::v6_from_str(s)
+        } else {
+            parse_v4(s)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<IpAddr, AddrParseError> {
+        s.parse()
+    }
+
+    #[test]
+    fn v4() {
+        assert_eq!(parse("127.0.0.1"), Ok(IpAddr::V4(127, 0, 0, 1)));
+        assert_eq!(parse("255.255.255.255"), Ok(IpAddr::V4(255, 255, 255, 255)));
+        assert_eq!(parse("256.1.1.1"), Err(AddrParseError::OctetOutOfRange));
+        assert_eq!(
+            parse("1.1.1.99999999999"),
+            Err(AddrParseError::OctetOutOfRange)
+        );
+        assert_eq!(parse("1.2.3"), Err(AddrParseError::TooFewOctets));
+        assert_eq!(parse("1.2.3.4.5"), Err(AddrParseError::TooManyOctets));
+        assert_eq!(parse("1..3.4"), Err(AddrParseError::EmptyGroup));
+        assert_eq!(parse("1.2.3.4."), Err(AddrParseError::EmptyGroup));
+        assert_eq!(
+            parse("1.2.x.4"),
+            Err(AddrParseError::InvalidCharacter { pos: 4 })
+        );
+    }
+
+    #[test]
+    fn v6() {
+        assert_eq!(parse("::1"), Ok(IpAddr::V6_LOOPBACK));
+        assert_eq!(parse("::"), Ok(IpAddr::V6_UNSPECIFIED));
+        assert_eq!(
+            parse("2001:DB8::1"),
+            Ok(IpAddr::V6([0x2001, 0xdb8, 0, 0, 0, 0, 0, 1]))
+        );
+        assert_eq!(
+            parse("1:2:3:4:5:6:7:8"),
+            Ok(IpAddr::V6([1, 2, 3, 4, 5, 6, 7, 8]))
+        );
+        assert_eq!(
+            parse("1:2:3:4:5:6:7::"),
+            Ok(IpAddr::V6([1, 2, 3, 4, 5, 6, 7, 0]))
+        );
+        assert_eq!(
+            parse("::2:3:4:5:6:7:8"),
+            Ok(IpAddr::V6([0, 2, 3, 4, 5, 6, 7, 8]))
+        );
+        assert_eq!(
+            parse("ffff:0::0:FFFF"),
+            Ok(IpAddr::V6([0xffff, 0, 0, 0, 0, 0, 0, 0xffff]))
+        );
+        assert_eq!(parse("1:2:3:4:5:6:7"), Err(AddrParseError::TooFewGroups));
+        assert_eq!(
+            parse("1:2:3:4:5:6:7:8:9"),
+            Err(AddrParseError::TooManyGroups)
+        );
+        assert_eq!(
+            parse("1:2:3:4::5:6:7:8"),
+            Err(AddrParseError::TooManyGroups)
+        );
+        assert_eq!(parse("1::2::3"), Err(AddrParseError::MultipleCompressions));
+        assert_eq!(parse(":::"), Err(AddrParseError::EmptyGroup));
+        assert_eq!(parse(":1:2:3:4:5:6:7"), Err(AddrParseError::EmptyGroup));
+        assert_eq!(parse("12345::"), Err(AddrParseError::GroupTooLong));
+        assert_eq!(
+            parse("::1:g"),
+            Err(AddrParseError::InvalidCharacter { pos: 4 })
+        );
+    }
+
+    #[test]
+    fn v6_with_embedded_v4() {
+        assert_eq!(
+            parse("::ffff:1.2.3.4"),
+            Ok(IpAddr::V6([0, 0, 0, 0, 0, 0xffff, 0x0102, 0x0304]))
+        );
+        assert_eq!(
+            parse("64:ff9b::192.0.2.33"),
+            Ok(IpAddr::V6([0x64, 0xff9b, 0, 0, 0, 0, 0xc000, 0x0221]))
+        );
+        assert_eq!(
+            parse("::ffff:1.2.3.256"),
+            Err(AddrParseError::OctetOutOfRange)
+        );
+        assert_eq!(
+            parse("::ffff:1.2.x.4"),
+            Err(AddrParseError::InvalidCharacter { pos: 11 })
+        );
+        assert_eq!(
+            parse("1:2:3:4:5:6:7:1.2.3.4"),
+            Err(AddrParseError::TooManyGroups)
+        );
+        assert_eq!(
+            parse("1.2.3.4::"),
+            Err(AddrParseError::InvalidCharacter { pos: 1 })
+        );
+    }
+
+    #[test]
+    fn garbage() {
+        assert_eq!(parse(""), Err(AddrParseError::Empty));
+        assert_eq!(
+            parse("hello"),
+            Err(AddrParseError::InvalidCharacter { pos: 0 })
+        );
+        assert_eq!(
+            parse("hello:world"),
+            Err(AddrParseError::InvalidCharacter { pos: 0 })
+        );
+    }
+}
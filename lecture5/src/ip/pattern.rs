@@ -0,0 +1,225 @@
+//! Glob-style patterns over V4 addresses, such as `192.168.*.*`,
+//! `10.0.0.1-50` (a range in the last octet) or `10.0.0.1-10.0.1.50` (a
+//! range of whole addresses).
+
+use std::fmt;
+use std::str::FromStr;
+
+use super::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatternError {
+    V6NotSupported,
+    WrongOctetCount {
+        got: usize,
+    },
+    /// The octet at `position` (0 to 3) is not a number, `*` or `lo-hi`.
+    /// For a range of whole addresses it refers to whichever end failed.
+    InvalidOctet {
+        position: usize,
+    },
+    /// The octet range at `position` has `lo > hi`.
+    EmptyRange {
+        position: usize,
+    },
+    /// The whole-address range ends before it starts.
+    EndBeforeStart,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PatternError::V6NotSupported => write!(f, "V6 patterns are not supported"),
+            PatternError::WrongOctetCount { got } => write!(f, "expected 4 octets, got {}", got),
+            PatternError::InvalidOctet { position } => write!(f, "invalid octet {}", position),
+            PatternError::EmptyRange { position } => {
+                write!(f, "octet {} has an empty range", position)
+            }
+            PatternError::EndBeforeStart => write!(f, "range ends before it starts"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// What one octet of an `IpPattern` accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OctetPattern {
+    Any,
+    Exact(u8),
+    /// Both ends included.
+    Range(u8, u8),
+}
+
+impl OctetPattern {
+    pub fn matches(&self, octet: u8) -> bool {
+        match *self {
+            OctetPattern::Any => true,
+            OctetPattern::Exact(value) => octet == value,
+            OctetPattern::Range(lo, hi) => lo <= octet && octet <= hi,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpPattern {
+    Octets([OctetPattern; 4]),
+    /// Every address from the first to the second, both included.
+    Range(IpAddr, IpAddr),
+}
+
+impl IpPattern {
+    /// Always false for V6 addresses.
+    pub fn matches(&self, addr: &IpAddr) -> bool {
+        match (self, addr) {
+            (IpPattern::Octets(patterns), IpAddr::V4(a, b, c, d)) => patterns
+                .iter()
+                .zip([*a, *b, *c, *d].iter())
+                .all(|(pattern, octet)| pattern.matches(*octet)),
+            (IpPattern::Range(start, end), IpAddr::V4(..)) => start <= addr && addr <= end,
+            (_, IpAddr::V6(_)) => false,
+        }
+    }
+}
+
+fn parse_octet(s: &str, position: usize) -> Result<u8, PatternError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(PatternError::InvalidOctet { position });
+    }
+    s.parse()
+        .map_err(|_| PatternError::InvalidOctet { position })
+}
+
+fn parse_octet_pattern(s: &str, position: usize) -> Result<OctetPattern, PatternError> {
+    if s == "*" {
+        return Ok(OctetPattern::Any);
+    }
+    match s.find('-') {
+        Some(dash) => {
+            let lo = parse_octet(&s[..dash], position)?;
+            let hi = parse_octet(&s[dash + 1..], position)?;
+            if lo > hi {
+                return Err(PatternError::EmptyRange { position });
+            }
+            Ok(OctetPattern::Range(lo, hi))
+        }
+        None => parse_octet(s, position).map(OctetPattern::Exact),
+    }
+}
+
+fn parse_addr(s: &str) -> Result<IpAddr, PatternError> {
+    let parts: Vec<&str> = s.split('.').collect();
+    if parts.len() != 4 {
+        return Err(PatternError::WrongOctetCount { got: parts.len() });
+    }
+    let mut octets = [0u8; 4];
+    for (position, part) in parts.iter().enumerate() {
+        octets[position] = parse_octet(part, position)?;
+    }
+    Ok(octets.into())
+}
+
+impl FromStr for IpPattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<IpPattern, PatternError> {
+        if s.contains(':') {
+            return Err(PatternError::V6NotSupported);
+        }
+        // Three dots after a dash mean it separates two whole addresses.
+        if let Some(dash) = s
+            .find('-')
+            .filter(|&dash| s[dash..].matches('.').count() == 3)
+        {
+            let start = parse_addr(&s[..dash])?;
+            let end = parse_addr(&s[dash + 1..])?;
+            if start > end {
+                return Err(PatternError::EndBeforeStart);
+            }
+            return Ok(IpPattern::Range(start, end));
+        }
+        let parts: Vec<&str> = s.split('.').collect();
+        if parts.len() != 4 {
+            return Err(PatternError::WrongOctetCount { got: parts.len() });
+        }
+        let mut patterns = [OctetPattern::Any; 4];
+        for (position, part) in parts.iter().enumerate() {
+            patterns[position] = parse_octet_pattern(part, position)?;
+        }
+        Ok(IpPattern::Octets(patterns))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, addr: &str) -> bool {
+        let pattern: IpPattern = pattern.parse().unwrap();
+        pattern.matches(&addr.parse().unwrap())
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(matches("192.168.*.*", "192.168.0.0"));
+        assert!(matches("192.168.*.*", "192.168.255.255"));
+        assert!(!matches("192.168.*.*", "192.169.0.0"));
+        assert!(matches("*.0.0.1", "0.0.0.1"));
+        assert!(matches("*.0.0.1", "255.0.0.1"));
+        assert!(!matches("*.0.0.1", "255.0.0.2"));
+    }
+
+    #[test]
+    fn octet_range_boundaries() {
+        assert!(!matches("10.0.0.1-50", "10.0.0.0"));
+        assert!(matches("10.0.0.1-50", "10.0.0.1"));
+        assert!(matches("10.0.0.1-50", "10.0.0.50"));
+        assert!(!matches("10.0.0.1-50", "10.0.0.51"));
+        assert!(matches("10.0.0.0-255", "10.0.0.255"));
+        assert!(matches("10.0.0.7-7", "10.0.0.7"));
+    }
+
+    #[test]
+    fn wildcard_and_range_together() {
+        assert!(matches("10.*.5-9.1", "10.200.5.1"));
+        assert!(matches("10.*.5-9.1", "10.0.9.1"));
+        assert!(!matches("10.*.5-9.1", "10.0.10.1"));
+        assert!(!matches("10.*.5-9.1", "10.0.7.2"));
+    }
+
+    #[test]
+    fn whole_address_ranges() {
+        assert!(matches("10.0.0.1-10.0.0.50", "10.0.0.1"));
+        assert!(matches("10.0.0.1-10.0.0.50", "10.0.0.50"));
+        assert!(!matches("10.0.0.1-10.0.0.50", "10.0.0.51"));
+        assert!(matches("10.0.0.250-10.0.1.5", "10.0.1.0"));
+        assert!(!matches("10.0.0.250-10.0.1.5", "10.0.0.249"));
+    }
+
+    #[test]
+    fn never_matches_v6() {
+        assert!(!matches("*.*.*.*", "::"));
+        assert!(!matches("0.0.0.0-255.255.255.255", "::1"));
+    }
+
+    #[test]
+    fn errors_name_the_octet() {
+        let err = |s: &str| s.parse::<IpPattern>().unwrap_err();
+        assert_eq!(err("fe80::*"), PatternError::V6NotSupported);
+        assert_eq!(err("10.0.*"), PatternError::WrongOctetCount { got: 3 });
+        assert_eq!(err("10.0.x.1"), PatternError::InvalidOctet { position: 2 });
+        assert_eq!(err("256.0.0.1"), PatternError::InvalidOctet { position: 0 });
+        assert_eq!(
+            err("10.0.0.1-256"),
+            PatternError::InvalidOctet { position: 3 }
+        );
+        assert_eq!(err("10.0.0.1-"), PatternError::InvalidOctet { position: 3 });
+        assert_eq!(err("10.0.**.1"), PatternError::InvalidOctet { position: 2 });
+        assert_eq!(err("10.50-1.0.1"), PatternError::EmptyRange { position: 1 });
+        assert_eq!(err("10.0.0.9-10.0.0.1"), PatternError::EndBeforeStart);
+        assert_eq!(
+            err("10.0.0.1-10.0.300.1"),
+            PatternError::InvalidOctet { position: 2 }
+        );
+    }
+}
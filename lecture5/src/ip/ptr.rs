@@ -0,0 +1,204 @@
+//! Reverse-DNS names: `4.3.2.1.in-addr.arpa` for `1.2.3.4`, and the 32
+//! nibbles of a V6 address in reverse order under `ip6.arpa`.
+
+use std::fmt;
+
+use super::IpAddr;
+
+const V4_SUFFIX: &str = ".in-addr.arpa";
+const V6_SUFFIX: &str = ".ip6.arpa";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtrParseError {
+    UnknownSuffix,
+    WrongLabelCount {
+        expected: usize,
+        got: usize,
+    },
+    /// The label at `index`, counting from the left, is not a valid octet
+    /// or nibble.
+    InvalidLabel {
+        index: usize,
+    },
+}
+
+impl fmt::Display for PtrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PtrParseError::UnknownSuffix => {
+                write!(f, "name does not end in in-addr.arpa or ip6.arpa")
+            }
+            PtrParseError::WrongLabelCount { expected, got } => {
+                write!(f, "expected {} labels, got {}", expected, got)
+            }
+            PtrParseError::InvalidLabel { index } => write!(f, "invalid label at index {}", index),
+        }
+    }
+}
+
+impl std::error::Error for PtrParseError {}
+
+/// Strips `suffix` ignoring ASCII case, as DNS names are case-insensitive.
+fn strip_suffix_ignore_case<'a>(name: &'a str, suffix: &str) -> Option<&'a str> {
+    let split = name.len().checked_sub(suffix.len())?;
+    if name.is_char_boundary(split) && name[split..].eq_ignore_ascii_case(suffix) {
+        Some(&name[..split])
+    } else {
+        None
+    }
+}
+
+impl IpAddr {
+    pub fn to_ptr_name(&self) -> String {
+        match *self {
+            IpAddr::V4(a, b, c, d) => format!("{}.{}.{}.{}{}", d, c, b, a, V4_SUFFIX),
+            IpAddr::V6(_) => {
+                let bits = self.to_bits();
+                let mut name = String::with_capacity(64 + V6_SUFFIX.len());
+                for i in 0..32 {
+                    if i > 0 {
+                        name.push('.');
+                    }
+                    let nibble = (bits >> (4 * i)) & 0xf;
+                    name.push(std::char::from_digit(nibble as u32, 16).unwrap());
+                }
+                name.push_str(V6_SUFFIX);
+                name
+            }
+        }
+    }
+
+    /// The inverse of `to_ptr_name`. A trailing dot is allowed and case is
+    /// ignored.
+    pub fn from_ptr_name(name: &str) -> Result<IpAddr, PtrParseError> {
+        let name = name.strip_suffix('.').unwrap_or(name);
+        if let Some(labels) = strip_suffix_ignore_case(name, V4_SUFFIX) {
+            let labels: Vec<&str> = labels.split('.').collect();
+            if labels.len() != 4 {
+                return Err(PtrParseError::WrongLabelCount {
+                    expected: 4,
+                    got: labels.len(),
+                });
+            }
+            let mut octets = [0u8; 4];
+            for (index, label) in labels.iter().enumerate() {
+                let valid = !label.is_empty() && label.bytes().all(|b| b.is_ascii_digit());
+                octets[3 - index] = label
+                    .parse()
+                    .ok()
+                    .filter(|_| valid)
+                    .ok_or(PtrParseError::InvalidLabel { index })?;
+            }
+            let [a, b, c, d] = octets;
+            Ok(IpAddr::V4(a, b, c, d))
+        } else if let Some(labels) = strip_suffix_ignore_case(name, V6_SUFFIX) {
+            let labels: Vec<&str> = labels.split('.').collect();
+            if labels.len() != 32 {
+                return Err(PtrParseError::WrongLabelCount {
+                    expected: 32,
+                    got: labels.len(),
+                });
+            }
+            let mut bits = 0u128;
+            for (index, label) in labels.iter().enumerate() {
+                let mut chars = label.chars();
+                let nibble = match (chars.next().and_then(|c| c.to_digit(16)), chars.next()) {
+                    (Some(nibble), None) => nibble,
+                    _ => return Err(PtrParseError::InvalidLabel { index }),
+                };
+                bits |= (nibble as u128) << (4 * index);
+            }
+            Ok(IpAddr::V6_UNSPECIFIED.with_bits(bits))
+        } else {
+            Err(PtrParseError::UnknownSuffix)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(s: &str) -> String {
+        let addr: IpAddr = s.parse().unwrap();
+        let name = addr.to_ptr_name();
+        assert_eq!(IpAddr::from_ptr_name(&name), Ok(addr));
+        name
+    }
+
+    #[test]
+    fn v4() {
+        assert_eq!(round_trip("192.0.2.5"), "5.2.0.192.in-addr.arpa");
+        assert_eq!(round_trip("0.0.0.0"), "0.0.0.0.in-addr.arpa");
+    }
+
+    #[test]
+    fn v6() {
+        assert_eq!(
+            round_trip("::1"),
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.ip6.arpa"
+        );
+        assert_eq!(
+            round_trip("2001:db8::567:89ab"),
+            "b.a.9.8.7.6.5.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa"
+        );
+    }
+
+    #[test]
+    fn tolerates_trailing_dot_and_case() {
+        assert_eq!(
+            IpAddr::from_ptr_name("4.3.2.1.in-addr.arpa."),
+            Ok(IpAddr::V4(1, 2, 3, 4))
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("4.3.2.1.IN-ADDR.ARPA"),
+            Ok(IpAddr::V4(1, 2, 3, 4))
+        );
+        let upper = IpAddr::V6_LOOPBACK.to_ptr_name().to_uppercase();
+        assert_eq!(IpAddr::from_ptr_name(&upper), Ok(IpAddr::V6_LOOPBACK));
+    }
+
+    #[test]
+    fn malformed_names() {
+        assert_eq!(
+            IpAddr::from_ptr_name("3.2.1.in-addr.arpa"),
+            Err(PtrParseError::WrongLabelCount {
+                expected: 4,
+                got: 3
+            })
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("1.0.ip6.arpa"),
+            Err(PtrParseError::WrongLabelCount {
+                expected: 32,
+                got: 2
+            })
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("4.256.2.1.in-addr.arpa"),
+            Err(PtrParseError::InvalidLabel { index: 1 })
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("4.+3.2.1.in-addr.arpa"),
+            Err(PtrParseError::InvalidLabel { index: 1 })
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("4..2.1.in-addr.arpa"),
+            Err(PtrParseError::InvalidLabel { index: 1 })
+        );
+        let mut name = IpAddr::V6_LOOPBACK.to_ptr_name();
+        name.replace_range(2..3, "10");
+        assert_eq!(
+            IpAddr::from_ptr_name(&name),
+            Err(PtrParseError::InvalidLabel { index: 1 })
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("4.3.2.1.in-addr.arpa.."),
+            Err(PtrParseError::UnknownSuffix)
+        );
+        assert_eq!(
+            IpAddr::from_ptr_name("example.com"),
+            Err(PtrParseError::UnknownSuffix)
+        );
+    }
+}
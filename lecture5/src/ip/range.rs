@@ -0,0 +1,224 @@
+use std::fmt;
+
+use super::IpAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeError {
+    FamilyMismatch,
+    StartAfterEnd,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeError::FamilyMismatch => write!(f, "start and end are different families"),
+            RangeError::StartAfterEnd => write!(f, "start is after end"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// The addresses from `start` to `end`, both included, of a single family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IpRange {
+    start: IpAddr,
+    end: IpAddr,
+}
+
+impl IpRange {
+    pub fn new(start: IpAddr, end: IpAddr) -> Result<IpRange, RangeError> {
+        if !start.same_family(&end) {
+            return Err(RangeError::FamilyMismatch);
+        }
+        if start > end {
+            return Err(RangeError::StartAfterEnd);
+        }
+        Ok(IpRange { start, end })
+    }
+
+    pub fn start(&self) -> IpAddr {
+        self.start
+    }
+
+    pub fn end(&self) -> IpAddr {
+        self.end
+    }
+
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        self.start <= *addr && *addr <= self.end
+    }
+
+    /// True when the ranges share at least one address.
+    pub fn overlaps(&self, other: &IpRange) -> bool {
+        self.start.same_family(&other.start) && self.start <= other.end && other.start <= self.end
+    }
+
+    /// The number of addresses. The whole V6 space holds 2^128, which does
+    /// not fit, so it saturates at `u128::MAX`.
+    pub fn len(&self) -> u128 {
+        (self.end.to_bits() - self.start.to_bits()).saturating_add(1)
+    }
+
+    /// Always false; a range holds at least one address.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Coalesces overlapping and adjacent ranges of the same family. The result
+    /// is sorted, V4 ranges first.
+    pub fn merge(mut ranges: Vec<IpRange>) -> Vec<IpRange> {
+        ranges.sort();
+        let mut merged: Vec<IpRange> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            if let Some(last) = merged.last_mut() {
+                let touches = last.start.same_family(&range.start)
+                    && range.start.to_bits() <= last.end.to_bits().saturating_add(1);
+                if touches {
+                    last.end = last.end.max(range.end);
+                    continue;
+                }
+            }
+            merged.push(range);
+        }
+        merged
+    }
+
+    /// Every address in order. V6 ranges can be astronomically long, so
+    /// bound them with `take`:
+    ///
+    /// ```
+    /// # use lecture5::ip::{IpAddr, IpRange};
+    /// let all = IpRange::new("::".parse().unwrap(), "ffff::".parse().unwrap()).unwrap();
+    /// let first: Vec<IpAddr> = all.iter().take(3).collect();
+    /// assert_eq!(first[2], "::2".parse().unwrap());
+    /// ```
+    pub fn iter(&self) -> RangeIter {
+        RangeIter {
+            family: self.start,
+            next: Some(self.start.to_bits()),
+            end: self.end.to_bits(),
+        }
+    }
+}
+
+impl fmt::Display for IpRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl IntoIterator for IpRange {
+    type Item = IpAddr;
+    type IntoIter = RangeIter;
+
+    fn into_iter(self) -> RangeIter {
+        self.iter()
+    }
+}
+
+/// Iterator returned by `IpRange::iter`.
+#[derive(Debug, Clone)]
+pub struct RangeIter {
+    family: IpAddr,
+    next: Option<u128>,
+    end: u128,
+}
+
+impl Iterator for RangeIter {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<IpAddr> {
+        let bits = self.next?;
+        self.next = if bits == self.end {
+            None
+        } else {
+            Some(bits + 1)
+        };
+        Some(self.family.with_bits(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: &str, end: &str) -> IpRange {
+        IpRange::new(start.parse().unwrap(), end.parse().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn construction_errors() {
+        let v4 = IpAddr::V4(10, 0, 0, 1);
+        assert_eq!(
+            IpRange::new(v4, IpAddr::V6_LOOPBACK),
+            Err(RangeError::FamilyMismatch)
+        );
+        assert_eq!(
+            IpRange::new(v4, IpAddr::V4(10, 0, 0, 0)),
+            Err(RangeError::StartAfterEnd)
+        );
+        assert!(IpRange::new(v4, v4).is_ok());
+    }
+
+    #[test]
+    fn ten_address_range() {
+        let r = range("10.0.0.250", "10.0.1.3");
+        assert_eq!(r.len(), 10);
+        let addrs: Vec<_> = r.iter().collect();
+        assert_eq!(addrs.len(), 10);
+        assert_eq!(addrs[5], IpAddr::V4(10, 0, 0, 255));
+        assert_eq!(addrs[6], IpAddr::V4(10, 0, 1, 0));
+        assert!(r.contains(&IpAddr::V4(10, 0, 1, 3)));
+        assert!(!r.contains(&IpAddr::V4(10, 0, 1, 4)));
+    }
+
+    #[test]
+    fn iterates_to_the_end_of_the_space() {
+        let r = range("255.255.255.254", "255.255.255.255");
+        assert_eq!(r.iter().count(), 2);
+        assert_eq!(
+            range("::", "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").len(),
+            u128::MAX
+        );
+    }
+
+    #[test]
+    fn overlaps_at_exact_boundaries() {
+        let a = range("10.0.0.0", "10.0.0.255");
+        assert!(a.overlaps(&range("10.0.0.255", "10.0.1.0")));
+        assert!(!a.overlaps(&range("10.0.1.0", "10.0.1.255")));
+        assert!(range("10.0.0.5", "10.0.0.5").overlaps(&a));
+        assert!(!range("::", "::ffff").overlaps(&range("0.0.0.0", "0.0.255.255")));
+    }
+
+    #[test]
+    fn merges_adjacent_and_overlapping() {
+        let merged = IpRange::merge(vec![
+            range("10.0.2.0", "10.0.2.255"),
+            range("10.0.0.0", "10.0.0.255"),
+            range("10.0.1.0", "10.0.1.255"),
+            range("10.0.4.0", "10.0.4.255"),
+            range("10.0.4.10", "10.0.4.20"),
+            range("::", "::ff"),
+            range("::100", "::1ff"),
+        ]);
+        assert_eq!(
+            merged,
+            vec![
+                range("10.0.0.0", "10.0.2.255"),
+                range("10.0.4.0", "10.0.4.255"),
+                range("::", "::1ff"),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_merge_across_families() {
+        let merged = IpRange::merge(vec![
+            range("255.255.255.0", "255.255.255.255"),
+            range("::", "::1"),
+        ]);
+        assert_eq!(merged.len(), 2);
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::btree_map::{self, BTreeMap};
+
+use super::{IpAddr, IpNetwork};
+
+/// Routes keyed by network, looked up by longest prefix match.
+#[derive(Debug, Clone)]
+pub struct RouteTable<T> {
+    routes: BTreeMap<IpNetwork, T>,
+}
+
+impl<T> Default for RouteTable<T> {
+    fn default() -> Self {
+        RouteTable::new()
+    }
+}
+
+impl<T> RouteTable<T> {
+    pub fn new() -> RouteTable<T> {
+        RouteTable {
+            routes: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a route, returning the value it replaced for the same network.
+    pub fn insert(&mut self, network: IpNetwork, value: T) -> Option<T> {
+        self.routes.insert(network, value)
+    }
+
+    pub fn remove(&mut self, network: &IpNetwork) -> Option<T> {
+        self.routes.remove(network)
+    }
+
+    /// The most specific route containing `addr`. Routes of the other family
+    /// never match.
+    pub fn lookup(&self, addr: &IpAddr) -> Option<(&IpNetwork, &T)> {
+        (0..=addr.bit_len()).rev().find_map(|prefix_len| {
+            let network = IpNetwork::new(*addr, prefix_len).ok()?;
+            self.routes.get_key_value(&network)
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Routes in `IpNetwork` order: by network address, V4 first, with
+    /// shorter prefixes before longer ones at the same address.
+    pub fn iter(&self) -> btree_map::Iter<'_, IpNetwork, T> {
+        self.routes.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RouteTable<T> {
+    type Item = (&'a IpNetwork, &'a T);
+    type IntoIter = btree_map::Iter<'a, IpNetwork, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(s: &str, prefix_len: u8) -> IpNetwork {
+        IpNetwork::new(s.parse().unwrap(), prefix_len).unwrap()
+    }
+
+    fn route<'a>(table: &'a RouteTable<&'static str>, addr: &str) -> Option<&'a str> {
+        table.lookup(&addr.parse().unwrap()).map(|(_, v)| *v)
+    }
+
+    fn table() -> RouteTable<&'static str> {
+        let mut table = RouteTable::new();
+        table.insert(net("0.0.0.0", 0), "default");
+        table.insert(net("10.1.2.0", 24), "lan");
+        table.insert(net("10.1.2.3", 32), "host");
+        table.insert(net("2001:db8::", 32), "v6");
+        table
+    }
+
+    #[test]
+    fn longest_prefix_wins() {
+        let table = table();
+        assert_eq!(route(&table, "10.1.2.3"), Some("host"));
+        assert_eq!(route(&table, "10.1.2.4"), Some("lan"));
+        assert_eq!(route(&table, "10.1.3.4"), Some("default"));
+        assert_eq!(route(&table, "2001:db8:1::1"), Some("v6"));
+    }
+
+    #[test]
+    fn v6_never_matches_v4_routes() {
+        let table = table();
+        assert_eq!(route(&table, "::"), None);
+        assert_eq!(route(&table, "::ffff:a01:203"), None);
+    }
+
+    #[test]
+    fn lookup_returns_the_matching_network() {
+        let table = table();
+        let (network, _) = table.lookup(&"10.1.2.200".parse().unwrap()).unwrap();
+        assert_eq!(*network, net("10.1.2.0", 24));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut table = table();
+        assert_eq!(table.len(), 4);
+        assert_eq!(table.insert(net("10.1.2.0", 24), "lan2"), Some("lan"));
+        assert_eq!(table.remove(&net("10.1.2.3", 32)), Some("host"));
+        assert_eq!(table.remove(&net("10.1.2.3", 32)), None);
+        assert_eq!(route(&table, "10.1.2.3"), Some("lan2"));
+        assert_eq!(table.remove(&net("0.0.0.0", 0)), Some("default"));
+        assert_eq!(route(&table, "8.8.8.8"), None);
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn iterates_in_prefix_order() {
+        let table = table();
+        let networks: Vec<_> = table.iter().map(|(n, _)| n.to_string()).collect();
+        assert_eq!(
+            networks,
+            vec!["0.0.0.0/0", "10.1.2.0/24", "10.1.2.3/32", "2001:db8::/32"]
+        );
+    }
+}
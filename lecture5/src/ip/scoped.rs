@@ -0,0 +1,115 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{AddrParseError, IpAddr};
+
+/// A V6 address with an optional zone, such as `fe80::1%eth0`. Only
+/// link-local addresses may carry a zone. The zone takes part in equality,
+/// ordering and hashing, so `fe80::1%eth0` and `fe80::1%eth1` differ.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScopedV6 {
+    addr: IpAddr,
+    zone: Option<String>,
+}
+
+impl ScopedV6 {
+    /// Fails with `ZoneNotAllowed` for a V4 address, or for a zone on an
+    /// address that is not link-local, and with `EmptyZone` for `Some("")`.
+    pub fn new(addr: IpAddr, zone: Option<String>) -> Result<ScopedV6, AddrParseError> {
+        if addr.bit_len() != 128 {
+            return Err(AddrParseError::ZoneNotAllowed);
+        }
+        match &zone {
+            Some(z) if z.is_empty() => return Err(AddrParseError::EmptyZone),
+            Some(_) if !addr.is_link_local() => return Err(AddrParseError::ZoneNotAllowed),
+            _ => {}
+        }
+        Ok(ScopedV6 { addr, zone })
+    }
+
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    pub fn zone(&self) -> Option<&str> {
+        self.zone.as_deref()
+    }
+}
+
+impl FromStr for ScopedV6 {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<ScopedV6, AddrParseError> {
+        match s.find('%') {
+            Some(percent) => {
+                let addr = IpAddr::v6_from_str(&s[..percent])?;
+                ScopedV6::new(addr, Some(s[percent + 1..].to_string()))
+            }
+            None => ScopedV6::new(IpAddr::v6_from_str(s)?, None),
+        }
+    }
+}
+
+impl fmt::Display for ScopedV6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.zone {
+            Some(zone) => write!(f, "{}%{}", self.addr, zone),
+            None => write!(f, "{}", self.addr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<ScopedV6, AddrParseError> {
+        s.parse()
+    }
+
+    #[test]
+    fn round_trips() {
+        for s in ["fe80::1%eth0", "fe80::1", "2001:db8::1", "fe80::a:b%en0.1"].iter() {
+            assert_eq!(parse(s).unwrap().to_string(), *s);
+        }
+        let scoped = parse("FE80::1%eth0").unwrap();
+        assert_eq!(scoped.addr(), "fe80::1".parse().unwrap());
+        assert_eq!(scoped.zone(), Some("eth0"));
+        assert_eq!(parse("fe80::1").unwrap().zone(), None);
+    }
+
+    #[test]
+    fn zone_is_part_of_identity() {
+        use std::collections::HashSet;
+        let eth0 = parse("fe80::1%eth0").unwrap();
+        let eth1 = parse("fe80::1%eth1").unwrap();
+        let bare = parse("fe80::1").unwrap();
+        assert_ne!(eth0, eth1);
+        assert_ne!(eth0, bare);
+        assert_eq!(eth0, parse("fe80:0::1%eth0").unwrap());
+        let set: HashSet<_> = vec![eth0.clone(), eth1, bare, eth0].into_iter().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn rejects_bad_zones() {
+        assert_eq!(parse("fe80::1%"), Err(AddrParseError::EmptyZone));
+        assert_eq!(
+            parse("2001:db8::1%eth0"),
+            Err(AddrParseError::ZoneNotAllowed)
+        );
+        assert_eq!(parse("::1%lo"), Err(AddrParseError::ZoneNotAllowed));
+        assert_eq!(
+            ScopedV6::new(IpAddr::V4(169, 254, 0, 1), None),
+            Err(AddrParseError::ZoneNotAllowed)
+        );
+        assert_eq!(
+            parse("1.2.3.4"),
+            Err(AddrParseError::InvalidCharacter { pos: 1 })
+        );
+        assert_eq!(
+            "fe80::1%eth0".parse::<IpAddr>(),
+            Err(AddrParseError::InvalidCharacter { pos: 7 })
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use super::{IpAddr, IpNetwork};
+
+impl IpAddr {
+    /// A uniformly random V4 address, taking bits from `rng`.
+    pub fn sample_v4(rng: &mut impl FnMut() -> u64) -> IpAddr {
+        IpAddr::v4_from_u32(rng() as u32)
+    }
+
+    /// A uniformly random V6 address, taking bits from `rng`.
+    pub fn sample_v6(rng: &mut impl FnMut() -> u64) -> IpAddr {
+        let bits = (rng() as u128) << 64 | rng() as u128;
+        IpAddr::V6_UNSPECIFIED.with_bits(bits)
+    }
+}
+
+/// A deterministic address generator for property-style tests, backed by a
+/// xorshift64* PRNG. The same seed always produces the same addresses.
+#[derive(Debug, Clone)]
+pub struct AddrGen {
+    state: u64,
+    within: Option<IpNetwork>,
+}
+
+impl AddrGen {
+    pub fn new(seed: u64) -> AddrGen {
+        AddrGen {
+            // xorshift gets stuck at 0, so that seed is swapped for a fixed
+            // non-zero one.
+            state: if seed == 0 {
+                0x9e37_79b9_7f4a_7c15
+            } else {
+                seed
+            },
+            within: None,
+        }
+    }
+
+    /// Only generates addresses inside `network`. For V4 these are the
+    /// usable hosts, as listed by `IpNetwork::hosts`, so a `/31` gives
+    /// either of its two addresses and a `/32` its only one.
+    pub fn within(mut self, network: &IpNetwork) -> AddrGen {
+        self.within = Some(*network);
+        self
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// An address inside the network given to `within`, or otherwise one of
+    /// either family with equal odds.
+    pub fn next_addr(&mut self) -> IpAddr {
+        let network = match self.within {
+            Some(network) => network,
+            None => {
+                let mut rng = || self.next_u64();
+                return if rng() & 1 == 0 {
+                    IpAddr::sample_v4(&mut rng)
+                } else {
+                    IpAddr::sample_v6(&mut rng)
+                };
+            }
+        };
+        let base = network.network();
+        let random = IpAddr::sample_v6(&mut || self.next_u64()).to_bits();
+        let (first, count) = match base {
+            IpAddr::V4(..) if network.prefix_len() <= 30 => {
+                (base.to_bits() + 1, network.num_addresses() - 2)
+            }
+            _ => (base.to_bits(), network.num_addresses()),
+        };
+        // Only a V6 /0 saturates `num_addresses`, and any address is in it.
+        let offset = if count == u128::MAX {
+            random
+        } else {
+            random % count
+        };
+        base.with_bits(first + offset)
+    }
+
+    pub fn addrs(&mut self, n: usize) -> Vec<IpAddr> {
+        (0..n).map(|_| self.next_addr()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn net(s: &str) -> IpNetwork {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_addrs() {
+        let a = AddrGen::new(53).addrs(100);
+        let b = AddrGen::new(53).addrs(100);
+        let c = AddrGen::new(54).addrs(100);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.iter().any(|a| a.bit_len() == 32));
+        assert!(a.iter().any(|a| a.bit_len() == 128));
+        assert_eq!(AddrGen::new(0).addrs(10), AddrGen::new(0).addrs(10));
+    }
+
+    #[test]
+    fn sampling_takes_bits_from_the_closure() {
+        let mut calls = 0;
+        let mut rng = || {
+            calls += 1;
+            0x0102_0304_0506_0708
+        };
+        assert_eq!(IpAddr::sample_v4(&mut rng), IpAddr::V4(5, 6, 7, 8));
+        assert_eq!(
+            IpAddr::sample_v6(&mut rng).to_string(),
+            "102:304:506:708:102:304:506:708"
+        );
+        assert_eq!(calls, 3);
+    }
+
+    #[test]
+    fn tiny_networks() {
+        let only = AddrGen::new(1).within(&net("10.0.0.7/32")).addrs(20);
+        assert!(only.iter().all(|a| *a == IpAddr::V4(10, 0, 0, 7)));
+
+        let pair = AddrGen::new(1).within(&net("10.0.0.6/31")).addrs(50);
+        assert!(pair.contains(&IpAddr::V4(10, 0, 0, 6)));
+        assert!(pair.contains(&IpAddr::V4(10, 0, 0, 7)));
+
+        let hosts = AddrGen::new(1).within(&net("10.0.0.4/30")).addrs(50);
+        assert!(hosts.contains(&IpAddr::V4(10, 0, 0, 5)));
+        assert!(hosts.contains(&IpAddr::V4(10, 0, 0, 6)));
+        assert!(!hosts.contains(&IpAddr::V4(10, 0, 0, 4)));
+        assert!(!hosts.contains(&IpAddr::V4(10, 0, 0, 7)));
+
+        let v6 = AddrGen::new(1).within(&net("2001:db8::1/128")).addrs(5);
+        assert!(v6.iter().all(|a| a.to_string() == "2001:db8::1"));
+    }
+
+    #[test]
+    fn sampled_addresses_are_contained() {
+        let mut seeds = AddrGen::new(2024);
+        for prefix in [
+            "0.0.0.0/0",
+            "10.0.0.0/8",
+            "192.168.1.0/24",
+            "::/0",
+            "fe80::/10",
+            "2001:db8::/127",
+        ]
+        .iter()
+        {
+            let network = net(prefix);
+            let mut gen = AddrGen::new(seeds.next_u64()).within(&network);
+            for addr in gen.addrs(500) {
+                assert!(network.contains(&addr), "{} not in {}", addr, network);
+            }
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use super::{IpAddr, Ipv6Repr};
+
+impl IpAddr {
+    /// Calls `on_v4` with the octets or `on_v6` with the segments, whichever
+    /// the address holds, and returns its result.
+    pub fn fold<R>(
+        &self,
+        on_v4: impl FnOnce(u8, u8, u8, u8) -> R,
+        on_v6: impl FnOnce(&Ipv6Repr) -> R,
+    ) -> R {
+        match self {
+            IpAddr::V4(a, b, c, d) => on_v4(*a, *b, *c, *d),
+            IpAddr::V6(segments) => on_v6(segments),
+        }
+    }
+
+    pub fn map_v4<R>(&self, f: impl FnOnce(u8, u8, u8, u8) -> R) -> Option<R> {
+        self.fold(|a, b, c, d| Some(f(a, b, c, d)), |_| None)
+    }
+
+    pub fn map_v6<R>(&self, f: impl FnOnce(&Ipv6Repr) -> R) -> Option<R> {
+        self.fold(|_, _, _, _| None, |segments| Some(f(segments)))
+    }
+
+    pub fn as_v4(&self) -> Option<(u8, u8, u8, u8)> {
+        self.map_v4(|a, b, c, d| (a, b, c, d))
+    }
+
+    /// The octets of a V4 address.
+    ///
+    /// # Panics
+    ///
+    /// Panics with `msg` if the address is V6.
+    pub fn expect_v4(&self, msg: &str) -> (u8, u8, u8, u8) {
+        match self.as_v4() {
+            Some(octets) => octets,
+            None => panic!("{}: {} is not a V4 address", msg, self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fold_visits_the_held_variant() {
+        let describe = |addr: IpAddr| {
+            addr.fold(
+                |a, _, _, d| format!("v4 {}..{}", a, d),
+                |segments| format!("v6 ending {:x}", segments[7]),
+            )
+        };
+        assert_eq!(describe(IpAddr::V4(10, 0, 0, 9)), "v4 10..9");
+        assert_eq!(describe(IpAddr::V6_LOOPBACK), "v6 ending 1");
+    }
+
+    #[test]
+    fn maps_and_accessors() {
+        let v4 = IpAddr::V4(1, 2, 3, 4);
+        let v6 = IpAddr::V6_LOOPBACK;
+        assert_eq!(v4.map_v4(|a, b, c, d| a + b + c + d), Some(10));
+        assert_eq!(v6.map_v4(|a, _, _, _| a), None);
+        assert_eq!(v6.map_v6(|s| s.iter().sum::<u16>()), Some(1));
+        assert_eq!(v4.map_v6(|s| s[0]), None);
+        assert_eq!(v4.as_v4(), Some((1, 2, 3, 4)));
+        assert_eq!(v6.as_v4(), None);
+        assert_eq!(v4.expect_v4("home"), (1, 2, 3, 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "gateway must be V4: ::1 is not a V4 address")]
+    fn expect_v4_panics_with_the_message() {
+        IpAddr::V6_LOOPBACK.expect_v4("gateway must be V4");
+    }
+}
@@ -0,0 +1,170 @@
+//! An arena that hands out box-like handles into chunked storage, so many
+//! small allocations share a few large ones.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// Values are pushed into `Vec` chunks that are never allowed to grow past
+/// their capacity, so a chunk's buffer never reallocates and a value never
+/// moves once allocated. When a chunk is full a new one twice its size is
+/// started. Every value is dropped when the arena is.
+pub struct BoxArena<T> {
+    chunks: RefCell<Vec<Vec<T>>>,
+}
+
+/// A handle to a value in a `BoxArena`. Dropping the handle leaves the
+/// value in place until the arena itself is dropped.
+///
+/// It holds a raw pointer rather than a `&mut T`: a reference would be
+/// asserted unique for the whole `'arena`, which later allocations from
+/// the same chunk would contradict when they write to it.
+pub struct ArenaBox<'arena, T> {
+    value: NonNull<T>,
+    _marker: PhantomData<&'arena mut T>,
+}
+
+impl<T> BoxArena<T> {
+    pub fn new() -> BoxArena<T> {
+        BoxArena::with_capacity(16)
+    }
+
+    /// An arena whose first chunk holds `n` values, or one if `n` is zero.
+    pub fn with_capacity(n: usize) -> BoxArena<T> {
+        BoxArena {
+            chunks: RefCell::new(vec![Vec::with_capacity(n.max(1))]),
+        }
+    }
+
+    pub fn alloc(&self, value: T) -> ArenaBox<'_, T> {
+        let mut chunks = self.chunks.borrow_mut();
+        let last = chunks.last().unwrap();
+        if last.len() == last.capacity() {
+            let capacity = last.capacity() * 2;
+            chunks.push(Vec::with_capacity(capacity));
+        }
+        let chunk = chunks.last_mut().unwrap();
+        chunk.push(value);
+        // Safety: the chunk holds at least the value just pushed. Going
+        // through `as_mut_ptr` rather than `last_mut` avoids reborrowing
+        // the whole chunk, which would invalidate the other handles into it.
+        let ptr = unsafe { chunk.as_mut_ptr().add(chunk.len() - 1) };
+        ArenaBox {
+            value: NonNull::new(ptr).unwrap(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of values allocated so far.
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes reserved for values across all chunks, used or not.
+    pub fn allocated_bytes(&self) -> usize {
+        let capacity: usize = self.chunks.borrow().iter().map(Vec::capacity).sum();
+        capacity * mem::size_of::<T>()
+    }
+}
+
+impl<T> Default for BoxArena<T> {
+    fn default() -> Self {
+        BoxArena::new()
+    }
+}
+
+// Safety: a handle is as good as the `&mut T` it stands in for.
+unsafe impl<T: Send> Send for ArenaBox<'_, T> {}
+unsafe impl<T: Sync> Sync for ArenaBox<'_, T> {}
+
+impl<T> Deref for ArenaBox<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the chunk is never pushed to past its capacity, so the
+        // value stays put until the arena is dropped, which the `'arena`
+        // borrow prevents while the handle is alive.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T> DerefMut for ArenaBox<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: as in `deref`, and each value gets exactly one handle, so
+        // the `&mut` is unique.
+        unsafe { self.value.as_mut() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn handles_deref() {
+        let arena = BoxArena::new();
+        let mut a = arena.alloc(String::from("a"));
+        let b = arena.alloc(String::from("b"));
+        a.push('!');
+        assert_eq!(*a, "a!");
+        assert_eq!(b.len(), 1);
+        assert_eq!(arena.len(), 2);
+        assert!(!arena.is_empty());
+    }
+
+    #[test]
+    fn values_never_move() {
+        let arena = BoxArena::with_capacity(2);
+        let mut handles = Vec::new();
+        let mut addresses = Vec::new();
+        for i in 0..1000u64 {
+            let handle = arena.alloc(i);
+            addresses.push(&*handle as *const u64);
+            handles.push(handle);
+        }
+        for (i, (handle, address)) in handles.iter().zip(&addresses).enumerate() {
+            assert_eq!(&**handle as *const u64, *address);
+            // Safety: the arena, and so the value, is still alive.
+            assert_eq!(unsafe { **address }, i as u64);
+        }
+        assert_eq!(arena.len(), 1000);
+    }
+
+    #[test]
+    fn chunks_double() {
+        let arena = BoxArena::with_capacity(4);
+        assert_eq!(arena.allocated_bytes(), 4 * 8);
+        for i in 0..5u64 {
+            arena.alloc(i);
+        }
+        assert_eq!(arena.allocated_bytes(), (4 + 8) * 8);
+        assert!(BoxArena::<u8>::with_capacity(0).allocated_bytes() > 0);
+    }
+
+    #[test]
+    fn dropping_arena_drops_values() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let arena = BoxArena::with_capacity(3);
+        for _ in 0..10 {
+            arena.alloc(Counted(&drops));
+        }
+        assert_eq!(drops.get(), 0);
+        drop(arena);
+        assert_eq!(drops.get(), 10);
+    }
+}
@@ -0,0 +1,3 @@
+pub mod arena;
+pub mod my_box;
+pub mod small_box;
@@ -0,0 +1,667 @@
+//! `MyBox<T>`, a hand-written version of `Box<T>` used by the `mybox`
+//! example.
+
+use std::borrow;
+use std::fmt;
+use std::marker::PhantomPinned;
+use std::ops::{Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::{ptr, slice, str, thread};
+
+/// The value lives on the heap, in a `Box`, so `T` may be unsized as in
+/// `MyBox<[T]>` and `MyBox<str>`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+pub struct MyBox<T: ?Sized>(Box<T>);
+
+impl<T> MyBox<T> {
+    pub fn new(x: T) -> MyBox<T> {
+        MyBox(Box::new(x))
+    }
+
+    /// Replaces the value, returning the old one.
+    pub fn set(&mut self, value: T) -> T {
+        self.replace(value)
+    }
+
+    /// Replaces the value, returning the old one, like `mem::replace`.
+    pub fn replace(&mut self, value: T) -> T {
+        std::mem::replace(&mut self.0, value)
+    }
+
+    /// Exchanges the values of two boxes. Only the heap pointers move.
+    pub fn swap(&mut self, other: &mut MyBox<T>) {
+        std::mem::swap(&mut self.0, &mut other.0)
+    }
+
+    /// Moves the value out, consuming the box.
+    pub fn into_inner(self) -> T {
+        *self.0
+    }
+
+    /// Moves the value into `f` and boxes the result.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> MyBox<U> {
+        MyBox::new(f(*self.0))
+    }
+
+    /// Boxes `value` together with a hook that sees it just before it
+    /// is dropped.
+    pub fn with_drop_hook(value: T, hook: impl FnOnce(&T) + 'static) -> MyBoxHooked<T> {
+        MyBoxHooked {
+            value,
+            hook: Some(Box::new(hook)),
+        }
+    }
+
+    /// Boxes and pins `value`. It stays at the same heap address until it
+    /// is dropped, so it may point into itself, like `SelfRef`.
+    ///
+    /// Getting the value back out, or a `&mut` to it, is only allowed for
+    /// `Unpin` types:
+    ///
+    /// ```compile_fail
+    /// use lecture7::my_box::{MyBox, SelfRef};
+    /// use std::pin::Pin;
+    ///
+    /// let pinned = SelfRef::new("hello");
+    /// // error: `PhantomPinned` cannot be unpinned
+    /// let unpinned: MyBox<SelfRef> = Pin::into_inner(pinned);
+    /// ```
+    ///
+    /// ```
+    /// use lecture7::my_box::MyBox;
+    /// use std::pin::Pin;
+    ///
+    /// let mut pinned = MyBox::pin(5);
+    /// *pinned.as_mut() += 1; // i32 is Unpin
+    /// assert_eq!(Pin::into_inner(pinned).into_inner(), 6);
+    /// ```
+    pub fn pin(value: T) -> Pin<MyBox<T>> {
+        // Safety: the value is on the heap and the `Pin` owns the only
+        // `MyBox` pointing at it, so it can't be moved again unless
+        // `T: Unpin`.
+        unsafe { Pin::new_unchecked(MyBox::new(value)) }
+    }
+}
+
+// Inherent as_ref and as_mut always give &T, where the AsRef and AsMut
+// traits would leave the target type to inference.
+#[allow(clippy::should_implement_trait)]
+impl<T: ?Sized> MyBox<T> {
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    pub fn as_ref(&self) -> &T {
+        &self.0
+    }
+
+    pub fn as_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    /// Gives up ownership of the heap allocation, returning a pointer to
+    /// it. Pass the pointer to `from_raw` exactly once to free it again;
+    /// otherwise the value is leaked.
+    pub fn into_raw(this: MyBox<T>) -> *mut T {
+        Box::into_raw(this.0)
+    }
+
+    /// Takes back ownership of a pointer from `into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must come from `MyBox::into_raw` and must not be passed here
+    /// more than once, since both boxes would then free it. It must not be
+    /// used by anything else after this call.
+    pub unsafe fn from_raw(ptr: *mut T) -> MyBox<T> {
+        MyBox(Box::from_raw(ptr))
+    }
+
+    /// Moves the value into a reference-counted allocation. For sized `T`
+    /// this copies it out of the box; the box's own memory is freed.
+    pub fn into_rc(this: MyBox<T>) -> Rc<T> {
+        Rc::from(this.0)
+    }
+
+    /// Like `into_rc`, for sharing across threads.
+    pub fn into_arc(this: MyBox<T>) -> Arc<T> {
+        Arc::from(this.0)
+    }
+
+    /// Never frees the value, and returns a reference that lives for the
+    /// rest of the program.
+    pub fn leak<'a>(this: MyBox<T>) -> &'a mut T {
+        Box::leak(this.0)
+    }
+}
+
+impl<T> MyBox<[T]> {
+    pub fn from_vec(v: Vec<T>) -> MyBox<[T]> {
+        MyBox(v.into_boxed_slice())
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.0.into_vec()
+    }
+}
+
+impl MyBox<str> {
+    pub fn from_string(s: String) -> MyBox<str> {
+        MyBox(s.into_boxed_str())
+    }
+
+    pub fn into_string(self) -> String {
+        self.0.into_string()
+    }
+}
+
+impl<T: ?Sized + fmt::Display> fmt::Display for MyBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Lets `MyBox::new(5) == 5` compare against the bare value.
+impl<T: PartialEq> PartialEq<T> for MyBox<T> {
+    fn eq(&self, other: &T) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for MyBox<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl AsRef<str> for MyBox<String> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<U> AsRef<[U]> for MyBox<Vec<U>> {
+    fn as_ref(&self) -> &[U] {
+        &self.0
+    }
+}
+
+// Hash and Eq are derived from the inner value, so they agree with the
+// borrowed forms as Borrow requires.
+impl<T: ?Sized> borrow::Borrow<T> for MyBox<T> {
+    fn borrow(&self) -> &T {
+        &self.0
+    }
+}
+
+/// Lets a map keyed by `MyBox<String>` be searched with a `&str`.
+impl borrow::Borrow<str> for MyBox<String> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T> From<T> for MyBox<T> {
+    fn from(value: T) -> MyBox<T> {
+        MyBox::new(value)
+    }
+}
+
+/// Turns a uniquely owned `Rc` back into a `MyBox`, or returns it unchanged
+/// if it is shared.
+///
+/// Unlike `Rc::try_unwrap`, this also fails while any `Weak` exists, even
+/// if the `Rc` is the only strong pointer: taking the value out would
+/// silently break those weak pointers, which is rarely what the caller
+/// wants when asking for unique ownership.
+pub fn rc_into_mybox<T>(rc: Rc<T>) -> Result<MyBox<T>, Rc<T>> {
+    if Rc::strong_count(&rc) != 1 || Rc::weak_count(&rc) != 0 {
+        return Err(rc);
+    }
+    Rc::try_unwrap(rc).map(MyBox::new)
+}
+
+/// A string kept in an inline buffer, read back through a pointer into
+/// that same buffer. Moving it would leave the pointer aimed at the old
+/// location, so it is `!Unpin` and only ever handed out pinned.
+pub struct SelfRef {
+    buf: [u8; SelfRef::CAPACITY],
+    len: usize,
+    start: *const u8,
+    _pin: PhantomPinned,
+}
+
+impl SelfRef {
+    pub const CAPACITY: usize = 32;
+
+    /// Panics if `text` is longer than `CAPACITY` bytes.
+    pub fn new(text: &str) -> Pin<MyBox<SelfRef>> {
+        assert!(text.len() <= SelfRef::CAPACITY, "text too long");
+        let mut buf = [0; SelfRef::CAPACITY];
+        buf[..text.len()].copy_from_slice(text.as_bytes());
+        let mut pinned = MyBox::pin(SelfRef {
+            buf,
+            len: text.len(),
+            start: ptr::null(),
+            _pin: PhantomPinned,
+        });
+        // Safety: only a field is written; the value itself isn't moved.
+        let this = unsafe { pinned.as_mut().get_unchecked_mut() };
+        this.start = this.buf.as_ptr();
+        pinned
+    }
+
+    /// The text, read through the self-referential pointer.
+    pub fn text(self: Pin<&Self>) -> &str {
+        // Safety: being pinned, the buffer hasn't moved since `start` was
+        // set, and its first `len` bytes were copied from a `&str`.
+        unsafe {
+            let bytes = slice::from_raw_parts(self.start, self.len);
+            str::from_utf8_unchecked(bytes)
+        }
+    }
+
+    /// Whether the pointer still points into the buffer, as pinning
+    /// guarantees.
+    pub fn points_to_self(self: Pin<&Self>) -> bool {
+        self.start == self.buf.as_ptr()
+    }
+}
+
+type DropHook<T> = Box<dyn FnOnce(&T)>;
+
+/// A box that runs a hook when dropped, before the value's own `Drop`.
+///
+/// If the box is dropped while the thread is already unwinding, a panic
+/// from the hook is caught and discarded, since a second panic would
+/// abort the process. Outside of unwinding the hook's panic propagates
+/// as usual.
+pub struct MyBoxHooked<T> {
+    value: T,
+    hook: Option<DropHook<T>>,
+}
+
+impl<T> Deref for MyBoxHooked<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T> Drop for MyBoxHooked<T> {
+    fn drop(&mut self) {
+        if let Some(hook) = self.hook.take() {
+            let value = &self.value;
+            if thread::panicking() {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| hook(value)));
+            } else {
+                hook(value);
+            }
+        }
+        // `value` is dropped after this returns
+    }
+}
+
+impl<T: ?Sized> Deref for MyBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized> DerefMut for MyBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shout(s: &mut str) {
+        s.make_ascii_uppercase();
+    }
+
+    #[test]
+    fn explicit_deref_mut() {
+        let mut b = MyBox::new(vec![1, 2]);
+        b.deref_mut().push(3);
+        *b.get_mut() = vec![4];
+        assert_eq!(*b, vec![4]);
+        assert_eq!(b.set(vec![5, 6]), vec![4]);
+        assert_eq!(*b, vec![5, 6]);
+    }
+
+    #[test]
+    fn coerces_to_mut_str() {
+        // &mut MyBox<String> -> &mut String -> &mut str
+        let mut b = MyBox::new(String::from("hello"));
+        shout(&mut b);
+        b.push_str(" there");
+        assert_eq!(*b, "HELLO there");
+    }
+
+    #[test]
+    fn into_inner_moves_out() {
+        let b = MyBox::new(String::from("moved"));
+        let s: String = b.into_inner();
+        assert_eq!(s, "moved");
+    }
+
+    #[test]
+    fn map_changes_type() {
+        let b: MyBox<u32> = MyBox::new(42);
+        let s: MyBox<String> = b.map(|n| n.to_string());
+        assert_eq!(s.as_ref(), "42");
+    }
+
+    #[test]
+    fn map_does_not_clone() {
+        // Not Clone, so map can only have moved it
+        struct Token(u8);
+        let b = MyBox::new(Token(1)).map(|t| Token(t.0 + 1));
+        assert_eq!(b.into_inner().0, 2);
+    }
+
+    #[test]
+    fn debug_wraps_inner() {
+        assert_eq!(format!("{:?}", MyBox::new(5)), "MyBox(5)");
+        assert_eq!(format!("{:?}", MyBox::new("a")), "MyBox(\"a\")");
+    }
+
+    #[test]
+    fn display_is_transparent() {
+        assert_eq!(MyBox::new(5).to_string(), "5");
+        assert_eq!(format!("{:>4}", MyBox::new(7)), "   7");
+    }
+
+    #[test]
+    fn clone_is_deep() {
+        let a = MyBox::new(vec![1]);
+        let mut b = a.clone();
+        b.push(2);
+        assert_eq!(*a, vec![1]);
+        assert_eq!(*b, vec![1, 2]);
+    }
+
+    #[test]
+    fn eq_compares_inner_values() {
+        assert_eq!(MyBox::new(5), MyBox::new(5));
+        assert_ne!(MyBox::new(5), MyBox::new(6));
+        assert!(MyBox::new(5) == 5);
+        assert!(MyBox::new(String::from("a")) == String::from("a"));
+        assert!(MyBox::new(5) != 6);
+    }
+
+    #[test]
+    fn default_and_from() {
+        assert_eq!(MyBox::<u32>::default(), 0);
+        assert_eq!(MyBox::<String>::default(), String::new());
+        let b: MyBox<&str> = "x".into();
+        assert_eq!(b, MyBox::from("x"));
+    }
+
+    #[test]
+    fn hash_matches_inner() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::HashSet;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of(value: impl Hash) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+        assert_eq!(hash_of(MyBox::new("k")), hash_of("k"));
+        let set: HashSet<_> = vec![MyBox::new(1), MyBox::new(1), MyBox::new(2)]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    type Log = Rc<RefCell<Vec<&'static str>>>;
+
+    struct Noisy(Log);
+
+    impl Drop for Noisy {
+        fn drop(&mut self) {
+            self.0.borrow_mut().push("inner");
+        }
+    }
+
+    #[test]
+    fn hook_runs_once_before_inner_drop() {
+        let log: Log = Rc::default();
+        let hook_log = Rc::clone(&log);
+        let b = MyBox::with_drop_hook(Noisy(Rc::clone(&log)), move |_| {
+            hook_log.borrow_mut().push("hook")
+        });
+        assert!(log.borrow().is_empty());
+        drop(b);
+        assert_eq!(*log.borrow(), ["hook", "inner"]);
+    }
+
+    #[test]
+    fn hook_panic_while_unwinding_is_swallowed() {
+        let dropped = Rc::new(RefCell::new(false));
+        let flag = Rc::clone(&dropped);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _b = MyBox::with_drop_hook(5, move |_| {
+                *flag.borrow_mut() = true;
+                panic!("hook");
+            });
+            panic!("body");
+        }));
+        let payload = result.unwrap_err();
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"body"));
+        assert!(*dropped.borrow());
+    }
+
+    #[test]
+    #[should_panic(expected = "hook")]
+    fn hook_panic_propagates_otherwise() {
+        drop(MyBox::with_drop_hook(5, |_| panic!("hook")));
+    }
+
+    #[test]
+    fn unsized_slices() {
+        let mut b = MyBox::from_vec(vec![3, 1, 2]);
+        b.sort();
+        assert_eq!(b.len(), 3);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+        let slice: &[i32] = &b;
+        assert_eq!(slice, [1, 2, 3]);
+        assert_eq!(b.into_vec(), vec![1, 2, 3]);
+
+        let empty = MyBox::<[u8]>::from_vec(Vec::new());
+        assert!(empty.is_empty());
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.into_vec(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn unsized_strings() {
+        fn hello(name: &str) -> String {
+            format!("Hello, {}!", name)
+        }
+        let mut b = MyBox::from_string(String::from("rust"));
+        assert_eq!(hello(&b), "Hello, rust!");
+        b.make_ascii_uppercase();
+        assert_eq!(b.len(), 4);
+        assert_eq!(b.to_string(), "RUST");
+        assert_eq!(format!("{:?}", b), "MyBox(\"RUST\")");
+        assert_eq!(b.into_string(), "RUST");
+
+        let empty = MyBox::from_string(String::new());
+        assert!(empty.is_empty());
+        assert_eq!(hello(&empty), "Hello, !");
+        assert_eq!(empty.into_string(), "");
+    }
+
+    #[test]
+    fn raw_round_trip() {
+        let raw = MyBox::into_raw(MyBox::new(String::from("raw")));
+        // Safety: `raw` came from into_raw and is only used here.
+        unsafe {
+            (*raw).push('!');
+            let back = MyBox::from_raw(raw);
+            assert_eq!(*back, "raw!");
+        }
+
+        let raw = MyBox::into_raw(MyBox::from_vec(vec![1, 2]));
+        let back = unsafe { MyBox::from_raw(raw) };
+        assert_eq!(back.into_vec(), vec![1, 2]);
+    }
+
+    #[test]
+    fn leaked_reference_is_mutable() {
+        let leaked: &'static mut Vec<i32> = MyBox::leak(MyBox::new(vec![1]));
+        leaked.push(2);
+        assert_eq!(*leaked, vec![1, 2]);
+        // Reclaim it so the test doesn't leak under Miri.
+        let back = unsafe { MyBox::from_raw(leaked) };
+        assert_eq!(back.into_inner(), vec![1, 2]);
+    }
+
+    #[test]
+    fn from_raw_drops_value() {
+        let log: Log = Rc::default();
+        let raw = MyBox::into_raw(MyBox::new(Noisy(Rc::clone(&log))));
+        assert!(log.borrow().is_empty());
+        drop(unsafe { MyBox::from_raw(raw) });
+        assert_eq!(*log.borrow(), ["inner"]);
+    }
+
+    #[test]
+    fn as_ref_traits() {
+        fn total_len(items: &[impl AsRef<[u8]>]) -> usize {
+            items.iter().map(|i| i.as_ref().len()).sum()
+        }
+        fn generic<T: AsRef<U>, U: ?Sized>(t: &T) -> &U {
+            t.as_ref()
+        }
+        assert_eq!(total_len(&[MyBox::new(vec![1u8, 2])]), 2);
+        let s = MyBox::new(String::from("abc"));
+        let as_str: &str = generic(&s);
+        let as_string: &String = generic(&s);
+        assert_eq!(as_str, as_string);
+        assert_eq!(generic::<_, str>(&s).len(), 3);
+    }
+
+    #[test]
+    fn borrow_for_map_lookups() {
+        use std::collections::HashMap;
+        let mut ages: HashMap<MyBox<String>, u32> = HashMap::new();
+        ages.insert(MyBox::new(String::from("ferris")), 7);
+        ages.insert(MyBox::new(String::from("corro")), 3);
+        assert_eq!(ages.get("ferris"), Some(&7));
+        assert_eq!(ages.get(&String::from("corro")), Some(&3));
+        assert_eq!(ages.get("bob"), None);
+        assert!(ages.contains_key(&MyBox::new(String::from("corro"))));
+    }
+
+    #[test]
+    fn replace_and_swap() {
+        struct Token {
+            name: &'static str,
+        }
+        let mut a = MyBox::new(Token { name: "a" });
+        let mut b = MyBox::new(Token { name: "b" });
+        let a_addr = &*a as *const Token;
+        a.swap(&mut b);
+        assert_eq!((a.name, b.name), ("b", "a"));
+        // swapping boxes moves pointers, not values
+        assert_eq!(&*b as *const Token, a_addr);
+        let old = a.replace(Token { name: "c" });
+        assert_eq!((old.name, a.name), ("b", "c"));
+    }
+
+    #[test]
+    fn pinned_address_survives_moves() {
+        let pinned = MyBox::pin(String::from("stay"));
+        let addr = &*pinned as *const String;
+        let moved = pinned;
+        assert_eq!(&*moved as *const String, addr);
+        let mut list = vec![moved];
+        list.insert(0, MyBox::pin(String::from("first")));
+        list.reserve(100); // reallocates the Vec, moving the boxes
+        assert_eq!(&*list[1] as *const String, addr);
+        let popped = list.pop().unwrap();
+        assert_eq!(&*popped as *const String, addr);
+        assert_eq!(*popped, "stay");
+    }
+
+    #[test]
+    fn self_ref_stays_valid() {
+        let a = SelfRef::new("hello");
+        let b = SelfRef::new("");
+        let mut all = vec![a, b];
+        all.reserve(100);
+        let a = all.remove(0);
+        assert!(a.as_ref().points_to_self());
+        assert_eq!(a.as_ref().text(), "hello");
+        assert_eq!(all[0].as_ref().text(), "");
+        assert!(all[0].as_ref().points_to_self());
+    }
+
+    #[test]
+    fn pin_as_mut_for_unpin() {
+        let mut pinned = MyBox::pin(vec![1]);
+        pinned.as_mut().push(2);
+        pinned.push(3); // DerefMut on the Pin itself, since Vec is Unpin
+        assert_eq!(*pinned.as_ref(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn into_shared_pointers() {
+        let rc = MyBox::into_rc(MyBox::new(String::from("shared")));
+        let rc2 = Rc::clone(&rc);
+        assert_eq!(*rc2, "shared");
+        let arc = MyBox::into_arc(MyBox::new(vec![1, 2]));
+        let handle = {
+            let arc = Arc::clone(&arc);
+            std::thread::spawn(move || arc.len())
+        };
+        assert_eq!(handle.join().unwrap(), 2);
+        let slice: Rc<str> = MyBox::into_rc(MyBox::from_string(String::from("s")));
+        assert_eq!(&*slice, "s");
+    }
+
+    #[test]
+    fn rc_back_to_mybox_when_unique() {
+        let rc = Rc::new(String::from("mine"));
+        let b = rc_into_mybox(rc).ok().unwrap();
+        assert_eq!(b.into_inner(), "mine");
+    }
+
+    #[test]
+    fn rc_back_to_mybox_fails_when_shared() {
+        let rc = Rc::new(5);
+        let clone = Rc::clone(&rc);
+        let rc = rc_into_mybox(rc).unwrap_err();
+        assert_eq!(Rc::strong_count(&rc), 2);
+        drop(clone);
+
+        let weak = Rc::downgrade(&rc);
+        let rc = rc_into_mybox(rc).unwrap_err();
+        assert_eq!(weak.upgrade().as_deref(), Some(&5));
+        drop(weak);
+        assert_eq!(*rc_into_mybox(rc).ok().unwrap(), 5);
+    }
+
+    #[test]
+    fn as_ref_and_as_mut() {
+        let mut b = MyBox::new(vec![1]);
+        b.as_mut().push(2);
+        assert_eq!(b.as_ref(), &vec![1, 2]);
+    }
+}
@@ -8,22 +8,75 @@ fn main() {
     assert_eq!(5, *y); // dereference
     assert_eq!(5, *z); // dereference
     assert_eq!(5, *(z.deref()));
+
+    // DerefMut lets us mutate through MyBox, just like through Box
+    let mut m = MyBox::new(5);
+    *m = 10;
+    assert_eq!(10, *m);
+    *m.get_mut() += 1;
+    assert_eq!(11, m.set(12)); // set hands back the old value
+    assert_eq!(12, *m);
+    let mut other = MyBox::new(0);
+    m.swap(&mut other);
+    assert_eq!(12, m.replace(13) + *other);
+
+    // push_str takes &mut String, which deref coercion finds inside MyBox
+    let mut s = MyBox::new(String::from("Hello"));
+    s.push_str(", world");
+    *s.as_mut() += "!";
+    println!("{}", s.as_ref());
+
+    // the field is private, so into_inner and map are the ways out
+    let len = s.map(|s| s.len());
+    assert_eq!(13, len.into_inner());
+
+    // the hook runs first, then the String inside is dropped
+    let hooked = MyBox::with_drop_hook(String::from("bye"), |s| println!("dropping {}", s));
+    println!("len = {}", hooked.len());
+
+    // &MyBox<str> -> &str, so the classic hello example works unsized too
+    let name = MyBox::from_string(String::from("Rust"));
+    hello(&name);
+    let nums = MyBox::from_vec(vec![1, 2, 3]);
+    println!("sum = {}", nums.iter().sum::<i32>());
+    let mut nums = nums.into_vec(); // back to a growable Vec
+    nums.push(4);
+    assert_eq!("Rust", name.into_string());
+
+    // hand the allocation out as a raw pointer, as you would across FFI
+    let raw = MyBox::into_raw(MyBox::new(7));
+    let back = unsafe { MyBox::from_raw(raw) }; // exactly once
+    let forever: &'static mut i32 = MyBox::leak(back);
+    *forever += 1;
+    println!("leaked {}", forever);
+
+    assert_eq!(5, takes_str(MyBox::new(String::from("boxed"))));
+    assert_eq!(5, takes_str(String::from("owned")));
+    assert_eq!(3, takes_str("str"));
 }
 
-struct MyBox<T>(T);
+fn hello(name: &str) {
+    println!("Hello, {}!", name);
+}
 
-impl<T> MyBox<T> {
-    fn new(x: T) -> MyBox<T> {
-        MyBox(x)
-    }
+// AsRef is the trait-based cousin of deref coercion: one generic function
+// accepts anything that can lend out a &str
+fn takes_str(s: impl AsRef<str>) -> usize {
+    s.as_ref().len()
 }
 
 use std::ops::Deref;
 
-impl<T> Deref for MyBox<T> {
-    type Target = T;
+use lecture7::my_box::MyBox;
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn takes_str_accepts_all_string_forms() {
+        assert_eq!(takes_str(MyBox::new(String::from("abc"))), 3);
+        assert_eq!(takes_str(String::from("abc")), 3);
+        assert_eq!(takes_str("abc"), 3);
     }
-}
\ No newline at end of file
+}
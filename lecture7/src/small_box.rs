@@ -0,0 +1,191 @@
+//! A box that keeps small values inline instead of on the heap.
+
+use std::marker::PhantomData;
+use std::mem::{self, ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+/// The inline buffer. Its alignment is fixed at 16, which covers every
+/// primitive type; a `T` needing more always goes to the heap.
+#[repr(C, align(16))]
+struct Inline<const N: usize>([MaybeUninit<u8>; N]);
+
+const INLINE_ALIGN: usize = 16;
+
+enum Storage<T, const N: usize> {
+    Inline(Inline<N>),
+    Heap(Box<T>),
+}
+
+/// Holds a `T` in an `N`-byte inline buffer when it fits, and in a `Box`
+/// otherwise. Either way the value is dropped exactly once, by `SmallBox`'s
+/// own `Drop` for the inline case and by the `Box` for the heap case.
+pub struct SmallBox<T, const N: usize> {
+    storage: Storage<T, N>,
+    // The inline bytes own a `T`, which the compiler can't see.
+    _marker: PhantomData<T>,
+}
+
+impl<T, const N: usize> SmallBox<T, N> {
+    const FITS: bool = mem::size_of::<T>() <= N && mem::align_of::<T>() <= INLINE_ALIGN;
+
+    pub fn new(value: T) -> SmallBox<T, N> {
+        let storage = if Self::FITS {
+            let mut inline = Inline([MaybeUninit::uninit(); N]);
+            // Safety: the buffer is big enough and aligned for a `T`.
+            unsafe { ptr::write(inline.0.as_mut_ptr() as *mut T, value) };
+            Storage::Inline(inline)
+        } else {
+            Storage::Heap(Box::new(value))
+        };
+        SmallBox {
+            storage,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Whether the value is stored inline rather than on the heap.
+    pub fn is_inline(&self) -> bool {
+        matches!(self.storage, Storage::Inline(_))
+    }
+
+    /// Moves the value out, consuming the box.
+    pub fn into_inner(self) -> T {
+        // Our `Drop` must not run, or the inline value would be dropped
+        // after being moved out.
+        let this = ManuallyDrop::new(self);
+        // Safety: `this` is never used or dropped again, so the value and
+        // the `Box` are each read out exactly once.
+        unsafe {
+            match &this.storage {
+                Storage::Inline(inline) => ptr::read(inline.0.as_ptr() as *const T),
+                Storage::Heap(boxed) => *ptr::read(boxed),
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallBox<T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match &self.storage {
+            // Safety: `new` wrote a `T` here.
+            Storage::Inline(inline) => unsafe { &*(inline.0.as_ptr() as *const T) },
+            Storage::Heap(boxed) => boxed,
+        }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallBox<T, N> {
+    fn deref_mut(&mut self) -> &mut T {
+        match &mut self.storage {
+            // Safety: `new` wrote a `T` here.
+            Storage::Inline(inline) => unsafe { &mut *(inline.0.as_mut_ptr() as *mut T) },
+            Storage::Heap(boxed) => boxed,
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for SmallBox<T, N> {
+    fn drop(&mut self) {
+        if let Storage::Inline(inline) = &mut self.storage {
+            // Safety: the value is still there, and the buffer is not
+            // read again.
+            unsafe { ptr::drop_in_place(inline.0.as_mut_ptr() as *mut T) };
+        }
+        // A `Heap` box is dropped along with `storage`.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct DropCounter<'a, P> {
+        drops: &'a Cell<usize>,
+        _payload: P,
+    }
+
+    impl<'a, P> DropCounter<'a, P> {
+        fn new(drops: &'a Cell<usize>, payload: P) -> Self {
+            DropCounter {
+                drops,
+                _payload: payload,
+            }
+        }
+    }
+
+    impl<P> Drop for DropCounter<'_, P> {
+        fn drop(&mut self) {
+            self.drops.set(self.drops.get() + 1);
+        }
+    }
+
+    #[test]
+    fn zero_sized_is_inline() {
+        struct Unit;
+        let b: SmallBox<Unit, 0> = SmallBox::new(Unit);
+        assert!(b.is_inline());
+        let _: Unit = b.into_inner();
+        assert!(SmallBox::<(), 8>::new(()).is_inline());
+    }
+
+    #[test]
+    fn exactly_at_the_boundary() {
+        let mut fits: SmallBox<[u8; 16], 16> = SmallBox::new([7; 16]);
+        assert!(fits.is_inline());
+        fits[15] = 1;
+        assert_eq!(fits[15], 1);
+        assert_eq!(fits.into_inner()[0], 7);
+
+        let spills: SmallBox<[u8; 17], 16> = SmallBox::new([7; 17]);
+        assert!(!spills.is_inline());
+        assert_eq!(spills.len(), 17);
+    }
+
+    #[test]
+    fn large_values_go_to_the_heap() {
+        let mut b: SmallBox<Vec<u64>, 8> = SmallBox::new(vec![1, 2, 3]);
+        assert!(!b.is_inline());
+        b.push(4);
+        assert_eq!(b.into_inner(), [1, 2, 3, 4]);
+
+        #[repr(align(32))]
+        struct OverAligned(u8);
+        let b: SmallBox<OverAligned, 64> = SmallBox::new(OverAligned(9));
+        assert!(!b.is_inline());
+        assert_eq!(b.0, 9);
+    }
+
+    #[test]
+    fn drops_exactly_once_inline() {
+        let drops = Cell::new(0);
+        let b: SmallBox<_, 32> = SmallBox::new(DropCounter::new(&drops, 0u8));
+        assert!(b.is_inline());
+        drop(b);
+        assert_eq!(drops.get(), 1);
+
+        let b: SmallBox<_, 32> = SmallBox::new(DropCounter::new(&drops, 0u8));
+        let inner = b.into_inner();
+        assert_eq!(drops.get(), 1);
+        drop(inner);
+        assert_eq!(drops.get(), 2);
+    }
+
+    #[test]
+    fn drops_exactly_once_on_heap() {
+        let drops = Cell::new(0);
+        let b: SmallBox<_, 8> = SmallBox::new(DropCounter::new(&drops, [0u8; 64]));
+        assert!(!b.is_inline());
+        drop(b);
+        assert_eq!(drops.get(), 1);
+
+        let b: SmallBox<_, 8> = SmallBox::new(DropCounter::new(&drops, [0u8; 64]));
+        let inner = b.into_inner();
+        assert_eq!(drops.get(), 1);
+        drop(inner);
+        assert_eq!(drops.get(), 2);
+    }
+}
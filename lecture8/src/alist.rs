@@ -0,0 +1,154 @@
+//! The cons list from `list` with `Arc` in place of `Rc`, so lists and
+//! their shared tails can be handed to other threads.
+
+use std::iter::FromIterator;
+use std::sync::Arc;
+use std::thread;
+
+/// Like `list::List`, but `Send + Sync` whenever `T` is.
+pub struct AList<T> {
+    head: Option<Arc<Node<T>>>,
+}
+
+struct Node<T> {
+    value: T,
+    next: AList<T>,
+}
+
+impl<T> AList<T> {
+    pub fn new() -> AList<T> {
+        AList { head: None }
+    }
+
+    /// A new list with `value` in front of `self`, which becomes its tail.
+    pub fn push_front(self, value: T) -> AList<T> {
+        AList {
+            head: Some(Arc::new(Node { value, next: self })),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+}
+
+/// Unlinks the nodes one by one, stopping at the first node that another
+/// list (possibly on another thread) still shares.
+impl<T> Drop for AList<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some(node) = head {
+            match Arc::try_unwrap(node) {
+                Ok(mut node) => head = node.next.head.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Collects in iteration order, so the first item becomes the head.
+impl<T> FromIterator<T> for AList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> AList<T> {
+        let items: Vec<T> = iter.into_iter().collect();
+        items
+            .into_iter()
+            .rev()
+            .fold(AList::new(), |list, item| list.push_front(item))
+    }
+}
+
+impl<T> Default for AList<T> {
+    fn default() -> Self {
+        AList::new()
+    }
+}
+
+impl<T> Clone for AList<T> {
+    fn clone(&self) -> Self {
+        AList {
+            head: self.head.clone(),
+        }
+    }
+}
+
+/// Iterator returned by `AList::iter`.
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        self.next = node.next.head.as_deref();
+        Some(&node.value)
+    }
+}
+
+/// Sums `list` on `threads` workers. Each one gets its own handle to the
+/// same nodes and sums every `threads`-th element, starting at its index.
+///
+/// # Panics
+///
+/// If `threads` is 0.
+pub fn parallel_sum(list: &AList<i64>, threads: usize) -> i64 {
+    assert!(threads > 0, "need at least one thread");
+    let workers: Vec<_> = (0..threads)
+        .map(|start| {
+            let list = list.clone();
+            thread::spawn(move || list.iter().skip(start).step_by(threads).sum::<i64>())
+        })
+        .collect();
+    workers.into_iter().map(|w| w.join().unwrap()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn is_send_and_sync() {
+        assert_send_sync::<AList<i64>>();
+        assert_send_sync::<AList<String>>();
+    }
+
+    #[test]
+    fn push_and_iterate() {
+        let a = AList::new().push_front(10).push_front(5);
+        let b = a.clone().push_front(3);
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), [5, 10]);
+        assert_eq!(b.iter().copied().collect::<Vec<_>>(), [3, 5, 10]);
+        assert_eq!(b.len(), 3);
+        assert!(AList::<i64>::new().is_empty());
+    }
+
+    #[test]
+    fn parallel_matches_sequential() {
+        let list: AList<i64> = (-500..10_000).collect();
+        let sequential: i64 = list.iter().sum();
+        for threads in [1, 2, 3, 7, 16] {
+            assert_eq!(parallel_sum(&list, threads), sequential);
+        }
+        assert_eq!(parallel_sum(&AList::new(), 4), 0);
+    }
+
+    #[test]
+    fn long_list_drops_after_workers_finish() {
+        let list: AList<i64> = (0..200_000).collect();
+        assert_eq!(parallel_sum(&list, 4), 199_999 * 200_000 / 2);
+        drop(list);
+    }
+}
@@ -1,29 +1,25 @@
 // Rc cons list
-use std::cell::RefCell;
-enum List {
-    Cons(i32, RefCell<Rc<List>>),
-    Nil,
-}
-
-use crate::List::{Cons, Nil};
-use std::rc::Rc;
+use lecture8::list::List;
 
 fn main() {
-    let a = Rc::new(Cons(5, Rc::new(Cons(10, Rc::new(Nil)))));
-    let _b = Cons(3, Rc::clone(&a)); // Fully qualified syntax, preferred
-    let _c = Cons(4, a.clone()); // Method-call syntax
+    let a = List::new().push_front(10).push_front(5);
+    let b = a.clone().push_front(3); // b and c share a as their tail
+    let c = a.clone().push_front(4);
+    // (depth, strong count) of each node
+    println!("b: {:?}", b.sharing_report());
+    println!("c: {:?}", c.sharing_report());
 }
 
 // check strong counts
 
 // fn main() {
-//     let a = Rc::new(Cons(5, Rc::new(Cons(10, Rc::new(Nil)))));
-//     println!("count after creating a = {}", Rc::strong_count(&a));
-//     let b = Cons(3, Rc::clone(&a));
-//     println!("count after creating b = {}", Rc::strong_count(&a));
+//     let a = List::new().push_front(10).push_front(5);
+//     println!("count after creating a = {}", a.strong_count());
+//     let b = a.clone().push_front(3);
+//     println!("count after creating b = {}", a.strong_count());
 //     {
-//         let c = Cons(4, Rc::clone(&a));
-//         println!("count after creating c = {}", Rc::strong_count(&a));
+//         let c = a.clone().push_front(4);
+//         println!("count after creating c = {}", a.strong_count());
 //     } // Drop decreases the reference count automatically
-//     println!("count after c goes out of scope = {}", Rc::strong_count(&a));
+//     println!("count after c goes out of scope = {}", a.strong_count());
 // }
@@ -0,0 +1,135 @@
+//! A vector that clones cheaply by sharing one buffer through an `Arc`,
+//! and copies the buffer only when a shared one is written to.
+
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct SharedVec<T: Clone> {
+    items: Arc<Vec<T>>,
+}
+
+impl<T: Clone> SharedVec<T> {
+    pub fn new() -> SharedVec<T> {
+        SharedVec::from(Vec::new())
+    }
+
+    /// Appends `value`, first copying the elements into a buffer of this
+    /// vector's own if any clone still shares them.
+    pub fn push(&mut self, value: T) {
+        Arc::make_mut(&mut self.items).push(value);
+    }
+
+    /// Replaces the element at `index`, copying first like `push`.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds. Nothing is copied in that case.
+    pub fn set(&mut self, index: usize, value: T) {
+        assert!(
+            index < self.items.len(),
+            "index {} out of bounds for length {}",
+            index,
+            self.items.len()
+        );
+        Arc::make_mut(&mut self.items)[index] = value;
+    }
+
+    /// Whether another clone shares this vector's buffer.
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.items) > 1
+    }
+
+    /// How many clones share this vector's buffer, itself included.
+    pub fn strong_count(&self) -> usize {
+        Arc::strong_count(&self.items)
+    }
+}
+
+/// Another handle to the same buffer; no elements are copied.
+impl<T: Clone> Clone for SharedVec<T> {
+    fn clone(&self) -> Self {
+        SharedVec {
+            items: Arc::clone(&self.items),
+        }
+    }
+}
+
+impl<T: Clone> Default for SharedVec<T> {
+    fn default() -> Self {
+        SharedVec::new()
+    }
+}
+
+impl<T: Clone> From<Vec<T>> for SharedVec<T> {
+    fn from(items: Vec<T>) -> SharedVec<T> {
+        SharedVec {
+            items: Arc::new(items),
+        }
+    }
+}
+
+impl<T: Clone> Deref for SharedVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unshared_writes_in_place() {
+        let mut v = SharedVec::from(Vec::with_capacity(8));
+        v.push(1);
+        let ptr = v.as_ptr();
+        v.push(2);
+        v.set(0, 10);
+        assert_eq!(v.as_ptr(), ptr);
+        assert_eq!(&v[..], [10, 2]);
+        assert!(!v.is_shared());
+    }
+
+    #[test]
+    fn shared_writes_copy() {
+        let mut a = SharedVec::from(vec![1, 2, 3]);
+        let b = a.clone();
+        assert_eq!(a.as_ptr(), b.as_ptr());
+        assert!(a.is_shared() && b.is_shared());
+
+        a.set(1, 20);
+        assert_ne!(a.as_ptr(), b.as_ptr());
+        assert_eq!(&a[..], [1, 20, 3]);
+        assert_eq!(&b[..], [1, 2, 3]);
+        assert!(!a.is_shared() && !b.is_shared());
+
+        let mut c = b.clone();
+        c.push(4);
+        assert_eq!(&b[..], [1, 2, 3]);
+        assert_eq!(&c[..], [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn counts_follow_clones_and_drops() {
+        let a = SharedVec::from(vec!['x']);
+        assert_eq!(a.strong_count(), 1);
+        let b = a.clone();
+        let c = b.clone();
+        assert_eq!(a.strong_count(), 3);
+        drop(b);
+        assert_eq!(c.strong_count(), 2);
+        let mut c = c;
+        c.push('y');
+        assert_eq!((a.strong_count(), c.strong_count()), (1, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn set_out_of_bounds() {
+        let mut v: SharedVec<i32> = SharedVec::new();
+        v.set(0, 1);
+    }
+}
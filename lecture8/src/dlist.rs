@@ -0,0 +1,265 @@
+//! A doubly linked list. Each node owns the next one through an `Rc` but
+//! only points back at the previous one through a `Weak`, so neighbours
+//! never form a strong cycle and every node is freed when the list goes.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+
+type Link<T> = Rc<RefCell<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Option<Link<T>>,
+    prev: Option<Weak<RefCell<Node<T>>>>,
+}
+
+pub struct DList<T> {
+    head: Option<Link<T>>,
+    tail: Option<Link<T>>,
+    len: usize,
+}
+
+impl<T> DList<T> {
+    pub fn new() -> DList<T> {
+        DList {
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: self.head.take(),
+            prev: None,
+        }));
+        match &node.borrow().next {
+            Some(old) => old.borrow_mut().prev = Some(Rc::downgrade(&node)),
+            None => self.tail = Some(Rc::clone(&node)),
+        }
+        self.head = Some(node);
+        self.len += 1;
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Rc::new(RefCell::new(Node {
+            value,
+            next: None,
+            prev: self.tail.as_ref().map(Rc::downgrade),
+        }));
+        match self.tail.take() {
+            Some(old) => old.borrow_mut().next = Some(Rc::clone(&node)),
+            None => self.head = Some(Rc::clone(&node)),
+        }
+        self.tail = Some(node);
+        self.len += 1;
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        let node = self.head.take()?;
+        match node.borrow_mut().next.take() {
+            Some(next) => {
+                next.borrow_mut().prev = None;
+                self.head = Some(next);
+            }
+            None => self.tail = None,
+        }
+        self.len -= 1;
+        Some(into_value(node))
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        let node = self.tail.take()?;
+        match node
+            .borrow_mut()
+            .prev
+            .take()
+            .and_then(|prev| prev.upgrade())
+        {
+            Some(prev) => {
+                prev.borrow_mut().next = None;
+                self.tail = Some(prev);
+            }
+            None => self.head = None,
+        }
+        self.len -= 1;
+        Some(into_value(node))
+    }
+
+    /// Clones of the values from front to back.
+    ///
+    /// The iterator holds `Rc`s to the nodes, so it borrows the list to
+    /// stop nodes being popped while it still points at them:
+    ///
+    /// ```compile_fail
+    /// let mut list = lecture8::dlist::DList::new();
+    /// list.push_back(1);
+    /// let mut iter = list.iter();
+    /// list.pop_front();
+    /// iter.next();
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.clone(),
+            _list: PhantomData,
+        }
+    }
+
+    /// Clones of the values from back to front, following the `Weak`
+    /// back-pointers. Borrows the list like `iter`.
+    pub fn iter_rev(&self) -> IterRev<'_, T> {
+        IterRev {
+            next: self.tail.clone(),
+            _list: PhantomData,
+        }
+    }
+}
+
+/// The value of a node that has been unlinked from both neighbours. The
+/// iterators borrow the list, so nothing else can still hold the node.
+fn into_value<T>(node: Link<T>) -> T {
+    match Rc::try_unwrap(node) {
+        Ok(cell) => cell.into_inner().value,
+        Err(_) => unreachable!("unlinked node still shared"),
+    }
+}
+
+impl<T> Default for DList<T> {
+    fn default() -> Self {
+        DList::new()
+    }
+}
+
+/// Pops nodes one at a time, so a long list doesn't drop recursively
+/// through its `next` links.
+impl<T> Drop for DList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
+/// Iterator returned by `DList::iter`.
+pub struct Iter<'a, T> {
+    next: Option<Link<T>>,
+    _list: PhantomData<&'a DList<T>>,
+}
+
+impl<T: Clone> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let node = self.next.take()?;
+        let node = node.borrow();
+        self.next = node.next.clone();
+        Some(node.value.clone())
+    }
+}
+
+/// Iterator returned by `DList::iter_rev`.
+pub struct IterRev<'a, T> {
+    next: Option<Link<T>>,
+    _list: PhantomData<&'a DList<T>>,
+}
+
+impl<T: Clone> Iterator for IterRev<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let node = self.next.take()?;
+        let node = node.borrow();
+        self.next = node.prev.as_ref().and_then(Weak::upgrade);
+        Some(node.value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn push_and_pop_both_ends() {
+        let mut list = DList::new();
+        assert_eq!(list.pop_front(), None);
+        assert_eq!(list.pop_back(), None);
+        list.push_back(2);
+        list.push_front(1);
+        list.push_back(3);
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.iter().collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(list.iter_rev().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_back(), Some(2));
+        assert!(list.is_empty());
+        assert_eq!(list.iter().count(), 0);
+        list.push_front(4);
+        assert_eq!(list.iter_rev().collect::<Vec<_>>(), [4]);
+    }
+
+    #[test]
+    fn pops_after_partial_iteration() {
+        let mut list = DList::new();
+        for i in 0..4 {
+            list.push_back(i);
+        }
+        let mut iter = list.iter();
+        assert_eq!(iter.next(), Some(0));
+        drop(iter);
+        let mut rev = list.iter_rev();
+        assert_eq!(rev.next(), Some(3));
+        drop(rev);
+        // the dropped iterators left no extra `Rc`s behind to trip up
+        // the pops
+        assert_eq!(list.pop_front(), Some(0));
+        assert_eq!(list.pop_back(), Some(3));
+        assert_eq!(list.iter().collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn back_pointers_are_weak() {
+        let mut list = DList::new();
+        list.push_back('a');
+        list.push_back('b');
+        let head = list.head.clone().unwrap();
+        // one from the list's head, one from this test, none from `b`
+        assert_eq!(Rc::strong_count(&head), 2);
+        assert_eq!(Rc::weak_count(&head), 1);
+    }
+
+    #[test]
+    fn every_node_is_freed() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let mut list = DList::new();
+        for i in 0..10_000 {
+            if i % 2 == 0 {
+                list.push_back(Counted(&drops));
+            } else {
+                list.push_front(Counted(&drops));
+            }
+        }
+        drop(list.pop_back());
+        drop(list.pop_front());
+        assert_eq!(drops.get(), 2);
+        drop(list);
+        assert_eq!(drops.get(), 10_000);
+    }
+}
@@ -0,0 +1,143 @@
+//! A first-in first-out queue made of two persistent lists: new elements
+//! are pushed onto `back`, and taken off `front`, which is refilled by
+//! reversing `back` whenever it runs out. Each element is moved across
+//! once, so every operation is O(1) amortized.
+
+use std::mem;
+
+use crate::list::List;
+
+/// `front` holds the oldest elements, oldest first; `back` the newest,
+/// newest first. `front` is only empty when the whole queue is, so the
+/// next element is always at its head. Cloning is cheap and the clones
+/// share nodes until one of them refills its `front`.
+#[derive(Clone)]
+pub struct Queue<T> {
+    front: List<T>,
+    back: List<T>,
+    len: usize,
+}
+
+impl<T: Clone> Queue<T> {
+    pub fn new() -> Queue<T> {
+        Queue {
+            front: List::new(),
+            back: List::new(),
+            len: 0,
+        }
+    }
+
+    pub fn enqueue(&mut self, value: T) {
+        if self.front.is_empty() {
+            self.front = List::new().push_front(value);
+        } else {
+            self.back = mem::take(&mut self.back).push_front(value);
+        }
+        self.len += 1;
+    }
+
+    pub fn dequeue(&mut self) -> Option<T> {
+        let value = self.front.pop_front()?;
+        self.len -= 1;
+        if self.front.is_empty() {
+            // moves the values across where `back` owns its nodes alone,
+            // cloning only those another queue still shares
+            while let Some(value) = self.back.pop_front() {
+                self.front = mem::take(&mut self.front).push_front(value);
+            }
+        }
+        Some(value)
+    }
+
+    /// The element `dequeue` would return next.
+    pub fn peek(&self) -> Option<&T> {
+        self.front.head()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T: Clone> Default for Queue<T> {
+    fn default() -> Self {
+        Queue::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    #[test]
+    fn fifo_order() {
+        let mut queue = Queue::new();
+        assert_eq!(queue.dequeue(), None);
+        assert_eq!(queue.peek(), None);
+        queue.enqueue(1);
+        queue.enqueue(2);
+        queue.enqueue(3);
+        assert_eq!(queue.peek(), Some(&1));
+        assert_eq!(queue.dequeue(), Some(1));
+        queue.enqueue(4);
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.dequeue(), Some(2));
+        assert_eq!(queue.dequeue(), Some(3));
+        queue.enqueue(5);
+        assert_eq!(queue.peek(), Some(&4));
+        assert_eq!(queue.dequeue(), Some(4));
+        assert_eq!(queue.dequeue(), Some(5));
+        assert_eq!(queue.dequeue(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn clones_are_independent() {
+        let mut a = Queue::new();
+        for i in 0..5 {
+            a.enqueue(i.to_string());
+        }
+        let mut b = a.clone();
+        assert_eq!(a.dequeue().as_deref(), Some("0"));
+        b.enqueue("5".to_string());
+        assert_eq!(a.len(), 4);
+        assert_eq!(b.len(), 6);
+        let rest: Vec<_> = (0..6).map(|_| b.dequeue().unwrap()).collect();
+        assert_eq!(rest, ["0", "1", "2", "3", "4", "5"]);
+        assert_eq!(a.peek().map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn matches_vec_deque() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = || {
+            state ^= state >> 12;
+            state ^= state << 25;
+            state ^= state >> 27;
+            state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32
+        };
+        let mut queue = Queue::new();
+        let mut expected = VecDeque::new();
+        for _ in 0..10_000 {
+            let r = next();
+            // slightly more enqueues than dequeues so the queue grows
+            if r % 5 < 3 {
+                queue.enqueue(r);
+                expected.push_back(r);
+            } else {
+                assert_eq!(queue.dequeue(), expected.pop_front());
+            }
+            assert_eq!(queue.peek(), expected.front());
+            assert_eq!(queue.len(), expected.len());
+        }
+        while let Some(value) = expected.pop_front() {
+            assert_eq!(queue.dequeue(), Some(value));
+        }
+        assert!(queue.is_empty());
+    }
+}
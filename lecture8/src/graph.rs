@@ -0,0 +1,153 @@
+//! A directed graph whose edges are `Weak`. Nodes are owned by whoever
+//! holds their `Rc`, so a node disappears, edges and all, as soon as the
+//! last outside handle to it is dropped, and cycles of edges never leak.
+
+use std::cell::RefCell;
+use std::collections::{HashSet, VecDeque};
+use std::rc::{Rc, Weak};
+
+pub type NodeRef<T> = Rc<RefCell<GraphNode<T>>>;
+
+#[derive(Debug)]
+pub struct GraphNode<T> {
+    pub value: T,
+    edges: Vec<Weak<RefCell<GraphNode<T>>>>,
+}
+
+/// Keeps only weak handles to its nodes, so that it can list the ones that
+/// are still alive without keeping any of them alive itself.
+#[derive(Debug)]
+pub struct Graph<T> {
+    nodes: Vec<Weak<RefCell<GraphNode<T>>>>,
+}
+
+impl<T> Graph<T> {
+    pub fn new() -> Graph<T> {
+        Graph { nodes: vec![] }
+    }
+
+    /// A new node with no edges. The caller owns it: dropping the returned
+    /// `Rc` (and any clones) removes it from the graph.
+    pub fn add_node(&mut self, value: T) -> NodeRef<T> {
+        self.nodes.retain(|n| n.strong_count() > 0);
+        let node = Rc::new(RefCell::new(GraphNode {
+            value,
+            edges: vec![],
+        }));
+        self.nodes.push(Rc::downgrade(&node));
+        node
+    }
+
+    /// Adds an edge from `a` to `b`, clearing out any of `a`'s edges to
+    /// nodes that have since been dropped.
+    pub fn add_edge(&self, a: &NodeRef<T>, b: &NodeRef<T>) {
+        let mut a = a.borrow_mut();
+        a.edges.retain(|e| e.strong_count() > 0);
+        a.edges.push(Rc::downgrade(b));
+    }
+
+    /// The nodes that are still alive, in the order they were added.
+    pub fn nodes(&self) -> Vec<NodeRef<T>> {
+        self.nodes.iter().filter_map(Weak::upgrade).collect()
+    }
+
+    /// The live targets of `node`'s edges.
+    pub fn neighbors(&self, node: &NodeRef<T>) -> Vec<NodeRef<T>> {
+        node.borrow()
+            .edges
+            .iter()
+            .filter_map(Weak::upgrade)
+            .collect()
+    }
+
+    /// Whether `to` can be reached from `from` by following edges, found
+    /// breadth-first. Every node is visited at most once, so cycles are
+    /// fine. A node always reaches itself.
+    pub fn reachable(&self, from: &NodeRef<T>, to: &NodeRef<T>) -> bool {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert(Rc::as_ptr(from));
+        queue.push_back(Rc::clone(from));
+        while let Some(node) = queue.pop_front() {
+            if Rc::ptr_eq(&node, to) {
+                return true;
+            }
+            for next in self.neighbors(&node) {
+                if seen.insert(Rc::as_ptr(&next)) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        false
+    }
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Graph::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(nodes: Vec<NodeRef<char>>) -> Vec<char> {
+        nodes.iter().map(|n| n.borrow().value).collect()
+    }
+
+    #[test]
+    fn cycle_does_not_hang() {
+        let mut graph = Graph::new();
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        let c = graph.add_node('c');
+        let d = graph.add_node('d');
+        graph.add_edge(&a, &b);
+        graph.add_edge(&b, &c);
+        graph.add_edge(&c, &a);
+        assert!(graph.reachable(&a, &c));
+        assert!(graph.reachable(&c, &b));
+        assert!(graph.reachable(&b, &b));
+        assert!(!graph.reachable(&a, &d));
+        assert!(!graph.reachable(&d, &a));
+        // weak edges: the cycle holds no strong counts
+        assert_eq!(Rc::strong_count(&a), 1);
+    }
+
+    #[test]
+    fn dropping_a_node_removes_its_edges() {
+        let mut graph = Graph::new();
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        let c = graph.add_node('c');
+        graph.add_edge(&a, &b);
+        graph.add_edge(&b, &c);
+        assert!(graph.reachable(&a, &c));
+        assert_eq!(values(graph.neighbors(&a)), ['b']);
+
+        drop(b);
+        assert!(!graph.reachable(&a, &c));
+        assert!(graph.neighbors(&a).is_empty());
+        assert_eq!(values(graph.nodes()), ['a', 'c']);
+    }
+
+    #[test]
+    fn dead_edges_are_skipped() {
+        let mut graph = Graph::new();
+        let a = graph.add_node('a');
+        let b = graph.add_node('b');
+        graph.add_edge(&a, &b);
+        for value in ['x', 'y', 'z'] {
+            let temp = graph.add_node(value);
+            graph.add_edge(&a, &temp);
+        }
+        // each new edge cleared out the one before, leaving only `z` dead
+        assert_eq!(a.borrow().edges.len(), 2);
+        assert_eq!(values(graph.neighbors(&a)), ['b']);
+        assert!(graph.reachable(&a, &b));
+        graph.add_edge(&a, &a);
+        assert_eq!(a.borrow().edges.len(), 2);
+        assert_eq!(values(graph.neighbors(&a)), ['b', 'a']);
+    }
+}
@@ -0,0 +1,130 @@
+//! A value computed on first use, like a simplified `std::cell::LazyCell`.
+
+use std::cell::UnsafeCell;
+use std::ops::Deref;
+
+enum State<T, F> {
+    Uninit(F),
+    Init(T),
+    /// The closure is running, or panicked while running.
+    Poisoned,
+}
+
+/// Holds a closure until the first deref or `force`, then the value it
+/// returned.
+pub struct LazyBox<T, F = fn() -> T> {
+    state: UnsafeCell<State<T, F>>,
+}
+
+impl<T, F: FnOnce() -> T> LazyBox<T, F> {
+    pub fn new(f: F) -> LazyBox<T, F> {
+        LazyBox {
+            state: UnsafeCell::new(State::Uninit(f)),
+        }
+    }
+
+    /// The value, running the closure first if this is the first access.
+    ///
+    /// Panics if the closure panicked on an earlier call, or if it tries to
+    /// read this same `LazyBox` while running.
+    pub fn force(&self) -> &T {
+        // Safety: references into the state are only handed out once it is
+        // `Init`, after which it never changes, and the mutable borrows
+        // below never overlap with one another.
+        unsafe {
+            if let State::Init(value) = &*self.state.get() {
+                return value;
+            }
+            let f = match std::mem::replace(&mut *self.state.get(), State::Poisoned) {
+                State::Uninit(f) => f,
+                State::Init(_) => unreachable!(),
+                State::Poisoned => panic!("LazyBox poisoned or forced re-entrantly"),
+            };
+            let value = f();
+            *self.state.get() = State::Init(value);
+            match &*self.state.get() {
+                State::Init(value) => value,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        matches!(unsafe { &*self.state.get() }, State::Init(_))
+    }
+
+    /// The value if it has been computed, or otherwise the closure that
+    /// would have computed it.
+    pub fn into_inner(self) -> Result<T, F> {
+        match self.state.into_inner() {
+            State::Init(value) => Ok(value),
+            State::Uninit(f) => Err(f),
+            State::Poisoned => panic!("LazyBox poisoned"),
+        }
+    }
+}
+
+impl<T, F: FnOnce() -> T> Deref for LazyBox<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.force()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn closure_runs_once() {
+        let calls = Cell::new(0);
+        let lazy = LazyBox::new(|| {
+            calls.set(calls.get() + 1);
+            String::from("computed")
+        });
+        assert!(!lazy.is_initialized());
+        assert_eq!(calls.get(), 0);
+        assert_eq!(lazy.len(), 8);
+        assert_eq!(*lazy, "computed");
+        assert_eq!(lazy.force(), "computed");
+        assert!(lazy.is_initialized());
+        assert_eq!(calls.get(), 1);
+        assert_eq!(lazy.into_inner().ok(), Some(String::from("computed")));
+    }
+
+    #[test]
+    fn into_inner_before_force_returns_closure() {
+        let calls = Cell::new(0);
+        let lazy = LazyBox::new(|| {
+            calls.set(calls.get() + 1);
+            7
+        });
+        let f = match lazy.into_inner() {
+            Ok(_) => panic!("never forced"),
+            Err(f) => f,
+        };
+        assert_eq!(calls.get(), 0);
+        assert_eq!(f(), 7);
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn fn_pointer_default() {
+        fn answer() -> u32 {
+            42
+        }
+        let lazy: LazyBox<u32> = LazyBox::new(answer);
+        assert_eq!(*lazy + 1, 43);
+    }
+
+    #[test]
+    #[should_panic(expected = "poisoned")]
+    fn panicking_closure_poisons() {
+        let lazy = LazyBox::new(|| -> u32 { panic!("boom") });
+        let lazy = std::panic::AssertUnwindSafe(lazy);
+        let _ = std::panic::catch_unwind(|| lazy.force());
+        lazy.force();
+    }
+}
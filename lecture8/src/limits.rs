@@ -0,0 +1,156 @@
+//! The quota tracker from the lecture, which reports through whatever
+//! `Messenger` it is given, along with a messenger that records messages
+//! for tests and one that prints them.
+
+use std::cell::RefCell;
+
+pub trait Messenger {
+    fn send(&self, msg: &str);
+}
+
+pub struct LimitTracker<'a, T: Messenger> {
+    messenger: &'a T,
+    value: u64,
+    max: u64,
+}
+
+impl<'a, T> LimitTracker<'a, T>
+where
+    T: Messenger,
+{
+    pub fn new(messenger: &'a T, max: u64) -> LimitTracker<'a, T> {
+        LimitTracker {
+            messenger,
+            value: 0,
+            max,
+        }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Records `value` and sends a message if it is at least 75%, 90% or
+    /// 100% of `max`. Only one message is sent per call: the one for the
+    /// highest threshold reached, however many were crossed to get there.
+    /// With a `max` of 0 every value is over quota.
+    pub fn set_value(&mut self, value: u64) {
+        self.value = value;
+
+        // compare value / max against each threshold without rounding
+        let percent = u128::from(self.value) * 100;
+        let max = u128::from(self.max);
+
+        if percent >= max * 100 {
+            self.messenger.send("Error: You are over your quota!");
+        } else if percent >= max * 90 {
+            self.messenger
+                .send("Urgent warning: You've used up over 90% of your quota!");
+        } else if percent >= max * 75 {
+            self.messenger
+                .send("Warning: You've used up over 75% of your quota!");
+        }
+    }
+}
+
+/// Keeps every message it is sent. `send` only takes `&self`, so the
+/// messages live in a `RefCell`.
+#[derive(Debug, Default)]
+pub struct MockMessenger {
+    sent_messages: RefCell<Vec<String>>,
+}
+
+impl MockMessenger {
+    pub fn new() -> MockMessenger {
+        MockMessenger {
+            sent_messages: RefCell::new(vec![]),
+        }
+    }
+
+    pub fn sent_messages(&self) -> Vec<String> {
+        self.sent_messages.borrow().clone()
+    }
+}
+
+impl Messenger for MockMessenger {
+    fn send(&self, message: &str) {
+        self.sent_messages.borrow_mut().push(String::from(message));
+    }
+}
+
+/// Prints each message on its own line.
+#[derive(Debug, Default)]
+pub struct StdoutMessenger;
+
+impl Messenger for StdoutMessenger {
+    fn send(&self, message: &str) {
+        println!("{}", message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WARNING: &str = "Warning: You've used up over 75% of your quota!";
+    const URGENT: &str = "Urgent warning: You've used up over 90% of your quota!";
+    const ERROR: &str = "Error: You are over your quota!";
+
+    /// The messages sent by a fresh tracker for a single `set_value`.
+    fn sent(max: u64, value: u64) -> Vec<String> {
+        let mock_messenger = MockMessenger::new();
+        LimitTracker::new(&mock_messenger, max).set_value(value);
+        mock_messenger.sent_messages()
+    }
+
+    #[test]
+    fn it_sends_an_over_75_percent_warning_message() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        limit_tracker.set_value(80);
+
+        assert_eq!(mock_messenger.sent_messages.borrow().len(), 1);
+    }
+
+    #[test]
+    fn exactly_75_percent() {
+        assert!(sent(100, 74).is_empty());
+        assert_eq!(sent(100, 75), [WARNING]);
+        assert!(sent(1000, 749).is_empty());
+        assert_eq!(sent(1000, 750), [WARNING]);
+        // 75% of 3 is 2.25
+        assert!(sent(3, 2).is_empty());
+        assert_eq!(sent(4, 3), [WARNING]);
+    }
+
+    #[test]
+    fn exactly_90_percent() {
+        assert_eq!(sent(100, 89), [WARNING]);
+        assert_eq!(sent(100, 90), [URGENT]);
+        assert_eq!(sent(1000, 899), [WARNING]);
+        assert_eq!(sent(1000, 900), [URGENT]);
+    }
+
+    #[test]
+    fn at_and_over_max() {
+        assert_eq!(sent(100, 99), [URGENT]);
+        assert_eq!(sent(100, 100), [ERROR]);
+        assert_eq!(sent(100, 101), [ERROR]);
+        assert_eq!(sent(u64::MAX, u64::MAX), [ERROR]);
+        assert_eq!(sent(u64::MAX, u64::MAX - 1), [URGENT]);
+        assert_eq!(sent(0, 0), [ERROR]);
+    }
+
+    #[test]
+    fn only_the_highest_threshold_is_reported() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+        limit_tracker.set_value(10);
+        limit_tracker.set_value(95);
+        limit_tracker.set_value(200);
+        limit_tracker.set_value(0);
+        assert_eq!(limit_tracker.value(), 0);
+        assert_eq!(mock_messenger.sent_messages(), [URGENT, ERROR]);
+    }
+}
@@ -0,0 +1,765 @@
+//! A persistent cons list: pushing onto a list makes a new list that shares
+//! the old one as its tail through an `Rc`, without copying any elements.
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::iter::FromIterator;
+use std::rc::Rc;
+use std::str::FromStr;
+
+/// Returned by `List::read_from`. `E` is the element type's `FromStr`
+/// error.
+#[derive(Debug)]
+pub enum ReadError<E> {
+    Io(io::Error),
+    Parse { line: usize, source: E },
+}
+
+impl<E: fmt::Display> fmt::Display for ReadError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => write!(f, "read failed: {}", e),
+            ReadError::Parse { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for ReadError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse { source, .. } => Some(source),
+        }
+    }
+}
+
+impl<E> From<io::Error> for ReadError<E> {
+    fn from(e: io::Error) -> Self {
+        ReadError::Io(e)
+    }
+}
+
+/// A list is a pointer to its first node, or nothing for the empty list.
+/// Cloning a list is cheap: it only bumps the first node's strong count.
+pub struct List<T> {
+    head: Option<Rc<Node<T>>>,
+}
+
+struct Node<T> {
+    value: T,
+    next: List<T>,
+}
+
+impl<T> List<T> {
+    pub fn new() -> List<T> {
+        List { head: None }
+    }
+
+    /// A new list with `value` in front of `self`, which becomes its tail.
+    pub fn push_front(self, value: T) -> List<T> {
+        List {
+            head: Some(Rc::new(Node { value, next: self })),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Everything after the first element, or `None` for the empty list.
+    pub fn tail(&self) -> Option<&List<T>> {
+        self.head.as_ref().map(|node| &node.next)
+    }
+
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// How many lists share the first node, or 0 for the empty list.
+    pub fn strong_count(&self) -> usize {
+        self.head.as_ref().map_or(0, Rc::strong_count)
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> List<U> {
+        self.iter().map(f).collect()
+    }
+
+    /// The elements matching `pred`, in order. The part of `self` after
+    /// the last rejected element is shared rather than copied, so keeping
+    /// everything just returns another handle to `self`.
+    pub fn filter(&self, pred: impl Fn(&T) -> bool) -> List<T>
+    where
+        T: Clone,
+    {
+        let mut kept = Vec::new();
+        let mut rebuilt = 0;
+        let mut shared = self;
+        let mut list = self;
+        while let Some(node) = list.head.as_deref() {
+            if pred(&node.value) {
+                kept.push(&node.value);
+            } else {
+                rebuilt = kept.len();
+                shared = &node.next;
+            }
+            list = &node.next;
+        }
+        kept.truncate(rebuilt);
+        kept.into_iter()
+            .rev()
+            .fold(shared.clone(), |list, value| list.push_front(value.clone()))
+    }
+
+    pub fn rev(&self) -> List<T>
+    where
+        T: Clone,
+    {
+        self.iter()
+            .fold(List::new(), |list, value| list.push_front(value.clone()))
+    }
+
+    pub fn fold<A>(&self, init: A, f: impl Fn(A, &T) -> A) -> A {
+        self.iter().fold(init, f)
+    }
+
+    /// Merges two sorted lists into one. On ties the element from `a`
+    /// comes first, so merging is stable.
+    pub fn merge_sorted(a: &List<T>, b: &List<T>) -> List<T>
+    where
+        T: Ord + Clone,
+    {
+        let mut merged = Vec::new();
+        let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            if y < x {
+                merged.push(b.next().unwrap().clone());
+            } else {
+                merged.push(a.next().unwrap().clone());
+            }
+        }
+        merged.extend(a.cloned());
+        merged.extend(b.cloned());
+        List::from(merged)
+    }
+
+    /// A stable sort: a bottom-up merge sort that merges runs of length
+    /// 1, 2, 4, ... in a loop rather than recursing.
+    pub fn sort(&self) -> List<T>
+    where
+        T: Ord + Clone,
+    {
+        let mut runs: Vec<List<T>> = self
+            .iter()
+            .map(|value| List::new().push_front(value.clone()))
+            .collect();
+        while runs.len() > 1 {
+            runs = runs
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => List::merge_sorted(a, b),
+                    [a] => a.clone(),
+                    _ => unreachable!(),
+                })
+                .collect();
+        }
+        runs.pop().unwrap_or_default()
+    }
+
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.iter().cloned().collect()
+    }
+
+    /// The depth and strong count of every node, head first, showing
+    /// where other lists share this one's tail.
+    pub fn sharing_report(&self) -> Vec<(usize, usize)> {
+        let mut report = Vec::new();
+        let mut list = self;
+        while let Some(node) = &list.head {
+            report.push((report.len(), Rc::strong_count(node)));
+            list = &node.next;
+        }
+        report
+    }
+
+    /// The element at index `n`, counting from 0 at the head.
+    pub fn nth(&self, n: usize) -> Option<&T> {
+        self.iter().nth(n)
+    }
+
+    pub fn contains(&self, x: &T) -> bool
+    where
+        T: PartialEq,
+    {
+        self.iter().any(|value| value == x)
+    }
+
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<&T> {
+        self.iter().find(|value| pred(value))
+    }
+
+    pub fn position(&self, pred: impl Fn(&T) -> bool) -> Option<usize> {
+        self.iter().position(pred)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.iter().last()
+    }
+
+    /// The first `n` elements and the rest. The second list shares its
+    /// nodes with `self`; only the first is rebuilt. If `n` is past the
+    /// end, the first list is a copy of `self` and the second is empty.
+    pub fn split_at(&self, n: usize) -> (List<T>, List<T>)
+    where
+        T: Clone,
+    {
+        let mut front = Vec::new();
+        let mut rest = self;
+        while front.len() < n {
+            match rest.head.as_deref() {
+                Some(node) => {
+                    front.push(node.value.clone());
+                    rest = &node.next;
+                }
+                None => break,
+            }
+        }
+        (List::from(front), rest.clone())
+    }
+
+    /// Removes and returns the first element, leaving the tail in `self`.
+    /// The value is moved out if no other list shares the first node and
+    /// cloned otherwise.
+    pub(crate) fn pop_front(&mut self) -> Option<T>
+    where
+        T: Clone,
+    {
+        let node = self.head.take()?;
+        match Rc::try_unwrap(node) {
+            Ok(mut node) => {
+                self.head = node.next.head.take();
+                Some(node.value)
+            }
+            Err(node) => {
+                self.head = node.next.head.clone();
+                Some(node.value.clone())
+            }
+        }
+    }
+}
+
+/// Unlinks the nodes one by one instead of letting each node's drop recurse
+/// into the next, which would overflow the stack on long lists. It stops at
+/// the first node that another list still shares.
+impl<T> Drop for List<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.take();
+        while let Some(node) = head {
+            match Rc::try_unwrap(node) {
+                Ok(mut node) => head = node.next.head.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+/// Collects in iteration order, so the first item becomes the head.
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> List<T> {
+        let items: Vec<T> = iter.into_iter().collect();
+        List::from(items)
+    }
+}
+
+impl<T> From<Vec<T>> for List<T> {
+    fn from(items: Vec<T>) -> List<T> {
+        items
+            .into_iter()
+            .rev()
+            .fold(List::new(), |list, item| list.push_front(item))
+    }
+}
+
+impl<'a, T> IntoIterator for &'a List<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T> Default for List<T> {
+    fn default() -> Self {
+        List::new()
+    }
+}
+
+impl<T> Clone for List<T> {
+    fn clone(&self) -> Self {
+        List {
+            head: self.head.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The lecture's nested notation, `(5, (10, Nil))`, or with `{:#}` the
+/// flat `5 -> 10 -> Nil`. Both are written in a loop, not recursively.
+impl<T: fmt::Display> fmt::Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut depth = 0;
+        for value in self {
+            if f.alternate() {
+                write!(f, "{} -> ", value)?;
+            } else {
+                write!(f, "({}, ", value)?;
+                depth += 1;
+            }
+        }
+        write!(f, "Nil")?;
+        for _ in 0..depth {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> List<T> {
+    /// The flat form, `5 -> 10 -> Nil`.
+    pub fn fmt_flat(&self) -> String {
+        format!("{:#}", self)
+    }
+}
+
+impl<T: fmt::Display> List<T> {
+    /// Writes one element per line, head first. Elements whose `Display`
+    /// contains a newline won't read back.
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        for value in self {
+            writeln!(w, "{}", value)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: FromStr> List<T> {
+    /// Reads the format written by `write_to`. Every line is an element,
+    /// including blank ones. Line numbers in errors start at 1.
+    pub fn read_from<R: BufRead>(r: R) -> Result<List<T>, ReadError<T::Err>> {
+        let mut items = Vec::new();
+        for (index, line) in r.lines().enumerate() {
+            let value = line?.parse().map_err(|source| ReadError::Parse {
+                line: index + 1,
+                source,
+            })?;
+            items.push(value);
+        }
+        Ok(List::from(items))
+    }
+}
+
+/// Compares element by element, so lists built with different sharing
+/// are equal when they hold the same values.
+impl<T: PartialEq> PartialEq for List<T> {
+    fn eq(&self, other: &List<T>) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for List<T> {}
+
+/// Iterator returned by `List::iter`.
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let node = self.next?;
+        self.next = node.next.head.as_deref();
+        Some(&node.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_head_tail() {
+        let empty: List<i32> = List::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.head(), None);
+        assert!(empty.tail().is_none());
+
+        let list = List::new().push_front(10).push_front(5);
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.head(), Some(&5));
+        let tail = list.tail().unwrap();
+        assert_eq!(tail.head(), Some(&10));
+        assert!(tail.tail().unwrap().is_empty());
+        assert_eq!(list.iter().collect::<Vec<_>>(), [&5, &10]);
+    }
+
+    #[test]
+    fn shared_tail_is_not_copied() {
+        // a = (5, (10, Nil)); b = (3, a); c = (4, a)
+        let a = List::new()
+            .push_front(String::from("10"))
+            .push_front(String::from("5"));
+        assert_eq!(a.strong_count(), 1);
+        let b = a.clone().push_front(String::from("3"));
+        assert_eq!(a.strong_count(), 2);
+        {
+            let c = a.clone().push_front(String::from("4"));
+            assert_eq!(a.strong_count(), 3);
+            // c's tail is a's very first node, not a copy of it
+            assert!(std::ptr::eq(
+                c.tail().unwrap().head().unwrap(),
+                a.head().unwrap()
+            ));
+        }
+        assert_eq!(a.strong_count(), 2);
+        assert_eq!(b.len(), 3);
+        assert_eq!(b.tail().unwrap().strong_count(), 2);
+    }
+
+    #[test]
+    fn debug_prints_like_a_vec() {
+        let list = List::new().push_front(3).push_front(2).push_front(1);
+        assert_eq!(format!("{:?}", list), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", List::<u8>::new()), "[]");
+    }
+
+    #[test]
+    fn vec_round_trips() {
+        let empty: List<i32> = Vec::new().into();
+        assert!(empty.is_empty());
+        assert_eq!(empty.to_vec(), Vec::<i32>::new());
+
+        let one = List::from(vec![String::from("only")]);
+        assert_eq!(one.to_vec(), ["only"]);
+
+        let list: List<u32> = (0..5).collect();
+        assert_eq!(list.head(), Some(&0));
+        assert_eq!(list.to_vec(), [0, 1, 2, 3, 4]);
+        let mut seen = Vec::new();
+        for item in &list {
+            seen.push(*item);
+        }
+        assert_eq!(seen, [0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn large_round_trip() {
+        let input: Vec<u32> = (0..100_000).collect();
+        let list: List<u32> = input.iter().copied().collect();
+        assert_eq!(list.len(), 100_000);
+        assert_eq!(list.to_vec(), input);
+    }
+
+    #[test]
+    fn long_list_drops_on_a_small_stack() {
+        let handle = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(|| {
+                let list: List<u64> = (0..200_000).collect();
+                assert_eq!(list.len(), 200_000);
+                drop(list);
+            })
+            .unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn dropping_one_list_keeps_shared_tail() {
+        let shared: List<i32> = (10..15).collect();
+        let a = shared.clone().push_front(1).push_front(0);
+        let b = shared.clone().push_front(2);
+        drop(shared);
+        assert_eq!(a.tail().unwrap().tail().unwrap().strong_count(), 2);
+        drop(a);
+        assert_eq!(b.tail().unwrap().strong_count(), 1);
+        assert_eq!(b.to_vec(), [2, 10, 11, 12, 13, 14]);
+    }
+
+    #[test]
+    fn display_formats() {
+        let list = List::from(vec![5, 10]);
+        assert_eq!(list.to_string(), "(5, (10, Nil))");
+        assert_eq!(format!("{:#}", list), "5 -> 10 -> Nil");
+        assert_eq!(list.fmt_flat(), "5 -> 10 -> Nil");
+        let empty = List::<i32>::new();
+        assert_eq!(empty.to_string(), "Nil");
+        assert_eq!(empty.fmt_flat(), "Nil");
+
+        let long: List<u32> = (0..100_000).collect();
+        let nested = long.to_string();
+        assert!(nested.starts_with("(0, (1, "));
+        assert!(nested.ends_with(&format!("(99999, Nil{}", ")".repeat(100_000))));
+    }
+
+    #[test]
+    fn equality_ignores_sharing() {
+        let shared = List::from(vec![2, 3]);
+        let a = shared.clone().push_front(1);
+        let b = List::from(vec![1, 2, 3]);
+        assert_eq!(a, b);
+        assert_eq!(a, a.clone());
+        assert_ne!(a, shared);
+        assert_ne!(shared, a);
+        assert_ne!(b, List::from(vec![1, 2, 4]));
+        assert_eq!(List::<i32>::new(), List::new());
+
+        let long_a: List<u32> = (0..100_000).collect();
+        let long_b: List<u32> = (0..100_000).collect();
+        assert_eq!(long_a, long_b);
+    }
+
+    #[test]
+    fn map_and_fold() {
+        let list = List::from(vec![1, 2, 3]);
+        let strings = list.map(|n| n.to_string());
+        assert_eq!(strings.to_vec(), ["1", "2", "3"]);
+        assert_eq!(list.fold(0, |sum, n| sum + n), 6);
+        assert_eq!(List::<i32>::new().map(|n| n * 2), List::new());
+        let long: List<u64> = (0..100_000).collect();
+        assert_eq!(long.map(|n| n * 2).fold(0, |sum, n| sum + n), 9_999_900_000);
+    }
+
+    #[test]
+    fn filter_leaves_source_untouched() {
+        let list = List::from(vec![1, 2, 3, 4, 5, 6]);
+        let evens = list.filter(|n| n % 2 == 0);
+        assert_eq!(evens.to_vec(), [2, 4, 6]);
+        assert_eq!(list.to_vec(), [1, 2, 3, 4, 5, 6]);
+        // [6] comes after the last rejected element, so it is shared
+        let six = list.iter().position(|n| *n == 6).unwrap();
+        let mut tail = &list;
+        for _ in 0..six {
+            tail = tail.tail().unwrap();
+        }
+        assert_eq!(tail.strong_count(), 2);
+        drop(evens);
+        assert_eq!(tail.strong_count(), 1);
+    }
+
+    #[test]
+    fn filter_all_or_nothing() {
+        let list = List::from(vec![1, 2, 3]);
+        let all = list.filter(|_| true);
+        assert_eq!(all, list);
+        assert_eq!(list.strong_count(), 2);
+        let none = list.filter(|_| false);
+        assert!(none.is_empty());
+        assert_eq!(list.strong_count(), 2);
+        assert_eq!(list.tail().unwrap().strong_count(), 1);
+    }
+
+    #[test]
+    fn rev_copies() {
+        let list = List::from(vec![1, 2, 3]);
+        assert_eq!(list.rev().to_vec(), [3, 2, 1]);
+        assert_eq!(list.to_vec(), [1, 2, 3]);
+        assert_eq!(list.strong_count(), 1);
+        assert!(List::<u8>::new().rev().is_empty());
+    }
+
+    /// Ordered by `key` alone, so `index` shows whether a sort was stable.
+    #[derive(Debug, Clone)]
+    struct Keyed {
+        key: u8,
+        index: usize,
+    }
+
+    impl PartialEq for Keyed {
+        fn eq(&self, other: &Keyed) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Keyed {}
+
+    impl PartialOrd for Keyed {
+        fn partial_cmp(&self, other: &Keyed) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Keyed {
+        fn cmp(&self, other: &Keyed) -> std::cmp::Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    fn keyed(pairs: &[(u8, usize)]) -> List<Keyed> {
+        pairs
+            .iter()
+            .map(|&(key, index)| Keyed { key, index })
+            .collect()
+    }
+
+    fn pairs(list: &List<Keyed>) -> Vec<(u8, usize)> {
+        list.iter().map(|k| (k.key, k.index)).collect()
+    }
+
+    #[test]
+    fn merge_prefers_a_on_ties() {
+        let a = keyed(&[(1, 0), (3, 1), (3, 2)]);
+        let b = keyed(&[(1, 10), (2, 11), (3, 12), (4, 13)]);
+        let merged = List::merge_sorted(&a, &b);
+        assert_eq!(
+            pairs(&merged),
+            [(1, 0), (1, 10), (2, 11), (3, 1), (3, 2), (3, 12), (4, 13)]
+        );
+        assert_eq!(List::merge_sorted(&a, &List::new()), a);
+        assert_eq!(List::merge_sorted(&List::new(), &b), b);
+    }
+
+    #[test]
+    fn sort_is_stable() {
+        let input = [2, 1, 2, 0, 1, 2, 0];
+        let list: List<Keyed> = input
+            .iter()
+            .enumerate()
+            .map(|(index, &key)| Keyed { key, index })
+            .collect();
+        assert_eq!(
+            pairs(&list.sort()),
+            [(0, 3), (0, 6), (1, 1), (1, 4), (2, 0), (2, 2), (2, 5)]
+        );
+        assert!(List::<u8>::new().sort().is_empty());
+        assert_eq!(List::from(vec![7]).sort().to_vec(), [7]);
+    }
+
+    #[test]
+    fn lookups() {
+        let list = List::from(vec![4, 8, 15, 16, 23, 42]);
+        assert_eq!(list.nth(0), Some(&4));
+        assert_eq!(list.nth(5), Some(&42));
+        assert_eq!(list.nth(6), None);
+        assert_eq!(List::<i32>::new().nth(0), None);
+        assert!(list.contains(&23));
+        assert!(!list.contains(&7));
+        assert_eq!(list.find(|&x| x > 10), Some(&15));
+        assert_eq!(list.find(|&x| x > 100), None);
+        assert_eq!(list.position(|&x| x % 2 == 1), Some(2));
+        assert_eq!(list.position(|&x| x < 0), None);
+        assert_eq!(list.last(), Some(&42));
+        assert_eq!(List::<i32>::new().last(), None);
+    }
+
+    #[test]
+    fn split_at_shares_the_back_half() {
+        let list = List::from(vec![1, 2, 3, 4, 5]);
+        let rest = list.tail().unwrap().tail().unwrap();
+        assert_eq!(rest.strong_count(), 1);
+        let (front, back) = list.split_at(2);
+        assert_eq!(front.to_vec(), [1, 2]);
+        assert_eq!(back.to_vec(), [3, 4, 5]);
+        // `back` is a second handle to the node holding 3
+        assert_eq!(rest.strong_count(), 2);
+        assert_eq!(list.strong_count(), 1);
+        assert_eq!(front.strong_count(), 1);
+    }
+
+    #[test]
+    fn split_at_the_ends() {
+        let list = List::from(vec![1, 2, 3]);
+        let (front, back) = list.split_at(0);
+        assert!(front.is_empty());
+        assert_eq!(back, list);
+        assert_eq!(list.strong_count(), 2);
+        drop(back);
+
+        for n in [3, 10] {
+            let (front, back) = list.split_at(n);
+            assert_eq!(front, list);
+            assert!(back.is_empty());
+            assert_eq!(list.strong_count(), 1);
+        }
+    }
+
+    fn round_trip<T: fmt::Display + FromStr>(list: &List<T>) -> List<T>
+    where
+        T::Err: fmt::Debug,
+    {
+        let mut bytes = Vec::new();
+        list.write_to(&mut bytes).unwrap();
+        List::read_from(io::Cursor::new(bytes)).unwrap()
+    }
+
+    #[test]
+    fn integers_round_trip() {
+        let list = List::from(vec![5, -10, 0, i64::MAX]);
+        let mut bytes = Vec::new();
+        list.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes, b"5\n-10\n0\n9223372036854775807\n");
+        assert_eq!(round_trip(&list), list);
+        assert!(round_trip(&List::<i64>::new()).is_empty());
+    }
+
+    #[test]
+    fn strings_round_trip() {
+        let list: List<String> = ["hello", "", "  spaced  ", "Nil", "(1, 2)"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(round_trip(&list), list);
+    }
+
+    #[test]
+    fn malformed_line_is_reported() {
+        let input = "1\n2\nthree\n4\n";
+        match List::<i32>::read_from(input.as_bytes()) {
+            Err(ReadError::Parse { line, source }) => {
+                assert_eq!(line, 3);
+                assert_eq!(source, "three".parse::<i32>().unwrap_err());
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+        let err = List::<u8>::read_from("7\n300\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: number too large to fit in target type"
+        );
+    }
+
+    #[test]
+    fn sorts_50k_random_integers() {
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let input: Vec<u32> = (0..50_000)
+            .map(|_| {
+                state ^= state >> 12;
+                state ^= state << 25;
+                state ^= state >> 27;
+                (state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 40) as u32
+            })
+            .collect();
+        let sorted = List::from(input.clone()).sort().to_vec();
+        assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+        let mut expected = input;
+        expected.sort_unstable();
+        assert_eq!(sorted, expected);
+    }
+}
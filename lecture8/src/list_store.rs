@@ -0,0 +1,27 @@
+// Keeps a List in a file between runs, one element per line. Each run
+// pushes the next run number onto the front and saves the list again.
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+
+use lecture8::list::List;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let path = env::args().nth(1).unwrap_or_else(|| "list.txt".to_string());
+
+    let list: List<u32> = match File::open(&path) {
+        Ok(file) => List::read_from(BufReader::new(file))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => List::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let run = list.head().map_or(1, |last| last + 1);
+    let list = list.push_front(run);
+    println!("run {}: {}", run, list);
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+    list.write_to(&mut writer)?;
+    // dropping a `BufWriter` would ignore any error from the final write
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! The mutable cons list from `memory_leak.rs`, whose tails sit in a
+//! `RefCell` so they can be relinked after construction. That makes
+//! reference cycles possible, so this module can also detect them.
+
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub enum List {
+    Cons(i32, RefCell<Rc<List>>),
+    Nil,
+}
+
+use List::{Cons, Nil};
+
+thread_local! {
+    // Bumped by every `link_tail`. `Rc` keeps lists on one thread, so a
+    // per-thread count sees every relink a list can be affected by.
+    static RELINKS: Cell<u64> = const { Cell::new(0) };
+}
+
+fn relink_generation() -> u64 {
+    RELINKS.with(Cell::get)
+}
+
+/// Returned by `List::safe_len` when following the tails loops forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleError;
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "list contains a cycle")
+    }
+}
+
+impl std::error::Error for CycleError {}
+
+impl List {
+    pub fn tail(&self) -> Option<&RefCell<Rc<List>>> {
+        match self {
+            Cons(_, item) => Some(item),
+            Nil => None,
+        }
+    }
+
+    /// Points this node's tail at `other`. Returns false, changing nothing,
+    /// if this is `Nil`, which has no tail.
+    pub fn link_tail(&self, other: &Rc<List>) -> bool {
+        match self.tail() {
+            Some(link) => {
+                *link.borrow_mut() = Rc::clone(other);
+                RELINKS.with(|n| n.set(n.get() + 1));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The node after this one, or `None` at `Nil`.
+    fn next(self: &Rc<List>) -> Option<Rc<List>> {
+        self.tail().map(|link| Rc::clone(&link.borrow()))
+    }
+
+    /// Whether following the tails from here ever comes back around, found
+    /// with Floyd's tortoise and hare: one pointer moves one step at a
+    /// time and the other two, and they only meet if there is a loop.
+    pub fn has_cycle(self: &Rc<List>) -> bool {
+        let mut slow = Rc::clone(self);
+        let mut fast = Rc::clone(self);
+        loop {
+            fast = match fast.next().and_then(|n| n.next()) {
+                Some(node) => node,
+                None => return false,
+            };
+            slow = slow.next().unwrap();
+            if Rc::ptr_eq(&slow, &fast) {
+                return true;
+            }
+        }
+    }
+
+    /// The number of `Cons` nodes, or an error instead of looping forever
+    /// if the list is cyclic.
+    pub fn safe_len(self: &Rc<List>) -> Result<usize, CycleError> {
+        if self.has_cycle() {
+            return Err(CycleError);
+        }
+        let mut len = 0;
+        let mut node = Rc::clone(self);
+        while let Some(next) = node.next() {
+            len += 1;
+            node = next;
+        }
+        Ok(len)
+    }
+}
+
+/// A list head that remembers its length after the first walk. The cached
+/// length is stored with the thread's `link_tail` count at the time, so
+/// any relink since, of any node, makes it stale. A tail replaced by
+/// writing to its `RefCell` directly isn't counted; call `invalidate`
+/// after doing that.
+#[derive(Debug)]
+pub struct CachedList {
+    head: Rc<List>,
+    len: Cell<Option<(usize, u64)>>,
+    traversals: Cell<usize>,
+}
+
+impl CachedList {
+    pub fn new(head: Rc<List>) -> CachedList {
+        CachedList {
+            head,
+            len: Cell::new(None),
+            traversals: Cell::new(0),
+        }
+    }
+
+    pub fn head(&self) -> &Rc<List> {
+        &self.head
+    }
+
+    /// The length, walking the list only if it isn't cached. A cyclic list
+    /// has no length, so nothing is cached for it.
+    pub fn len_cached(&self) -> Result<usize, CycleError> {
+        if let Some((len, generation)) = self.len.get() {
+            if generation == relink_generation() {
+                return Ok(len);
+            }
+        }
+        self.traversals.set(self.traversals.get() + 1);
+        let len = self.head.safe_len()?;
+        self.len.set(Some((len, relink_generation())));
+        Ok(len)
+    }
+
+    pub fn is_cached(&self) -> bool {
+        matches!(self.len.get(), Some((_, generation)) if generation == relink_generation())
+    }
+
+    /// How many times `len_cached` has had to walk the list.
+    pub fn traversals(&self) -> usize {
+        self.traversals.get()
+    }
+
+    pub fn invalidate(&self) {
+        self.len.set(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cons(value: i32, tail: &Rc<List>) -> Rc<List> {
+        Rc::new(Cons(value, RefCell::new(Rc::clone(tail))))
+    }
+
+    #[test]
+    fn straight_list() {
+        let nil = Rc::new(Nil);
+        assert!(!nil.has_cycle());
+        assert_eq!(nil.safe_len(), Ok(0));
+
+        let c = cons(3, &nil);
+        let b = cons(2, &c);
+        let a = cons(1, &b);
+        assert!(!a.has_cycle());
+        assert_eq!(a.safe_len(), Ok(3));
+        assert_eq!(c.safe_len(), Ok(1));
+        assert!(!nil.link_tail(&a));
+    }
+
+    #[test]
+    fn detects_cycle() {
+        let nil = Rc::new(Nil);
+        let c = cons(3, &nil);
+        let b = cons(2, &c);
+        let a = cons(1, &b);
+        // the last node back to the head
+        assert!(c.link_tail(&a));
+        assert!(a.has_cycle());
+        assert!(b.has_cycle());
+        assert_eq!(a.safe_len(), Err(CycleError));
+        // break the cycle again so the nodes are freed
+        c.link_tail(&nil);
+        assert_eq!(a.safe_len(), Ok(3));
+    }
+
+    #[test]
+    fn length_is_cached_after_one_walk() {
+        let nil = Rc::new(Nil);
+        let list = CachedList::new(cons(1, &cons(2, &cons(3, &nil))));
+        assert!(!list.is_cached());
+        assert_eq!(list.len_cached(), Ok(3));
+        assert!(list.is_cached());
+        assert_eq!(list.traversals(), 1);
+        assert_eq!(list.len_cached(), Ok(3));
+        assert_eq!(list.traversals(), 1);
+    }
+
+    #[test]
+    fn relinking_invalidates() {
+        let nil = Rc::new(Nil);
+        let c = cons(3, &nil);
+        let list = CachedList::new(cons(1, &cons(2, &c)));
+        assert_eq!(list.len_cached(), Ok(3));
+
+        let longer = cons(4, &cons(5, &nil));
+        assert!(c.link_tail(&longer));
+        assert!(!list.is_cached());
+        assert_eq!(list.len_cached(), Ok(5));
+        assert_eq!(list.traversals(), 2);
+
+        // `Nil` has no tail, so nothing changes and the cache stays
+        assert!(!nil.link_tail(&longer));
+        assert!(list.is_cached());
+
+        // a cycle is reported every time and never cached
+        c.link_tail(list.head());
+        assert_eq!(list.len_cached(), Err(CycleError));
+        assert_eq!(list.len_cached(), Err(CycleError));
+        assert!(!list.is_cached());
+        assert_eq!(list.traversals(), 4);
+        c.link_tail(&nil);
+        assert_eq!(list.len_cached(), Ok(3));
+    }
+
+    #[test]
+    fn relinking_any_node_invalidates() {
+        let nil = Rc::new(Nil);
+        let b = cons(2, &nil);
+        let list = CachedList::new(cons(1, &b));
+        assert_eq!(list.len_cached(), Ok(2));
+        b.link_tail(&cons(3, &nil));
+        assert!(!list.is_cached());
+        assert_eq!(list.len_cached(), Ok(3));
+
+        // only writes that bypass `link_tail` need an explicit invalidate
+        *b.tail().unwrap().borrow_mut() = Rc::clone(&nil);
+        list.invalidate();
+        assert_eq!(list.len_cached(), Ok(2));
+    }
+
+    #[test]
+    fn detects_memory_leak_example_cycle() {
+        // a -> b -> a, as in memory_leak.rs
+        let a = cons(5, &Rc::new(Nil));
+        let b = cons(10, &a);
+        a.link_tail(&b);
+        assert!(a.has_cycle());
+        let self_loop = cons(1, &Rc::new(Nil));
+        self_loop.link_tail(&self_loop);
+        assert_eq!(self_loop.safe_len(), Err(CycleError));
+        a.link_tail(&Rc::new(Nil));
+        self_loop.link_tail(&Rc::new(Nil));
+        assert_eq!(b.safe_len(), Ok(2));
+    }
+}
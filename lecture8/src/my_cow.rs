@@ -0,0 +1,173 @@
+//! A clone-on-write pointer, built the same way as `std::borrow::Cow`.
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::ops::Deref;
+
+/// Either borrowed data or an owned copy of it, made only when mutation
+/// is needed.
+pub enum MyCow<'a, B: ToOwned + ?Sized> {
+    Borrowed(&'a B),
+    Owned(B::Owned),
+}
+
+use MyCow::{Borrowed, Owned};
+
+impl<'a, B: ToOwned + ?Sized> MyCow<'a, B> {
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, Borrowed(_))
+    }
+
+    /// Mutable access to owned data, cloning the borrowed data first if
+    /// this is still `Borrowed`.
+    pub fn to_mut(&mut self) -> &mut B::Owned {
+        if let Borrowed(borrowed) = *self {
+            *self = Owned(borrowed.to_owned());
+        }
+        match self {
+            Owned(owned) => owned,
+            Borrowed(_) => unreachable!(),
+        }
+    }
+
+    /// The owned data, cloning it if this is `Borrowed`.
+    pub fn into_owned(self) -> B::Owned {
+        match self {
+            Borrowed(borrowed) => borrowed.to_owned(),
+            Owned(owned) => owned,
+        }
+    }
+}
+
+impl<B: ToOwned + ?Sized> Deref for MyCow<'_, B> {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        match self {
+            Borrowed(borrowed) => borrowed,
+            Owned(owned) => owned.borrow(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for MyCow<'a, str> {
+    fn from(s: &'a str) -> MyCow<'a, str> {
+        Borrowed(s)
+    }
+}
+
+impl From<String> for MyCow<'_, str> {
+    fn from(s: String) -> Self {
+        Owned(s)
+    }
+}
+
+impl<B: ToOwned + ?Sized + fmt::Display> fmt::Display for MyCow<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<B: ToOwned + ?Sized + fmt::Debug> fmt::Debug for MyCow<'_, B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Compares the data, whether each side is borrowed or owned.
+impl<'a, 'b, B, C> PartialEq<MyCow<'b, C>> for MyCow<'a, B>
+where
+    B: ToOwned + ?Sized + PartialEq<C>,
+    C: ToOwned + ?Sized,
+{
+    fn eq(&self, other: &MyCow<'b, C>) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<str> for MyCow<'_, str> {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for MyCow<'_, str> {
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Not `Clone`, so its only copies come from `to_owned`, which it counts.
+    #[derive(Debug, PartialEq)]
+    struct Counted {
+        value: i32,
+        clones: Rc<Cell<usize>>,
+    }
+
+    impl ToOwned for Counted {
+        type Owned = Counted;
+
+        fn to_owned(&self) -> Counted {
+            self.clones.set(self.clones.get() + 1);
+            Counted {
+                value: self.value,
+                clones: Rc::clone(&self.clones),
+            }
+        }
+    }
+
+    #[test]
+    fn to_mut_clones_once() {
+        let original = Counted {
+            value: 1,
+            clones: Rc::new(Cell::new(0)),
+        };
+        let mut cow = MyCow::Borrowed(&original);
+        assert!(cow.is_borrowed());
+        cow.to_mut().value += 1;
+        assert!(!cow.is_borrowed());
+        cow.to_mut().value += 1;
+        cow.to_mut().value += 1;
+        assert_eq!(original.clones.get(), 1);
+        assert_eq!(cow.value, 4);
+        assert_eq!(original.value, 1);
+        assert_eq!(cow.into_owned().value, 4);
+        assert_eq!(original.clones.get(), 1);
+    }
+
+    #[test]
+    fn into_owned_only_clones_borrowed() {
+        let original = Counted {
+            value: 1,
+            clones: Rc::new(Cell::new(0)),
+        };
+        let owned = MyCow::<Counted>::Owned(original.to_owned()).into_owned();
+        assert_eq!(original.clones.get(), 1);
+        assert_eq!(MyCow::Borrowed(&original).into_owned(), owned);
+        assert_eq!(original.clones.get(), 2);
+    }
+
+    #[test]
+    fn strings() {
+        let borrowed: MyCow<str> = "hello".into();
+        let owned: MyCow<str> = String::from("hello").into();
+        assert!(borrowed.is_borrowed());
+        assert!(!owned.is_borrowed());
+        assert!(borrowed == owned);
+        assert!(borrowed == "hello");
+        assert_eq!(owned.to_string(), "hello");
+        assert_eq!(format!("{:>6}|{:?}", borrowed, owned), " hello|\"hello\"");
+        assert_eq!(borrowed.len(), 5);
+
+        let mut edited = borrowed;
+        edited.to_mut().push('!');
+        assert!(edited != owned);
+        assert_eq!(edited.into_owned(), "hello!");
+    }
+}
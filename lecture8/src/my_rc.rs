@@ -0,0 +1,228 @@
+//! A single-threaded reference-counted pointer, built the same way as
+//! `std::rc::Rc`.
+//!
+//! Like `Rc`, `MyRc` is neither `Send` nor `Sync`, because its counts are
+//! updated without synchronization:
+//!
+//! ```compile_fail
+//! fn assert_send<T: Send>() {}
+//! assert_send::<lecture8::my_rc::MyRc<i32>>();
+//! ```
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+/// The heap allocation shared by every `MyRc` and `MyWeak` of a value.
+///
+/// All the strong pointers together hold one extra weak reference, so the
+/// allocation is freed exactly when `weak` reaches zero, whichever kind of
+/// pointer goes last.
+struct RcBox<T> {
+    strong: Cell<usize>,
+    weak: Cell<usize>,
+    value: ManuallyDrop<T>,
+}
+
+pub struct MyRc<T> {
+    ptr: NonNull<RcBox<T>>,
+    // Tells the compiler we own an `RcBox<T>`, for drop checking.
+    _marker: PhantomData<RcBox<T>>,
+}
+
+/// A non-owning pointer from `MyRc::downgrade`; it keeps the allocation but
+/// not the value alive.
+pub struct MyWeak<T> {
+    ptr: NonNull<RcBox<T>>,
+}
+
+impl<T> MyRc<T> {
+    pub fn new(value: T) -> MyRc<T> {
+        let boxed = Box::new(RcBox {
+            strong: Cell::new(1),
+            weak: Cell::new(1),
+            value: ManuallyDrop::new(value),
+        });
+        MyRc {
+            ptr: NonNull::from(Box::leak(boxed)),
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &RcBox<T> {
+        // Safety: the allocation lives as long as any strong pointer does.
+        unsafe { self.ptr.as_ref() }
+    }
+
+    pub fn strong_count(this: &MyRc<T>) -> usize {
+        this.inner().strong.get()
+    }
+
+    /// The number of `MyWeak` pointers, not counting the one shared by the
+    /// strong pointers.
+    pub fn weak_count(this: &MyRc<T>) -> usize {
+        this.inner().weak.get() - 1
+    }
+
+    pub fn downgrade(this: &MyRc<T>) -> MyWeak<T> {
+        let inner = this.inner();
+        inner.weak.set(inner.weak.get() + 1);
+        MyWeak { ptr: this.ptr }
+    }
+}
+
+impl<T> Clone for MyRc<T> {
+    fn clone(&self) -> MyRc<T> {
+        let inner = self.inner();
+        inner.strong.set(inner.strong.get() + 1);
+        MyRc {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for MyRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<T> Drop for MyRc<T> {
+    fn drop(&mut self) {
+        let ptr = self.ptr.as_ptr();
+        // Safety: we hold a strong reference, so the value hasn't been
+        // dropped and the allocation hasn't been freed yet.
+        unsafe {
+            let strong = (*ptr).strong.get() - 1;
+            (*ptr).strong.set(strong);
+            if strong == 0 {
+                ManuallyDrop::drop(&mut (*ptr).value);
+                release_weak(self.ptr);
+            }
+        }
+    }
+}
+
+impl<T> MyWeak<T> {
+    /// A new strong pointer, or `None` once the value has been dropped.
+    pub fn upgrade(&self) -> Option<MyRc<T>> {
+        // Safety: a weak pointer keeps the allocation, though not the value,
+        // alive.
+        let inner = unsafe { self.ptr.as_ref() };
+        if inner.strong.get() == 0 {
+            return None;
+        }
+        inner.strong.set(inner.strong.get() + 1);
+        Some(MyRc {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn strong_count(&self) -> usize {
+        unsafe { self.ptr.as_ref() }.strong.get()
+    }
+}
+
+impl<T> Clone for MyWeak<T> {
+    fn clone(&self) -> MyWeak<T> {
+        let inner = unsafe { self.ptr.as_ref() };
+        inner.weak.set(inner.weak.get() + 1);
+        MyWeak { ptr: self.ptr }
+    }
+}
+
+impl<T> Drop for MyWeak<T> {
+    fn drop(&mut self) {
+        // Safety: this weak reference is still counted, so the allocation
+        // is live.
+        unsafe { release_weak(self.ptr) }
+    }
+}
+
+/// Gives up one weak reference, freeing the allocation if it was the last.
+/// The value must already have been dropped if this could be the last one.
+///
+/// Safety: `ptr` must point to a live `RcBox` and the caller must own one of
+/// its weak references.
+unsafe fn release_weak<T>(ptr: NonNull<RcBox<T>>) {
+    let weak = ptr.as_ref().weak.get() - 1;
+    ptr.as_ref().weak.set(weak);
+    if weak == 0 {
+        // `value` is ManuallyDrop, so this only frees the memory.
+        drop(Box::from_raw(ptr.as_ptr()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    /// Counts how many times it has been dropped.
+    struct DropCounter(Rc<Cell<usize>>);
+
+    impl Drop for DropCounter {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    fn counts<T>(rc: &MyRc<T>) -> (usize, usize) {
+        (MyRc::strong_count(rc), MyRc::weak_count(rc))
+    }
+
+    #[test]
+    fn counts_follow_rc_example() {
+        let rc = MyRc::new(5);
+        assert_eq!(counts(&rc), (1, 0));
+
+        let strong_ptr1 = MyRc::clone(&rc);
+        assert_eq!(counts(&rc), (2, 0));
+
+        let weak_ptr = MyRc::downgrade(&rc);
+        assert_eq!(counts(&rc), (2, 1));
+
+        let strong_ptr2 = weak_ptr.upgrade().unwrap();
+        assert_eq!(counts(&rc), (3, 1));
+        assert_eq!(*strong_ptr2, 5);
+
+        drop(strong_ptr1);
+        assert_eq!(counts(&rc), (2, 1));
+        let weak_ptr2 = weak_ptr.clone();
+        assert_eq!(counts(&rc), (2, 2));
+        drop(weak_ptr2);
+        drop(strong_ptr2);
+        assert_eq!(counts(&rc), (1, 1));
+    }
+
+    #[test]
+    fn value_dropped_with_last_strong() {
+        let drops = Rc::new(Cell::new(0));
+        let a = MyRc::new(DropCounter(Rc::clone(&drops)));
+        let b = a.clone();
+        drop(a);
+        assert_eq!(drops.get(), 0);
+        drop(b);
+        assert_eq!(drops.get(), 1);
+    }
+
+    #[test]
+    fn weak_outlives_strongs() {
+        let drops = Rc::new(Cell::new(0));
+        let rc = MyRc::new(DropCounter(Rc::clone(&drops)));
+        let weak = MyRc::downgrade(&rc);
+        drop(rc);
+        // The value is gone but the counts are still readable.
+        assert_eq!(drops.get(), 1);
+        assert_eq!(weak.strong_count(), 0);
+        assert!(weak.upgrade().is_none());
+        drop(weak);
+        assert_eq!(drops.get(), 1);
+    }
+}
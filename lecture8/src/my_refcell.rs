@@ -0,0 +1,324 @@
+//! A cell with borrow rules checked at run time, built the same way as
+//! `std::cell::RefCell`.
+
+use std::cell::{Cell, UnsafeCell};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::ptr::NonNull;
+
+/// Why a borrow was refused: the borrow state at the time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorrowError {
+    /// A `MyRefMut` is alive.
+    MutablyBorrowed,
+    /// `count` `MyRef`s are alive, so a mutable borrow isn't allowed.
+    SharedBorrowed { count: usize },
+}
+
+impl fmt::Display for BorrowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BorrowError::MutablyBorrowed => write!(f, "already mutably borrowed"),
+            BorrowError::SharedBorrowed { count } => {
+                write!(f, "already borrowed by {} shared borrow(s)", count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BorrowError {}
+
+// Borrow state: the number of live `MyRef`s, or `WRITING` while a
+// `MyRefMut` is alive.
+const UNUSED: isize = 0;
+const WRITING: isize = -1;
+
+pub struct MyRefCell<T> {
+    state: Cell<isize>,
+    value: UnsafeCell<T>,
+}
+
+impl<T> MyRefCell<T> {
+    pub fn new(value: T) -> MyRefCell<T> {
+        MyRefCell {
+            state: Cell::new(UNUSED),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    /// Panics with the borrow state if the value is mutably borrowed.
+    pub fn borrow(&self) -> MyRef<'_, T> {
+        match self.try_borrow() {
+            Ok(r) => r,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Panics with the borrow state if the value is borrowed at all.
+    pub fn borrow_mut(&self) -> MyRefMut<'_, T> {
+        match self.try_borrow_mut() {
+            Ok(r) => r,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Replaces the value with `f` applied to it, returning the old value.
+    /// Panics with the borrow state if the value is borrowed.
+    pub fn replace_with(&self, f: impl FnOnce(&mut T) -> T) -> T {
+        match self.try_replace_with(f) {
+            Ok(old) => old,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Like `replace_with`, but fails instead of panicking if the value is
+    /// borrowed, without calling `f`.
+    pub fn try_replace_with(&self, f: impl FnOnce(&mut T) -> T) -> Result<T, BorrowError> {
+        let mut guard = self.try_borrow_mut()?;
+        let new = f(&mut guard);
+        Ok(std::mem::replace(&mut *guard, new))
+    }
+
+    /// Takes the value, leaving `T::default()`. Panics with the borrow state
+    /// if the value is borrowed.
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.replace_with(|_| T::default())
+    }
+
+    pub fn try_take(&self) -> Result<T, BorrowError>
+    where
+        T: Default,
+    {
+        self.try_replace_with(|_| T::default())
+    }
+
+    pub fn try_borrow(&self) -> Result<MyRef<'_, T>, BorrowError> {
+        let state = self.state.get();
+        if state == WRITING {
+            return Err(BorrowError::MutablyBorrowed);
+        }
+        self.state.set(state + 1);
+        Ok(MyRef {
+            value: NonNull::new(self.value.get()).unwrap(),
+            state: &self.state,
+            _marker: PhantomData,
+        })
+    }
+
+    pub fn try_borrow_mut(&self) -> Result<MyRefMut<'_, T>, BorrowError> {
+        match self.state.get() {
+            UNUSED => {}
+            WRITING => return Err(BorrowError::MutablyBorrowed),
+            count => {
+                return Err(BorrowError::SharedBorrowed {
+                    count: count as usize,
+                })
+            }
+        }
+        self.state.set(WRITING);
+        Ok(MyRefMut {
+            value: NonNull::new(self.value.get()).unwrap(),
+            state: &self.state,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// A shared borrow from `MyRefCell::borrow`, released when dropped.
+///
+/// Like std's `Ref`, it holds a raw pointer rather than a `&'b T`. A
+/// reference field passed into a function is assumed to stay valid until
+/// that function returns, even if the guard is dropped inside it and the
+/// cell is then mutably borrowed.
+pub struct MyRef<'b, T> {
+    value: NonNull<T>,
+    state: &'b Cell<isize>,
+    _marker: PhantomData<&'b T>,
+}
+
+impl<T> Deref for MyRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: there is no mutable borrow, and the state stops one from
+        // being made until this `MyRef` is dropped.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T> Drop for MyRef<'_, T> {
+    fn drop(&mut self) {
+        self.state.set(self.state.get() - 1);
+    }
+}
+
+/// A mutable borrow from `MyRefCell::borrow_mut`, released when dropped.
+/// It holds a raw pointer for the same reason as `MyRef`.
+pub struct MyRefMut<'b, T> {
+    value: NonNull<T>,
+    state: &'b Cell<isize>,
+    _marker: PhantomData<&'b mut T>,
+}
+
+impl<T> Deref for MyRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: there are no other borrows, and the state stops any from
+        // being made until this `MyRefMut` is dropped.
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T> DerefMut for MyRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: as in `deref`.
+        unsafe { self.value.as_mut() }
+    }
+}
+
+impl<T> Drop for MyRefMut<'_, T> {
+    fn drop(&mut self) {
+        self.state.set(UNUSED);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_refcell_example() {
+        let rc = MyRefCell::new(5);
+        assert_eq!(*rc.borrow(), 5);
+        *rc.borrow_mut() = 6;
+        assert_eq!(*rc.borrow(), 6);
+        assert_eq!(rc.into_inner(), 6);
+    }
+
+    #[test]
+    fn nested_shared_borrows() {
+        let cell = MyRefCell::new(vec![1, 2]);
+        let a = cell.borrow();
+        let b = cell.borrow();
+        assert_eq!(a.len() + b.len(), 4);
+        assert_eq!(
+            cell.try_borrow_mut().err(),
+            Some(BorrowError::SharedBorrowed { count: 2 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed by 1 shared borrow(s)")]
+    fn mut_while_shared_panics() {
+        let cell = MyRefCell::new(1);
+        let _r = cell.borrow();
+        let _w = cell.borrow_mut();
+    }
+
+    #[test]
+    #[should_panic(expected = "already mutably borrowed")]
+    fn shared_while_mut_panics() {
+        let cell = MyRefCell::new(1);
+        let _w = cell.borrow_mut();
+        let _r = cell.borrow();
+    }
+
+    #[test]
+    fn try_borrow_mut_while_borrowed() {
+        let cell = MyRefCell::new(1);
+        let w = cell.borrow_mut();
+        assert_eq!(
+            cell.try_borrow_mut().err(),
+            Some(BorrowError::MutablyBorrowed)
+        );
+        assert!(cell.try_borrow().is_err());
+        drop(w);
+        assert!(cell.try_borrow().is_ok());
+    }
+
+    #[test]
+    fn replace_with_and_take() {
+        let cell = MyRefCell::new(vec![1]);
+        let old = cell.replace_with(|v| {
+            v.push(2);
+            vec![3]
+        });
+        assert_eq!(old, vec![1, 2]);
+        assert_eq!(cell.take(), vec![3]);
+        assert_eq!(cell.try_take(), Ok(vec![]));
+    }
+
+    #[test]
+    fn try_forms_respect_borrows() {
+        let cell = MyRefCell::new(5);
+        let r = cell.borrow();
+        let mut called = false;
+        let result = cell.try_replace_with(|_| {
+            called = true;
+            6
+        });
+        assert_eq!(result, Err(BorrowError::SharedBorrowed { count: 1 }));
+        assert!(!called);
+        assert_eq!(
+            cell.try_take(),
+            Err(BorrowError::SharedBorrowed { count: 1 })
+        );
+        drop(r);
+        assert_eq!(cell.try_replace_with(|n| *n + 1), Ok(5));
+        assert_eq!(*cell.borrow(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "already mutably borrowed")]
+    fn take_while_mut_borrowed_panics() {
+        let cell = MyRefCell::new(String::from("x"));
+        let _w = cell.borrow_mut();
+        cell.take();
+    }
+
+    #[test]
+    fn guards_release_at_end_of_scope() {
+        let cell = MyRefCell::new(String::from("a"));
+        {
+            let r1 = cell.borrow();
+            let r2 = cell.borrow();
+            assert_eq!(*r1, *r2);
+        }
+        cell.borrow_mut().push('b');
+        {
+            let mut w = cell.borrow_mut();
+            w.push('c');
+        }
+        assert_eq!(*cell.borrow(), "abc");
+    }
+
+    #[test]
+    fn guard_dropped_inside_callee() {
+        fn release_shared(cell: &MyRefCell<String>, r: MyRef<'_, String>) {
+            assert_eq!(*r, "a");
+            drop(r);
+            cell.borrow_mut().push('b');
+        }
+
+        fn release_mut(cell: &MyRefCell<String>, mut w: MyRefMut<'_, String>) {
+            w.push('c');
+            drop(w);
+            cell.borrow_mut().push('d');
+            assert_eq!(*cell.borrow(), "abcd");
+        }
+
+        let cell = MyRefCell::new(String::from("a"));
+        release_shared(&cell, cell.borrow());
+        release_mut(&cell, cell.borrow_mut());
+        assert_eq!(cell.into_inner(), "abcd");
+    }
+}
@@ -0,0 +1,121 @@
+//! A pool of byte buffers that are reused rather than freed.
+
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+type Idle<T> = Rc<RefCell<Vec<T>>>;
+
+/// Hands out `PooledBox`es whose buffers come back to the pool when they
+/// are dropped, keeping their capacity for the next `get`.
+pub struct BufferPool {
+    idle: Idle<Vec<u8>>,
+    max_idle: usize,
+}
+
+impl BufferPool {
+    /// A pool keeping at most `max_idle` returned buffers; any beyond that
+    /// are freed.
+    pub fn new(max_idle: usize) -> BufferPool {
+        BufferPool {
+            idle: Rc::new(RefCell::new(Vec::new())),
+            max_idle,
+        }
+    }
+
+    /// An empty buffer, reusing an idle one if there is any.
+    pub fn get(&self) -> PooledBox<Vec<u8>> {
+        let buffer = self.idle.borrow_mut().pop().unwrap_or_default();
+        PooledBox {
+            value: Some(buffer),
+            idle: Rc::clone(&self.idle),
+            max_idle: self.max_idle,
+            reset: Vec::clear,
+        }
+    }
+
+    pub fn idle_count(&self) -> usize {
+        self.idle.borrow().len()
+    }
+}
+
+/// A value on loan from a pool. Dropping it resets the value and gives it
+/// back, unless the pool already has enough idle ones. It holds its own
+/// handle to the pool, so it may outlive the `BufferPool` it came from.
+pub struct PooledBox<T> {
+    // Only `None` while being dropped.
+    value: Option<T>,
+    idle: Idle<T>,
+    max_idle: usize,
+    reset: fn(&mut T),
+}
+
+impl<T> Deref for PooledBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for PooledBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for PooledBox<T> {
+    fn drop(&mut self) {
+        let mut value = self.value.take().unwrap();
+        let mut idle = self.idle.borrow_mut();
+        if idle.len() < self.max_idle {
+            (self.reset)(&mut value);
+            idle.push(value);
+        }
+        // Otherwise `value` is freed here.
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_survives_reuse() {
+        let pool = BufferPool::new(4);
+        let mut buf = pool.get();
+        buf.extend_from_slice(&[1; 1000]);
+        let capacity = buf.capacity();
+        let ptr = buf.as_ptr();
+        drop(buf);
+        assert_eq!(pool.idle_count(), 1);
+
+        let again = pool.get();
+        assert!(again.is_empty());
+        assert_eq!(again.capacity(), capacity);
+        assert_eq!(again.as_ptr(), ptr);
+        assert_eq!(pool.idle_count(), 0);
+    }
+
+    #[test]
+    fn idle_count_is_capped() {
+        let pool = BufferPool::new(2);
+        let bufs: Vec<_> = (0..5).map(|_| pool.get()).collect();
+        assert_eq!(pool.idle_count(), 0);
+        drop(bufs);
+        assert_eq!(pool.idle_count(), 2);
+
+        let none = BufferPool::new(0);
+        drop(none.get());
+        assert_eq!(none.idle_count(), 0);
+    }
+
+    #[test]
+    fn outlives_pool() {
+        let pool = BufferPool::new(1);
+        let mut buf = pool.get();
+        drop(pool);
+        buf.push(1);
+        assert_eq!(*buf, [1]);
+    }
+}
@@ -1,35 +1,21 @@
 use std::rc::Rc;
 
+use lecture8::rc_report::RcStats;
+
 fn main() {
     let mut rc = Rc::new(5); // create a strong pointer
                              // *rc = 6; //Doesn't work
-    println!(
-        "Strong count: {}, weak count: {}",
-        Rc::strong_count(&rc),
-        Rc::weak_count(&rc)
-    );
+    println!("{}", RcStats::of(&rc));
 
     let strong_ptr1 = Rc::clone(&rc); // second strong pointer
-    println!(
-        "Strong count: {}, weak count: {}",
-        Rc::strong_count(&rc),
-        Rc::weak_count(&rc)
-    );
+    println!("{}", RcStats::of(&rc));
 
     let weak_ptr = Rc::downgrade(&rc); // create a weak pointer
-    println!(
-        "Strong count: {}, weak count: {}",
-        Rc::strong_count(&rc),
-        Rc::weak_count(&rc)
-    );
+    println!("{}", RcStats::of(&rc));
 
     // upgrade the weak pointer to be the third strong poointer
     let strong_ptr2 = weak_ptr.upgrade();
-    println!(
-        "Strong count: {}, weak count: {}",
-        Rc::strong_count(&rc),
-        Rc::weak_count(&rc)
-    );
+    println!("{}", RcStats::of(&rc));
 
     // drop a strong pointer
     drop(rc);
@@ -0,0 +1,105 @@
+//! Strong and weak counts for a set of `Rc`s, listing each allocation
+//! once however many of the given handles point at it.
+
+use std::fmt;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RcStats {
+    pub strong: usize,
+    pub weak: usize,
+    /// The address of the value, which identifies the allocation.
+    pub ptr: usize,
+}
+
+impl RcStats {
+    pub fn of<T: ?Sized>(rc: &Rc<T>) -> RcStats {
+        RcStats {
+            strong: Rc::strong_count(rc),
+            weak: Rc::weak_count(rc),
+            ptr: Rc::as_ptr(rc) as *const u8 as usize,
+        }
+    }
+}
+
+impl fmt::Display for RcStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#x}: strong count = {}, weak count = {}",
+            self.ptr, self.strong, self.weak
+        )
+    }
+}
+
+/// The counts for each distinct allocation in `roots`, in the order each
+/// is first seen.
+pub fn report<T: ?Sized>(roots: &[&Rc<T>]) -> Vec<RcStats> {
+    let mut stats: Vec<RcStats> = Vec::new();
+    for rc in roots {
+        let entry = RcStats::of(rc);
+        if !stats.iter().any(|s| s.ptr == entry.ptr) {
+            stats.push(entry);
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list::List;
+
+    #[test]
+    fn shared_allocations_appear_once() {
+        let x = Rc::new(5);
+        let x2 = Rc::clone(&x);
+        let _weak = Rc::downgrade(&x);
+        let y = Rc::new(5);
+        let stats = report(&[&x, &y, &x2]);
+        assert_eq!(stats.len(), 2);
+        assert_eq!((stats[0].strong, stats[0].weak), (2, 1));
+        assert_eq!((stats[1].strong, stats[1].weak), (1, 0));
+        assert_eq!(stats[0], RcStats::of(&x2));
+        assert_ne!(stats[0].ptr, stats[1].ptr);
+        assert!(report::<i32>(&[]).is_empty());
+    }
+
+    #[test]
+    fn unsized_values() {
+        let s: Rc<str> = Rc::from("shared");
+        let t = Rc::clone(&s);
+        let stats = report(&[&s, &t]);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].strong, 2);
+        assert_eq!(stats[0].ptr, s.as_ptr() as usize);
+    }
+
+    #[test]
+    fn display() {
+        let x = Rc::new(());
+        let stats = RcStats::of(&x);
+        assert_eq!(
+            stats.to_string(),
+            format!("{:#x}: strong count = 1, weak count = 0", stats.ptr)
+        );
+    }
+
+    #[test]
+    fn cons_list_sharing() {
+        // as in cons_list.rs
+        let a = List::new().push_front(10).push_front(5);
+        assert_eq!(a.sharing_report(), [(0, 1), (1, 1)]);
+        let b = a.clone().push_front(3);
+        assert_eq!(a.sharing_report(), [(0, 2), (1, 1)]);
+        let c = a.clone().push_front(4);
+        assert_eq!(a.sharing_report(), [(0, 3), (1, 1)]);
+        assert_eq!(b.sharing_report(), [(0, 1), (1, 3), (2, 1)]);
+        assert_eq!(c.sharing_report(), [(0, 1), (1, 3), (2, 1)]);
+        drop(c);
+        assert_eq!(b.sharing_report(), [(0, 1), (1, 2), (2, 1)]);
+        drop(a);
+        assert_eq!(b.sharing_report(), [(0, 1), (1, 1), (2, 1)]);
+        assert!(List::<i32>::new().sharing_report().is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+//! An `Rc` wrapper that records how its strong and weak counts change, to
+//! make the choreography in `rc_example.rs` checkable.
+
+use std::cell::{Cell, RefCell};
+use std::ops::Deref;
+use std::rc::{Rc, Weak};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountOp {
+    New,
+    Clone,
+    Downgrade,
+    Upgrade,
+    /// `upgrade` was called after the value had been dropped.
+    UpgradeFailed,
+    Drop,
+    DropWeak,
+    /// The last strong pointer went away, dropping the value.
+    ValueDropped,
+    /// The last pointer of either kind went away, freeing the allocation.
+    AllocationFreed,
+}
+
+/// One operation with the counts just after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountEvent {
+    pub op: CountOp,
+    pub strong: usize,
+    pub weak: usize,
+}
+
+/// The events of one tracked allocation, shared by all of its pointers.
+#[derive(Debug, Clone, Default)]
+pub struct CountLog {
+    events: Rc<RefCell<Vec<CountEvent>>>,
+    // `Weak::weak_count` reads zero once the value is gone, so the weak
+    // pointers are also counted here.
+    weak: Rc<Cell<usize>>,
+}
+
+impl CountLog {
+    pub fn events(&self) -> Vec<CountEvent> {
+        self.events.borrow().clone()
+    }
+
+    /// Just the operations, for when the counts don't matter.
+    pub fn ops(&self) -> Vec<CountOp> {
+        self.events.borrow().iter().map(|e| e.op).collect()
+    }
+
+    fn record(&self, op: CountOp, strong: usize) {
+        let weak = self.weak.get();
+        self.events
+            .borrow_mut()
+            .push(CountEvent { op, strong, weak });
+    }
+}
+
+pub struct TrackedRc<T> {
+    // Only `None` while being dropped.
+    rc: Option<Rc<T>>,
+    log: CountLog,
+}
+
+pub struct TrackedWeak<T> {
+    weak: Weak<T>,
+    log: CountLog,
+}
+
+impl<T> TrackedRc<T> {
+    /// A new pointer and the log its allocation reports to.
+    pub fn new_with_log(value: T) -> (TrackedRc<T>, CountLog) {
+        let log = CountLog::default();
+        let rc = TrackedRc {
+            rc: Some(Rc::new(value)),
+            log: log.clone(),
+        };
+        log.record(CountOp::New, 1);
+        (rc, log)
+    }
+
+    fn rc(&self) -> &Rc<T> {
+        self.rc.as_ref().unwrap()
+    }
+
+    pub fn strong_count(this: &TrackedRc<T>) -> usize {
+        Rc::strong_count(this.rc())
+    }
+
+    pub fn weak_count(this: &TrackedRc<T>) -> usize {
+        Rc::weak_count(this.rc())
+    }
+
+    pub fn downgrade(this: &TrackedRc<T>) -> TrackedWeak<T> {
+        let weak = Rc::downgrade(this.rc());
+        this.log.weak.set(this.log.weak.get() + 1);
+        this.log
+            .record(CountOp::Downgrade, Rc::strong_count(this.rc()));
+        TrackedWeak {
+            weak,
+            log: this.log.clone(),
+        }
+    }
+}
+
+impl<T> Clone for TrackedRc<T> {
+    fn clone(&self) -> TrackedRc<T> {
+        let rc = Rc::clone(self.rc());
+        self.log.record(CountOp::Clone, Rc::strong_count(&rc));
+        TrackedRc {
+            rc: Some(rc),
+            log: self.log.clone(),
+        }
+    }
+}
+
+impl<T> Deref for TrackedRc<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.rc()
+    }
+}
+
+impl<T> Drop for TrackedRc<T> {
+    fn drop(&mut self) {
+        let rc = self.rc.take().unwrap();
+        let strong = Rc::strong_count(&rc) - 1;
+        drop(rc);
+        self.log.record(CountOp::Drop, strong);
+        if strong == 0 {
+            self.log.record(CountOp::ValueDropped, 0);
+            if self.log.weak.get() == 0 {
+                self.log.record(CountOp::AllocationFreed, 0);
+            }
+        }
+    }
+}
+
+impl<T> TrackedWeak<T> {
+    pub fn upgrade(&self) -> Option<TrackedRc<T>> {
+        match self.weak.upgrade() {
+            Some(rc) => {
+                self.log.record(CountOp::Upgrade, Rc::strong_count(&rc));
+                Some(TrackedRc {
+                    rc: Some(rc),
+                    log: self.log.clone(),
+                })
+            }
+            None => {
+                self.log.record(CountOp::UpgradeFailed, 0);
+                None
+            }
+        }
+    }
+}
+
+impl<T> Drop for TrackedWeak<T> {
+    fn drop(&mut self) {
+        let strong = self.weak.strong_count();
+        self.log.weak.set(self.log.weak.get() - 1);
+        self.log.record(CountOp::DropWeak, strong);
+        if strong == 0 && self.log.weak.get() == 0 {
+            self.log.record(CountOp::AllocationFreed, 0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use CountOp::*;
+
+    fn event(op: CountOp, strong: usize, weak: usize) -> CountEvent {
+        CountEvent { op, strong, weak }
+    }
+
+    #[test]
+    fn rc_example_choreography() {
+        let (rc, log) = TrackedRc::new_with_log(5);
+        let strong_ptr1 = TrackedRc::clone(&rc);
+        let weak_ptr = TrackedRc::downgrade(&rc);
+        let strong_ptr2 = weak_ptr.upgrade();
+        assert_eq!(TrackedRc::strong_count(&rc), 3);
+        assert_eq!(TrackedRc::weak_count(&rc), 1);
+        drop(rc);
+        drop(strong_ptr1);
+        drop(strong_ptr2);
+        // the value is gone, but the allocation is kept for the weak pointer
+        assert!(weak_ptr.upgrade().is_none());
+        drop(weak_ptr);
+        assert_eq!(
+            log.events(),
+            [
+                event(New, 1, 0),
+                event(Clone, 2, 0),
+                event(Downgrade, 2, 1),
+                event(Upgrade, 3, 1),
+                event(Drop, 2, 1),
+                event(Drop, 1, 1),
+                event(Drop, 0, 1),
+                event(ValueDropped, 0, 1),
+                event(UpgradeFailed, 0, 1),
+                event(DropWeak, 0, 0),
+                event(AllocationFreed, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn last_strong_without_weaks_frees_everything() {
+        let (rc, log) = TrackedRc::new_with_log(String::from("x"));
+        let weak = TrackedRc::downgrade(&rc);
+        drop(weak);
+        assert_eq!(rc.len(), 1);
+        drop(rc);
+        assert_eq!(
+            log.ops(),
+            [
+                New,
+                Downgrade,
+                DropWeak,
+                Drop,
+                ValueDropped,
+                AllocationFreed
+            ]
+        );
+    }
+
+    #[test]
+    fn weak_drops_while_value_alive() {
+        let (rc, log) = TrackedRc::new_with_log(1);
+        let a = TrackedRc::downgrade(&rc);
+        let b = TrackedRc::downgrade(&rc);
+        drop(a);
+        drop(rc);
+        drop(b);
+        assert_eq!(
+            log.events()[3..],
+            [
+                event(DropWeak, 1, 1),
+                event(Drop, 0, 1),
+                event(ValueDropped, 0, 1),
+                event(DropWeak, 0, 0),
+                event(AllocationFreed, 0, 0),
+            ]
+        );
+    }
+}
@@ -1,52 +1,207 @@
+//! A tree whose nodes own their children and point back at their parent
+//! through a `Weak`, so a parent and child never keep each other alive.
 
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 use std::rc::{Rc, Weak};
 
 #[derive(Debug)]
-struct Node {
-    value: i32,
-    parent: RefCell<Weak<Node>>,
-    children: RefCell<Vec<Rc<Node>>>,
+pub struct Node<T> {
+    pub value: T,
+    children: RefCell<Vec<Rc<Node<T>>>>,
+    parent: RefCell<Weak<Node<T>>>,
 }
-fn main() {
-    let leaf = Rc::new(Node {
-        value: 3,
-        parent: RefCell::new(Weak::new()),
-        children: RefCell::new(vec![]),
-    });
-
-    println!(
-        "leaf strong = {}, weak = {}",
-        Rc::strong_count(&leaf),
-        Rc::weak_count(&leaf),
-    );
-
-    {
-        let branch = Rc::new(Node {
-            value: 5,
-            parent: RefCell::new(Weak::new()),
-            children: RefCell::new(vec![Rc::clone(&leaf)]),
-        });
 
-        *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+impl<T> Node<T> {
+    /// A node with no parent and no children.
+    pub fn new(value: T) -> Rc<Node<T>> {
+        Rc::new(Node {
+            value,
+            children: RefCell::new(vec![]),
+            parent: RefCell::new(Weak::new()),
+        })
+    }
 
-        println!(
-            "branch strong = {}, weak = {}",
-            Rc::strong_count(&branch),
-            Rc::weak_count(&branch),
+    /// Appends `child` to `parent`'s children and points it back at
+    /// `parent`. A child that already has a parent is removed from the old
+    /// parent's children first.
+    ///
+    /// # Panics
+    ///
+    /// If `child` is `parent` or one of its ancestors, since the tree would
+    /// then become a cycle.
+    pub fn add_child(parent: &Rc<Node<T>>, child: Rc<Node<T>>) {
+        assert!(
+            !Rc::ptr_eq(parent, &child) && !parent.ancestors().any(|a| Rc::ptr_eq(&a, &child)),
+            "a node cannot become its own descendant"
         );
+        if let Some(old) = child.parent() {
+            old.children.borrow_mut().retain(|c| !Rc::ptr_eq(c, &child));
+        }
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
+        parent.children.borrow_mut().push(child);
+    }
+
+    /// The parent, if it has one that is still alive.
+    pub fn parent(&self) -> Option<Rc<Node<T>>> {
+        self.parent.borrow().upgrade()
+    }
+
+    pub fn children(&self) -> Ref<'_, [Rc<Node<T>>]> {
+        Ref::map(self.children.borrow(), |c| c.as_slice())
+    }
+
+    /// The number of ancestors; 0 for a root.
+    pub fn depth(&self) -> usize {
+        self.ancestors().count()
+    }
+
+    /// The topmost ancestor, or this node itself if it has no parent.
+    pub fn root(self: &Rc<Self>) -> Rc<Node<T>> {
+        self.ancestors().last().unwrap_or_else(|| Rc::clone(self))
+    }
+
+    /// The parent, then the grandparent, and so on up to the root.
+    pub fn ancestors(&self) -> Ancestors<T> {
+        Ancestors {
+            next: self.parent(),
+        }
+    }
+
+    /// This node followed by all of its descendants, each node before its
+    /// children and children in insertion order.
+    pub fn descendants_preorder(self: &Rc<Self>) -> Descendants<T> {
+        Descendants {
+            stack: vec![Rc::clone(self)],
+        }
+    }
+}
+
+/// Iterator returned by `Node::ancestors`.
+pub struct Ancestors<T> {
+    next: Option<Rc<Node<T>>>,
+}
+
+impl<T> Iterator for Ancestors<T> {
+    type Item = Rc<Node<T>>;
+
+    fn next(&mut self) -> Option<Rc<Node<T>>> {
+        let node = self.next.take()?;
+        self.next = node.parent();
+        Some(node)
+    }
+}
+
+/// Iterator returned by `Node::descendants_preorder`.
+pub struct Descendants<T> {
+    stack: Vec<Rc<Node<T>>>,
+}
+
+impl<T> Iterator for Descendants<T> {
+    type Item = Rc<Node<T>>;
+
+    fn next(&mut self) -> Option<Rc<Node<T>>> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().iter().rev().cloned());
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn values(nodes: impl Iterator<Item = Rc<Node<i32>>>) -> Vec<i32> {
+        nodes.map(|n| n.value).collect()
+    }
+
+    #[test]
+    fn parent_link_upgrades() {
+        let branch = Node::new(5);
+        let leaf = Node::new(3);
+        assert!(leaf.parent().is_none());
+        Node::add_child(&branch, Rc::clone(&leaf));
+        assert!(Rc::ptr_eq(&leaf.parent().unwrap(), &branch));
+        assert_eq!(Rc::strong_count(&branch), 1);
+        assert_eq!(Rc::weak_count(&branch), 1);
+        assert_eq!(Rc::strong_count(&leaf), 2);
+        drop(branch);
+        assert!(leaf.parent().is_none());
+        assert_eq!(Rc::strong_count(&leaf), 1);
+    }
+
+    #[test]
+    fn ancestors_depth_and_root() {
+        let root = Node::new(1);
+        let parent = Node::new(2);
+        let grandchild = Node::new(3);
+        Node::add_child(&root, Rc::clone(&parent));
+        Node::add_child(&parent, Rc::clone(&grandchild));
+        assert_eq!(values(grandchild.ancestors()), [2, 1]);
+        assert_eq!(grandchild.depth(), 2);
+        assert_eq!(root.depth(), 0);
+        assert!(Rc::ptr_eq(&grandchild.root(), &root));
+        assert!(Rc::ptr_eq(&root.root(), &root));
+    }
 
-        println!(
-            "leaf strong = {}, weak = {}",
-            Rc::strong_count(&leaf),
-            Rc::weak_count(&leaf),
+    #[test]
+    fn preorder() {
+        let nodes: Vec<_> = (0..7).map(Node::new).collect();
+        for (parent, child) in [(0, 1), (1, 2), (1, 3), (0, 4), (4, 5), (0, 6)] {
+            Node::add_child(&nodes[parent], Rc::clone(&nodes[child]));
+        }
+        assert_eq!(
+            values(nodes[0].descendants_preorder()),
+            [0, 1, 2, 3, 4, 5, 6]
         );
+        assert_eq!(values(nodes[4].descendants_preorder()), [4, 5]);
     }
 
-    println!("leaf parent = {:?}", leaf.parent.borrow().upgrade());
-    println!(
-        "leaf strong = {}, weak = {}",
-        Rc::strong_count(&leaf),
-        Rc::weak_count(&leaf),
-    );
-}
\ No newline at end of file
+    #[test]
+    fn reparenting_updates_old_parent() {
+        let a = Node::new(1);
+        let b = Node::new(2);
+        let child = Node::new(3);
+        Node::add_child(&a, Rc::clone(&child));
+        Node::add_child(&b, Rc::clone(&child));
+        assert!(a.children().is_empty());
+        assert_eq!(b.children().len(), 1);
+        assert!(Rc::ptr_eq(&child.parent().unwrap(), &b));
+        assert_eq!(Rc::strong_count(&child), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "own descendant")]
+    fn adding_an_ancestor_panics() {
+        let root = Node::new(1);
+        let child = Node::new(2);
+        Node::add_child(&root, Rc::clone(&child));
+        Node::add_child(&child, root);
+    }
+
+    #[test]
+    fn dropping_the_root_frees_the_tree() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Drop for Counted<'_> {
+            fn drop(&mut self) {
+                self.0.set(self.0.get() + 1);
+            }
+        }
+
+        let drops = Cell::new(0);
+        let root = Node::new(Counted(&drops));
+        let mut parent = Rc::clone(&root);
+        for i in 0..10 {
+            let child = Node::new(Counted(&drops));
+            Node::add_child(&parent, Rc::clone(&child));
+            if i % 3 == 0 {
+                parent = child;
+            }
+        }
+        drop(parent);
+        assert_eq!(drops.get(), 0);
+        drop(root);
+        assert_eq!(drops.get(), 11);
+    }
+}
@@ -0,0 +1,117 @@
+//! A cache that holds its values weakly, so an entry lives only as long as
+//! something outside the cache still holds an `Rc` to it.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::rc::{Rc, Weak};
+
+pub struct WeakCache<K, V> {
+    entries: HashMap<K, Weak<V>>,
+}
+
+impl<K: Hash + Eq, V> Default for WeakCache<K, V> {
+    fn default() -> Self {
+        WeakCache::new()
+    }
+}
+
+impl<K: Hash + Eq, V> WeakCache<K, V> {
+    pub fn new() -> WeakCache<K, V> {
+        WeakCache {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Caches `value` under `key`, replacing any earlier entry. The cache
+    /// doesn't keep `value` alive, so it is handed back for the caller to
+    /// hold on to.
+    pub fn insert(&mut self, key: K, value: Rc<V>) -> Rc<V> {
+        self.entries.insert(key, Rc::downgrade(&value));
+        value
+    }
+
+    /// The cached value, if it is still alive somewhere else.
+    pub fn get(&self, key: &K) -> Option<Rc<V>> {
+        self.entries.get(key)?.upgrade()
+    }
+
+    /// Removes the entries whose values have been dropped, returning how
+    /// many there were.
+    pub fn prune(&mut self) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|_, weak| weak.strong_count() > 0);
+        before - self.entries.len()
+    }
+
+    /// The number of entries whose values are still alive.
+    pub fn live_len(&self) -> usize {
+        self.entries
+            .values()
+            .filter(|weak| weak.strong_count() > 0)
+            .count()
+    }
+
+    /// The number of entries, including dead ones not yet pruned.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_die_with_last_rc() {
+        let mut cache = WeakCache::new();
+        let apple = cache.insert("a", Rc::new(String::from("apple")));
+        let _pear = cache.insert("p", Rc::new(String::from("pear")));
+        assert_eq!(cache.get(&"a").as_deref(), Some(&apple.to_string()));
+        // the cache itself holds no strong count
+        assert_eq!(Rc::strong_count(&apple), 1);
+        assert_eq!(cache.live_len(), 2);
+
+        drop(apple);
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(cache.live_len(), 1);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.prune(), 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.prune(), 0);
+        assert_eq!(*cache.get(&"p").unwrap(), "pear");
+    }
+
+    #[test]
+    fn reinsert_after_death() {
+        let mut cache = WeakCache::new();
+        let first = Rc::new(1);
+        cache.insert(7, Rc::clone(&first));
+        drop(first);
+        assert!(cache.get(&7).is_none());
+
+        let _second = cache.insert(7, Rc::new(2));
+        assert_eq!(cache.get(&7).as_deref(), Some(&2));
+        assert_eq!(cache.prune(), 0);
+        assert_eq!(cache.live_len(), 1);
+    }
+
+    #[test]
+    fn get_keeps_value_alive() {
+        let mut cache = WeakCache::new();
+        let value = cache.insert("v", Rc::new(vec![1]));
+        let fetched = cache.get(&"v").unwrap();
+        drop(value);
+        assert_eq!(cache.live_len(), 1);
+        drop(fetched);
+        assert_eq!(cache.live_len(), 0);
+        assert!(!cache.is_empty());
+
+        // dropping the returned Rc leaves nothing to keep the value alive
+        cache.insert("gone", Rc::new(vec![2]));
+        assert_eq!(cache.get(&"gone"), None);
+    }
+}
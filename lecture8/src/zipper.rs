@@ -0,0 +1,185 @@
+//! A zipper over `List`: a cursor that splits the list at a focused
+//! element so it can be read and edited in O(1), then zipped back up.
+
+use std::mem;
+
+use crate::list::List;
+
+/// The elements before the focus are kept in `left` nearest-first, so
+/// stepping either way only moves one element between the two lists.
+/// `focus` is `None` only when the zipper holds no elements at all.
+pub struct ListZipper<T> {
+    left: List<T>,
+    focus: Option<T>,
+    right: List<T>,
+}
+
+impl<T: Clone> ListZipper<T> {
+    /// A zipper focused on the first element of `list`. Nodes that other
+    /// lists still share are cloned as the zipper passes over them, so
+    /// those lists are never changed.
+    pub fn from_list(mut list: List<T>) -> ListZipper<T> {
+        ListZipper {
+            left: List::new(),
+            focus: list.pop_front(),
+            right: list,
+        }
+    }
+
+    pub fn focus(&self) -> Option<&T> {
+        self.focus.as_ref()
+    }
+
+    /// Moves the focus one element to the right, returning `false` if it is
+    /// already on the last element.
+    pub fn move_right(&mut self) -> bool {
+        match self.right.pop_front() {
+            Some(next) => {
+                let prev = self.focus.replace(next).unwrap();
+                self.left = mem::take(&mut self.left).push_front(prev);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the focus one element to the left, returning `false` if it is
+    /// already on the first element.
+    pub fn move_left(&mut self) -> bool {
+        match self.left.pop_front() {
+            Some(prev) => {
+                let next = self.focus.replace(prev).unwrap();
+                self.right = mem::take(&mut self.right).push_front(next);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the focused element, returning the old one. On an empty
+    /// zipper `value` becomes the only element.
+    pub fn set(&mut self, value: T) -> Option<T> {
+        self.focus.replace(value)
+    }
+
+    /// Inserts `value` just after the focus, which stays where it is.
+    pub fn insert_after(&mut self, value: T) {
+        if self.focus.is_none() {
+            self.focus = Some(value);
+        } else {
+            self.right = mem::take(&mut self.right).push_front(value);
+        }
+    }
+
+    /// Removes the focused element. The focus moves to the next element, or
+    /// to the previous one if the last element was removed.
+    pub fn delete(&mut self) -> Option<T> {
+        let removed = self.focus.take()?;
+        self.focus = self.right.pop_front().or_else(|| self.left.pop_front());
+        Some(removed)
+    }
+
+    /// Zips the list back up, in O(n) for the elements left of the focus.
+    pub fn into_list(mut self) -> List<T> {
+        let mut list = mem::take(&mut self.right);
+        if let Some(focus) = self.focus.take() {
+            list = list.push_front(focus);
+        }
+        while let Some(value) = self.left.pop_front() {
+            list = list.push_front(value);
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zipper(values: &[i32]) -> ListZipper<i32> {
+        ListZipper::from_list(List::from(values.to_vec()))
+    }
+
+    #[test]
+    fn walks_both_ways() {
+        let mut z = zipper(&[1, 2, 3]);
+        assert_eq!(z.focus(), Some(&1));
+        assert!(!z.move_left());
+        assert!(z.move_right());
+        assert!(z.move_right());
+        assert_eq!(z.focus(), Some(&3));
+        assert!(!z.move_right());
+        assert!(z.move_left());
+        assert_eq!(z.focus(), Some(&2));
+        assert_eq!(z.into_list().to_vec(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn edits_in_the_middle() {
+        let mut z = zipper(&[1, 2, 3, 4, 5]);
+        z.move_right();
+        z.move_right();
+        assert_eq!(z.set(30), Some(3));
+        z.insert_after(35);
+        assert_eq!(z.focus(), Some(&30));
+        z.move_right();
+        z.move_right();
+        assert_eq!(z.delete(), Some(4));
+        assert_eq!(z.focus(), Some(&5));
+        assert_eq!(z.into_list().to_vec(), [1, 2, 30, 35, 5]);
+    }
+
+    #[test]
+    fn edits_at_both_ends() {
+        let mut z = zipper(&[1, 2, 3]);
+        z.set(10);
+        z.insert_after(15);
+        while z.move_right() {}
+        z.set(30);
+        z.insert_after(40);
+        assert_eq!(z.into_list().to_vec(), [10, 15, 2, 30, 40]);
+
+        let mut z = zipper(&[1, 2, 3]);
+        assert_eq!(z.delete(), Some(1));
+        assert_eq!(z.focus(), Some(&2));
+        assert_eq!(z.into_list().to_vec(), [2, 3]);
+    }
+
+    #[test]
+    fn deleting_the_last_element_moves_left() {
+        let mut z = zipper(&[1, 2, 3]);
+        while z.move_right() {}
+        assert_eq!(z.delete(), Some(3));
+        assert_eq!(z.focus(), Some(&2));
+        assert_eq!(z.into_list().to_vec(), [1, 2]);
+
+        let mut z = zipper(&[7]);
+        assert_eq!(z.delete(), Some(7));
+        assert_eq!(z.focus(), None);
+        assert_eq!(z.delete(), None);
+        assert!(z.into_list().is_empty());
+    }
+
+    #[test]
+    fn empty_list() {
+        let mut z = ListZipper::from_list(List::<i32>::new());
+        assert_eq!(z.focus(), None);
+        assert!(!z.move_right());
+        assert!(!z.move_left());
+        z.insert_after(1);
+        z.insert_after(2);
+        assert_eq!(z.into_list().to_vec(), [1, 2]);
+    }
+
+    #[test]
+    fn shared_lists_are_left_alone() {
+        let original = List::from(vec![1, 2, 3, 4]);
+        let mut z = ListZipper::from_list(original.clone());
+        z.move_right();
+        z.set(20);
+        z.move_right();
+        z.delete();
+        assert_eq!(z.into_list().to_vec(), [1, 20, 4]);
+        assert_eq!(original.to_vec(), [1, 2, 3, 4]);
+    }
+}